@@ -0,0 +1,81 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Index};
+
+pub fn r#impl(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    match derive(input) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "Lenses can only be derived for structs",
+        ));
+    };
+
+    if let Fields::Unit = data.fields {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "Lenses cannot be derived for unit structs",
+        ));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let params = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    let lenses = data.fields.iter().enumerate().map(|(i, field)| {
+        let vis = &field.vis;
+        let ty = &field.ty;
+
+        let (member, lens) = match &field.ident {
+            Some(ident) => (quote!(#ident), format_ident!("{ident}_lens")),
+            None => {
+                let index = Index::from(i);
+                (quote!(#index), format_ident!("_{i}_lens"))
+            }
+        };
+
+        let doc = format!("A lens focusing on the `{member}` field of [`{name}`].");
+
+        quote! {
+            #[doc = #doc]
+            #vis fn #lens() -> glasgae::base::data::optics::SimpleLens<Self, #ty> {
+                glasgae::base::data::optics::Lens::new(
+                    |s: Self| s.#member,
+                    |mut s: Self, a: #ty| {
+                        s.#member = a;
+                        s
+                    },
+                )
+            }
+        }
+    });
+
+    let where_clause = match where_clause {
+        Some(where_clause) => {
+            let predicates = where_clause.predicates.iter();
+            quote!(where #(#predicates,)* #(#params: glasgae::prelude::Term,)* Self: glasgae::prelude::Term)
+        }
+        None => quote!(where #(#params: glasgae::prelude::Term,)* Self: glasgae::prelude::Term),
+    };
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#lenses)*
+        }
+    })
+}
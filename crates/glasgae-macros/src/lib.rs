@@ -2,7 +2,10 @@ use proc_macro::TokenStream;
 mod punct;
 
 mod _do;
+mod lenses;
 mod op;
+mod plated;
+mod recursive;
 
 /// Basic implementation of Haskell `do` sugar.
 #[proc_macro]
@@ -14,3 +17,33 @@ pub fn _do(input: TokenStream) -> TokenStream {
 pub fn op(input: TokenStream) -> TokenStream {
     op::r#impl(input)
 }
+
+/// Derive a base functor for a recursive type,
+/// alongside its `Base`, `Recursive` and `Corecursive` instances.
+///
+/// For a type `T`, the base functor `TF<.., R>` replicates its constructors,
+/// with each recursive occurrence of `T` (including within `Box`, `Vec` and `Option`)
+/// replaced by the new type parameter `R`.
+#[proc_macro_derive(Recursive)]
+pub fn recursive(input: TokenStream) -> TokenStream {
+    recursive::r#impl(input)
+}
+
+/// Derive a simple lens for each field of a struct.
+///
+/// For each named field `x` of type `T`, this generates an associated function
+/// `x_lens() -> SimpleLens<Self, T>`; fields of tuple structs are named by index,
+/// as in `_0_lens()`.
+#[proc_macro_derive(Lenses)]
+pub fn lenses(input: TokenStream) -> TokenStream {
+    lenses::r#impl(input)
+}
+
+/// Derive a `Plated` instance for a recursive type.
+///
+/// The immediate children of a value are its fields of the same type,
+/// including those within `Box`, `Vec` and `Option`, in declaration order.
+#[proc_macro_derive(Plated)]
+pub fn plated(input: TokenStream) -> TokenStream {
+    plated::r#impl(input)
+}
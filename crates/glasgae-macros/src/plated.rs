@@ -0,0 +1,138 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Error};
+
+use crate::recursive::{Constructor, Shape};
+
+impl Shape {
+    /// Whether a field holds exactly one recursive position and nothing else.
+    fn is_rec(&self) -> bool {
+        match self {
+            Shape::Rec => true,
+            Shape::Boxed(s) => s.is_rec(),
+            _ => false,
+        }
+    }
+
+    /// Move each recursive position of a field onto `__children`,
+    /// leaving behind a skeleton with `()` in their place.
+    fn split(&self, var: TokenStream2) -> TokenStream2 {
+        match self {
+            Shape::Plain => var,
+            Shape::Rec => quote!(__children.push(#var)),
+            Shape::Boxed(s) => s.split(quote!((*#var))),
+            Shape::Vec(s) => {
+                let inner = s.split(quote!(x));
+                quote!(#var.into_iter().map(|x| #inner).collect::<Vec<_>>())
+            }
+            Shape::Option(s) => {
+                let inner = s.split(quote!(x));
+                quote!(#var.map(|x| #inner))
+            }
+        }
+    }
+
+    /// Fill each `()` of a field's skeleton with the next of `__children`.
+    fn replace(&self, var: TokenStream2) -> TokenStream2 {
+        match self {
+            Shape::Plain => var,
+            Shape::Rec => quote!(__children.next().expect("plate: too few children")),
+            Shape::Boxed(s) => {
+                let inner = s.replace(var);
+                quote!(Box::new(#inner))
+            }
+            Shape::Vec(s) => {
+                let inner = s.replace(quote!(x));
+                quote!(#var.into_iter().map(|x| #inner).collect::<Vec<_>>())
+            }
+            Shape::Option(s) => {
+                let inner = s.replace(quote!(x));
+                quote!(#var.map(|x| #inner))
+            }
+        }
+    }
+}
+
+pub fn r#impl(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    match derive(input) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+
+    if input.generics.lifetimes().next().is_some() || input.generics.const_params().next().is_some()
+    {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "Plated can only be derived for types with type parameters",
+        ));
+    }
+
+    let params = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    let constructors = Constructor::all(&input, "Plated")?;
+
+    // Each arm moves the children out of its fields, and rebuilds from
+    // a closure that captures only what remains.
+    let arms = constructors.iter().map(|c| {
+        let bindings = c.bindings();
+        let pattern = c.pattern(name, &bindings);
+        let shapes = c.shapes(name);
+
+        let splits = shapes
+            .iter()
+            .zip(bindings.iter())
+            .map(|(shape, binding)| {
+                let split = shape.split(quote!(#binding));
+                if shape.is_rec() {
+                    quote!(#split;)
+                } else {
+                    quote!(let #binding = #split;)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let exprs = shapes
+            .iter()
+            .zip(bindings.iter())
+            .map(|(shape, binding)| shape.replace(quote!(#binding)))
+            .collect::<Vec<_>>();
+        let construct = c.construct(name, &exprs);
+
+        quote!(#pattern => {
+            #(#splits)*
+
+            let rebuild = move |__children: Vec<Self>| {
+                let mut __children = __children.into_iter();
+                #construct
+            };
+
+            (__children, Box::new(rebuild))
+        })
+    });
+
+    Ok(quote! {
+        impl<#(#params),*> glasgae::base::data::plated::Plated for #name<#(#params),*>
+        where
+            #(#params: glasgae::prelude::Term,)*
+        {
+            #[allow(unused_variables, unused_mut, clippy::redundant_closure)]
+            fn plate(self) -> glasgae::base::data::optics::Parts<Self, Self, Self> {
+                let mut __children = vec![];
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,409 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, GenericArgument, Ident, PathArguments,
+    Type,
+};
+
+/// The position of the recursive type within a field.
+pub(crate) enum Shape {
+    /// The field does not mention the recursive type.
+    Plain,
+    /// The field is the recursive type.
+    Rec,
+    /// The field is a [`Box`] of the given shape.
+    Boxed(Box<Shape>),
+    /// The field is a [`Vec`] of the given shape.
+    Vec(Box<Shape>),
+    /// The field is an [`Option`] of the given shape.
+    Option(Box<Shape>),
+}
+
+impl Shape {
+    pub(crate) fn new(ty: &Type, name: &Ident) -> Self {
+        let Type::Path(path) = ty else {
+            return Shape::Plain;
+        };
+
+        let Some(last) = path.path.segments.last() else {
+            return Shape::Plain;
+        };
+
+        if path.qself.is_none() && (last.ident == *name || last.ident == "Self") {
+            return Shape::Rec;
+        }
+
+        let inner = match &last.arguments {
+            PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+                GenericArgument::Type(inner) => Shape::new(inner, name),
+                _ => return Shape::Plain,
+            },
+            _ => return Shape::Plain,
+        };
+
+        if let Shape::Plain = inner {
+            return Shape::Plain;
+        }
+
+        if last.ident == "Box" {
+            Shape::Boxed(Box::new(inner))
+        } else if last.ident == "Vec" {
+            Shape::Vec(Box::new(inner))
+        } else if last.ident == "Option" {
+            Shape::Option(Box::new(inner))
+        } else {
+            Shape::Plain
+        }
+    }
+
+    /// The type of this field in the base functor, with the recursive type replaced by `r`.
+    fn base_type(&self, ty: &Type, r: &Ident) -> TokenStream2 {
+        match self {
+            Shape::Plain => quote!(#ty),
+            Shape::Rec => quote!(#r),
+            Shape::Boxed(s) => s.base_type(inner_type(ty), r),
+            Shape::Vec(s) => {
+                let inner = s.base_type(inner_type(ty), r);
+                quote!(Vec<#inner>)
+            }
+            Shape::Option(s) => {
+                let inner = s.base_type(inner_type(ty), r);
+                quote!(Option<#inner>)
+            }
+        }
+    }
+
+    /// Map `f` over the recursive positions of a base functor field.
+    fn fmap(&self, var: TokenStream2) -> TokenStream2 {
+        match self {
+            Shape::Plain => var,
+            Shape::Rec => quote!(f.clone()(#var)),
+            Shape::Boxed(s) => s.fmap(var),
+            Shape::Vec(s) => {
+                let inner = s.fmap(quote!(x));
+                quote!(#var.into_iter().map(|x| #inner).collect::<Vec<_>>())
+            }
+            Shape::Option(s) => {
+                let inner = s.fmap(quote!(x));
+                quote!(#var.map(|x| #inner))
+            }
+        }
+    }
+
+    /// Convert a field of the recursive type into a field of its base functor.
+    fn project(&self, var: TokenStream2) -> TokenStream2 {
+        match self {
+            Shape::Plain | Shape::Rec => var,
+            Shape::Boxed(s) => s.project(quote!((*#var))),
+            Shape::Vec(s) => {
+                let inner = s.project(quote!(x));
+                quote!(#var.into_iter().map(|x| #inner).collect::<Vec<_>>())
+            }
+            Shape::Option(s) => {
+                let inner = s.project(quote!(x));
+                quote!(#var.map(|x| #inner))
+            }
+        }
+    }
+
+    /// Convert a field of the base functor into a field of the recursive type.
+    fn embed(&self, var: TokenStream2) -> TokenStream2 {
+        match self {
+            Shape::Plain | Shape::Rec => var,
+            Shape::Boxed(s) => {
+                let inner = s.embed(var);
+                quote!(Box::new(#inner))
+            }
+            Shape::Vec(s) => {
+                let inner = s.embed(quote!(x));
+                quote!(#var.into_iter().map(|x| #inner).collect::<Vec<_>>())
+            }
+            Shape::Option(s) => {
+                let inner = s.embed(quote!(x));
+                quote!(#var.map(|x| #inner))
+            }
+        }
+    }
+}
+
+fn inner_type(ty: &Type) -> &Type {
+    let Type::Path(path) = ty else { unreachable!() };
+
+    let PathArguments::AngleBracketed(args) = &path.path.segments.last().unwrap().arguments else {
+        unreachable!()
+    };
+
+    let GenericArgument::Type(inner) = &args.args[0] else {
+        unreachable!()
+    };
+
+    inner
+}
+
+/// A single constructor of the recursive type: a struct body, or an enum variant.
+pub(crate) struct Constructor {
+    /// The variant name, or `None` for a struct.
+    pub(crate) variant: Option<Ident>,
+    pub(crate) fields: Fields,
+}
+
+impl Constructor {
+    /// Collect the constructors of a struct or enum,
+    /// reporting unions as an error against the given derive.
+    pub(crate) fn all(input: &DeriveInput, derive: &str) -> syn::Result<Vec<Constructor>> {
+        match &input.data {
+            Data::Struct(s) => Ok(vec![Constructor {
+                variant: None,
+                fields: s.fields.clone(),
+            }]),
+            Data::Enum(e) => Ok(e
+                .variants
+                .iter()
+                .map(|variant| Constructor {
+                    variant: Some(variant.ident.clone()),
+                    fields: variant.fields.clone(),
+                })
+                .collect()),
+            Data::Union(_) => Err(Error::new_spanned(
+                &input.ident,
+                format!("{derive} cannot be derived for unions"),
+            )),
+        }
+    }
+
+    pub(crate) fn bindings(&self) -> Vec<Ident> {
+        (0..self.fields.len())
+            .map(|i| format_ident!("_{i}"))
+            .collect()
+    }
+
+    pub(crate) fn pattern(&self, ty: &Ident, bindings: &[Ident]) -> TokenStream2 {
+        let path = match &self.variant {
+            Some(variant) => quote!(#ty::#variant),
+            None => quote!(#ty),
+        };
+
+        match &self.fields {
+            Fields::Named(named) => {
+                let names = named.named.iter().map(|field| &field.ident);
+                quote!(#path { #(#names: #bindings),* })
+            }
+            Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+            Fields::Unit => quote!(#path),
+        }
+    }
+
+    pub(crate) fn shapes(&self, name: &Ident) -> Vec<Shape> {
+        self.fields
+            .iter()
+            .map(|field| Shape::new(&field.ty, name))
+            .collect()
+    }
+
+    /// Build the arm `from_pattern => to_pattern`, converting each field with `f`.
+    pub(crate) fn arm(
+        &self,
+        name: &Ident,
+        from: &Ident,
+        to: &Ident,
+        f: impl Fn(&Shape, TokenStream2) -> TokenStream2,
+    ) -> TokenStream2 {
+        let bindings = self.bindings();
+        let from_pat = self.pattern(from, &bindings);
+
+        let exprs = self
+            .shapes(name)
+            .iter()
+            .zip(bindings.iter())
+            .map(|(shape, binding)| f(shape, quote!(#binding)))
+            .collect::<Vec<_>>();
+
+        let to_expr = self.construct(to, &exprs);
+
+        quote!(#from_pat => #to_expr)
+    }
+
+    /// Build this constructor of `ty` from one expression per field.
+    pub(crate) fn construct(&self, ty: &Ident, exprs: &[TokenStream2]) -> TokenStream2 {
+        let path = match &self.variant {
+            Some(variant) => quote!(#ty::#variant),
+            None => quote!(#ty),
+        };
+
+        match &self.fields {
+            Fields::Named(named) => {
+                let names = named.named.iter().map(|field| &field.ident);
+                quote!(#path { #(#names: #exprs),* })
+            }
+            Fields::Unnamed(_) => quote!(#path(#(#exprs),*)),
+            Fields::Unit => quote!(#path),
+        }
+    }
+
+    /// The fields of this constructor within the base functor.
+    fn base_fields(&self, name: &Ident, r: &Ident) -> TokenStream2 {
+        let fields = self.fields.iter().map(|field| {
+            let vis = &field.vis;
+            let ty = Shape::new(&field.ty, name).base_type(&field.ty, r);
+            match &field.ident {
+                Some(ident) => quote!(#vis #ident: #ty),
+                None => quote!(#vis #ty),
+            }
+        });
+
+        match &self.fields {
+            Fields::Named(_) => quote!({ #(#fields),* }),
+            Fields::Unnamed(_) => quote!((#(#fields),*)),
+            Fields::Unit => quote!(),
+        }
+    }
+}
+
+pub fn r#impl(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    match derive(input) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let base = format_ident!("{name}F");
+    let r = Ident::new("_R", Span::call_site());
+    let r_ = Ident::new("_R_", Span::call_site());
+
+    if input.generics.lifetimes().next().is_some() || input.generics.const_params().next().is_some()
+    {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "Recursive can only be derived for types with type parameters",
+        ));
+    }
+
+    let params = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    let constructors = Constructor::all(&input, "Recursive")?;
+    let is_enum = matches!(input.data, Data::Enum(_));
+
+    let doc = format!("Base functor for [`{name}`], generated by `#[derive(Recursive)]`.");
+
+    let definition = if is_enum {
+        let variants = constructors.iter().map(|c| {
+            let variant = &c.variant;
+            let fields = c.base_fields(name, &r);
+            quote!(#variant #fields)
+        });
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Clone)]
+            #vis enum #base<#(#params,)* #r> {
+                #(#variants),*
+            }
+        }
+    } else {
+        let fields = constructors[0].base_fields(name, &r);
+        let semi = match &constructors[0].fields {
+            Fields::Named(_) => quote!(),
+            _ => quote!(;),
+        };
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Clone)]
+            #vis struct #base<#(#params,)* #r> #fields #semi
+        }
+    };
+
+    let fmap_arms = constructors
+        .iter()
+        .map(|c| c.arm(name, &base, &base, Shape::fmap));
+    let project_arms = constructors
+        .iter()
+        .map(|c| c.arm(name, name, &base, Shape::project));
+    let embed_arms = constructors
+        .iter()
+        .map(|c| c.arm(name, &base, name, Shape::embed));
+
+    let out = quote! {
+        #definition
+
+        impl<#(#params,)* #r> glasgae::prelude::Pointed for #base<#(#params,)* #r>
+        where
+            #(#params: glasgae::prelude::Term,)*
+            #r: glasgae::prelude::Term,
+        {
+            type Pointed = #r;
+        }
+
+        impl<#(#params,)* #r, #r_> glasgae::prelude::WithPointed<#r_> for #base<#(#params,)* #r>
+        where
+            #(#params: glasgae::prelude::Term,)*
+            #r: glasgae::prelude::Term,
+            #r_: glasgae::prelude::Term,
+        {
+            type WithPointed = #base<#(#params,)* #r_>;
+        }
+
+        impl<#(#params,)* #r, #r_> glasgae::prelude::Functor<#r_> for #base<#(#params,)* #r>
+        where
+            #(#params: glasgae::prelude::Term,)*
+            #r: glasgae::prelude::Term,
+            #r_: glasgae::prelude::Term,
+        {
+            type Mapped = #base<#(#params,)* #r_>;
+
+            #[allow(unused_variables, clippy::redundant_closure)]
+            fn fmap(
+                self,
+                f: impl glasgae::prelude::FunctionT<#r, #r_>,
+            ) -> #base<#(#params,)* #r_> {
+                let f = glasgae::prelude::FunctionT::to_function(&f);
+                match self {
+                    #(#fmap_arms,)*
+                }
+            }
+        }
+
+        impl<#(#params),*> glasgae::base::data::fix::Base for #name<#(#params),*>
+        where
+            #(#params: glasgae::prelude::Term,)*
+        {
+            type Base = #base<#(#params,)* ()>;
+        }
+
+        impl<#(#params),*> glasgae::base::data::fix::Recursive for #name<#(#params),*>
+        where
+            #(#params: glasgae::prelude::Term,)*
+        {
+            #[allow(clippy::redundant_closure)]
+            fn project(self) -> #base<#(#params,)* Self> {
+                match self {
+                    #(#project_arms,)*
+                }
+            }
+        }
+
+        impl<#(#params),*> glasgae::base::data::fix::Corecursive for #name<#(#params),*>
+        where
+            #(#params: glasgae::prelude::Term,)*
+        {
+            #[allow(clippy::redundant_closure)]
+            fn embed(layer: #base<#(#params,)* Self>) -> Self {
+                match layer {
+                    #(#embed_arms,)*
+                }
+            }
+        }
+    };
+
+    Ok(out)
+}
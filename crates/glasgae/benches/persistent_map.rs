@@ -0,0 +1,99 @@
+//! Compare std and persistent maps as `State` threaded through a computation.
+//!
+//! Each step reads the current map with `get` and writes back an updated copy,
+//! so the cost of cloning the state dominates for std maps.
+
+use std::{collections::HashMap, hash::Hash};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use glasgae::{
+    base::data::collection::map::{
+        hash_map,
+        p_map::{self, PMap},
+    },
+    prelude::*,
+    transformers::state::State,
+};
+
+const SIZES: [u64; 3] = [100, 1_000, 5_000];
+
+/// Insert `0..n` one at a time, reading the whole map back at each step.
+fn insert_steps<M>(n: u64, insert: fn(u64, u64, M) -> M, len: fn(&M) -> usize) -> State<M, usize>
+where
+    M: Term,
+{
+    (0..n).fold(State::return_m(0), move |acc, i| {
+        acc.then_m(State::get().chain_m(move |m: M| {
+            let size = len(&m);
+            State::put(insert(i, i, m)).then_m(State::return_m(size))
+        }))
+    })
+}
+
+fn hash_map_len<K, V>(m: &HashMap<K, V>) -> usize
+where
+    K: Eq + Hash,
+{
+    m.len()
+}
+
+fn p_map_len<K, V>(m: &PMap<K, V>) -> usize {
+    m.len()
+}
+
+fn state_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("state_insert");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("HashMap", n), &n, |b, n| {
+            b.iter(|| {
+                insert_steps(*n, hash_map::insert, hash_map_len)
+                    .run(HashMap::new())
+                    .1
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("PMap", n), &n, |b, n| {
+            b.iter(|| {
+                insert_steps(*n, p_map::insert, p_map_len)
+                    .run(PMap::new())
+                    .1
+            })
+        });
+    }
+    group.finish();
+}
+
+fn clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clone");
+    for n in SIZES {
+        let hash_map = (0..n).map(|i| (i, i)).collect::<HashMap<_, _>>();
+        group.bench_with_input(BenchmarkId::new("HashMap", n), &hash_map, |b, m| {
+            b.iter(|| black_box(m.clone()))
+        });
+
+        let p_map = (0..n).map(|i| (i, i)).collect::<PMap<_, _>>();
+        group.bench_with_input(BenchmarkId::new("PMap", n), &p_map, |b, m| {
+            b.iter(|| black_box(m.clone()))
+        });
+    }
+    group.finish();
+}
+
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup");
+    for n in SIZES {
+        let hash_map = (0..n).map(|i| (i, i)).collect::<HashMap<_, _>>();
+        group.bench_with_input(BenchmarkId::new("HashMap", n), &hash_map, |b, m| {
+            b.iter(|| (0..n).filter_map(|i| m.get(&i)).sum::<u64>())
+        });
+
+        let p_map = (0..n).map(|i| (i, i)).collect::<PMap<_, _>>();
+        group.bench_with_input(BenchmarkId::new("PMap", n), &p_map, |b, m| {
+            b.iter(|| (0..n).filter_map(|i| m.get(&i)).sum::<u64>())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, state_insert, clone, lookup);
+criterion_main!(benches);
@@ -0,0 +1,162 @@
+//! Command-line option parsing with the free applicative.
+//!
+//! A single description of the program's options is interpreted twice:
+//! once statically with `run_ap_` to print help text,
+//! and once with `run_ap` to parse the arguments it was invoked with.
+//!
+//! Try `cargo run --example options -- --help`,
+//! or `cargo run --example options -- --port=80 --name=glasgae`.
+
+use glasgae::{
+    base::{
+        control::applicative::free::{Ap, ApLayerT},
+        grl::io::{exit_failure, put_str, put_str_ln},
+    },
+    derive_pointed, derive_with_pointed,
+    prelude::*,
+};
+use std::str::FromStr;
+
+/// A single named option, producing a value of type `N`.
+#[derive(Clone)]
+struct Opt<N: Term> {
+    name: &'static str,
+    meta: &'static str,
+    help: &'static str,
+    default: Option<&'static str>,
+    parse: Function<String, Option<N>>,
+}
+
+derive_pointed!(Opt<(N)>);
+derive_with_pointed!(Opt<(N)>);
+
+impl<N: Term, M: Term> Functor<M> for Opt<N> {
+    type Mapped = Opt<M>;
+
+    fn fmap(self, f: impl FunctionT<N, M>) -> Opt<M> {
+        let f = f.to_function();
+        let parse = self.parse;
+        Opt {
+            name: self.name,
+            meta: self.meta,
+            help: self.help,
+            default: self.default,
+            parse: (move |s| parse(s).fmap(f)).boxed(),
+        }
+    }
+}
+
+type Parser<A> = Ap<Opt<()>, A>;
+
+fn option<A>(
+    name: &'static str,
+    meta: &'static str,
+    help: &'static str,
+    default: Option<&'static str>,
+) -> Parser<A>
+where
+    A: Term + FromStr,
+{
+    Ap::lift_ap(Opt {
+        name,
+        meta,
+        help,
+        default,
+        parse: (|s: String| s.parse().ok()).boxed(),
+    })
+}
+
+#[derive(Clone)]
+struct Config {
+    name: String,
+    host: String,
+    port: u16,
+    verbose: bool,
+}
+
+fn config() -> Parser<Config> {
+    option("name", "NAME", "Name to greet", None)
+        .fmap(|name: String| {
+            move |host: String| {
+                move |port: u16| {
+                    move |verbose: bool| Config {
+                        name,
+                        host,
+                        port,
+                        verbose,
+                    }
+                }
+            }
+        })
+        .app_a(option(
+            "host",
+            "HOST",
+            "Host to connect to",
+            Some("localhost"),
+        ))
+        .app_a(option("port", "PORT", "Port to connect on", Some("8080")))
+        .app_a(option(
+            "verbose",
+            "BOOL",
+            "Enable verbose output",
+            Some("false"),
+        ))
+}
+
+/// Render help text by statically inspecting every option of a parser.
+fn help<A: Term>(parser: Parser<A>) -> String {
+    let options = parser.run_ap_(|opt: ApLayerT<Opt<()>>| {
+        let default = opt
+            .default
+            .map(|d| format!(" (default: {d})"))
+            .unwrap_or_default();
+
+        let flag = format!("--{}={}", opt.name, opt.meta);
+        format!("  {flag:<20}{}{default}\n", opt.help)
+    });
+
+    format!("Usage: options [OPTION]...\n\nOptions:\n{options}")
+}
+
+/// Run a parser against a list of `--name=value` arguments.
+fn parse<A: Term>(parser: Parser<A>, args: Vec<String>) -> Either<String, A> {
+    parser.run_ap(move |opt: ApLayerT<Opt<()>>| {
+        let prefix = format!("--{}=", opt.name);
+
+        let value = args
+            .iter()
+            .find_map(|arg| arg.strip_prefix(&prefix).map(ToString::to_string))
+            .or(opt.default.map(ToString::to_string));
+
+        match value {
+            Some(value) => match (opt.parse)(value.clone()) {
+                Some(v) => Right(v),
+                None => Left(format!("Invalid value for --{}: {value}", opt.name)),
+            },
+            None => Left(format!("Missing required option --{}", opt.name)),
+        }
+    })
+}
+
+fn main() -> IO<()> {
+    IO::new(|| std::env::args().skip(1).collect::<Vec<_>>()).chain_m(|args| {
+        if args.iter().any(|arg| arg == "--help") {
+            return put_str(help(config()));
+        }
+
+        match parse(config(), args) {
+            Right(Config {
+                name,
+                host,
+                port,
+                verbose,
+            }) => put_str_ln(format!(
+                "Hello, {name}! Connecting to {host}:{port}{}",
+                if verbose { " (verbose)" } else { "" }
+            )),
+            Left(e) => put_str_ln(e)
+                .then_m(put_str(help(config())))
+                .then_m(exit_failure()),
+        }
+    })
+}
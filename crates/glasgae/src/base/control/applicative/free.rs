@@ -0,0 +1,359 @@
+//! The free applicative functor over a [`Functor`].
+//!
+//! Given any functor `F`, [`Ap<F, A>`] is the applicative of programs
+//! built from the instructions that `F` describes.
+//!
+//! Unlike [`Free`](crate::base::control::monad::free::Free),
+//! later instructions cannot depend on the results of earlier ones,
+//! so the full structure of a program can be inspected before it is run;
+//! for example, to print help text for a command-line parser with [`Ap::run_ap_`],
+//! and then run it with [`Ap::run_ap`].
+//!
+//! # Representation
+//!
+//! Haskell's `Ap` quantifies existentially over the result type of each instruction.
+//! Rust has no existential types, so each instruction is stored as an [`ApLayerT<F>`]
+//! (that is, `F` mapped over an [`Erased`] result),
+//! alongside a function which assembles the final result from the erased results of every instruction.
+//!
+//! # Example
+//!
+//! ```
+//! # use glasgae::{
+//! #     base::control::applicative::free::{Ap, ApLayerT},
+//! #     base::data::tuple::pair::Pair,
+//! #     derive_pointed, derive_with_pointed, prelude::*,
+//! # };
+//! #[derive(Clone)]
+//! struct Ask<N: Term>(&'static str, Function<usize, N>);
+//!
+//! derive_pointed!(Ask<(N)>);
+//! derive_with_pointed!(Ask<(N)>);
+//!
+//! impl<N: Term, M: Term> Functor<M> for Ask<N> {
+//!     type Mapped = Ask<M>;
+//!
+//!     fn fmap(self, f: impl FunctionT<N, M>) -> Ask<M> {
+//!         Ask(self.0, self.1.fmap(f))
+//!     }
+//! }
+//!
+//! fn ask(name: &'static str) -> Ap<Ask<()>, usize> {
+//!     Ap::lift_ap(Ask(name, identity.boxed()))
+//! }
+//!
+//! let program = Pair::pair.lift_a2()(ask("width"), ask("height"));
+//!
+//! // Inspect the program without running it
+//! let names = program
+//!     .clone()
+//!     .run_ap_(|Ask(name, _): ApLayerT<Ask<()>>| vec![name]);
+//! assert_eq!(names, vec!["width", "height"]);
+//!
+//! // Run the program
+//! let size: Option<(usize, usize)> = program.run_ap(|Ask(name, k): ApLayerT<Ask<()>>| {
+//!     Some(k(name.len()))
+//! });
+//! assert_eq!(size, Some((5, 6)));
+//! ```
+
+use crate::prelude::*;
+
+/// The `F`-shaped layer of a single [`Ap`] instruction.
+pub type ApLayerT<F> = WithPointedT<F, Erased>;
+
+/// The free applicative functor over the functor `F`.
+#[derive(Clone)]
+pub struct Ap<F, A>
+where
+    F: WithPointed<Erased>,
+    A: Term,
+{
+    effects: Vec<ApLayerT<F>>,
+    run: Function<Vec<Erased>, A>,
+}
+
+impl<F, A> Ap<F, A>
+where
+    F: WithPointed<Erased>,
+    A: Term,
+{
+    /// Lift a value of `F` into the free applicative.
+    pub fn lift_ap<FA>(fa: FA) -> Self
+    where
+        FA: Functor<Erased, Pointed = A, Mapped = ApLayerT<F>>,
+    {
+        Ap {
+            effects: vec![fa.fmap(|a: A| -> Erased { a.boxed() })],
+            run: (|mut xs: Vec<Erased>| xs.remove(0).downcast()).boxed(),
+        }
+    }
+
+    /// Given a natural transformation from `F` to `G`,
+    /// this gives a canonical natural transformation from `Ap<F, _>` to `G`.
+    ///
+    /// As Rust lacks rank-2 types, the transformation is specialized
+    /// to the layer type it will be applied to.
+    ///
+    /// ```text
+    /// run_ap(nt)(pure_a(a)) == pure_a(a)
+    /// run_ap(nt)(lift_ap(fa)) == nt(fa)
+    /// ```
+    pub fn run_ap<GE, GV, GF, GA>(self, nt: impl FunctionT<ApLayerT<F>, GE>) -> GA
+    where
+        GE: Pointed<Pointed = Erased> + WithPointed<Vec<Erased>, WithPointed = GV>,
+        GV: PureA<Pointed = Vec<Erased>>
+            + Functor<Function<Erased, Vec<Erased>>, Mapped = GF>
+            + Functor<A, Mapped = GA>,
+        GF: Applicative<Erased, Vec<Erased>, WithA = GE, WithB = GV>,
+        GA: Pointed<Pointed = A>,
+    {
+        let nt = nt.to_function();
+        self.effects
+            .foldl(
+                move |acc: GV, next| {
+                    acc.fmap(|xs: Vec<Erased>| -> Function<Erased, Vec<Erased>> {
+                        (move |x| {
+                            let mut xs = xs;
+                            xs.push(x);
+                            xs
+                        })
+                        .boxed()
+                    })
+                    .app_a(nt.clone()(next))
+                },
+                PureA::pure_a(vec![]),
+            )
+            .fmap(self.run)
+    }
+
+    /// Perform a monoidal analysis over the instructions of a free applicative program.
+    ///
+    /// ```text
+    /// run_ap_(f)(pure_a(a)) == mempty()
+    /// run_ap_(f)(lift_ap(fa)) == f(fa)
+    /// ```
+    pub fn run_ap_<M>(self, f: impl FunctionT<ApLayerT<F>, M>) -> M
+    where
+        M: Monoid,
+    {
+        M::mconcat(self.effects.fmap(f))
+    }
+
+    /// Tear down a free applicative whose functor is itself the target applicative.
+    ///
+    /// ```text
+    /// retract_ap == run_ap(identity)
+    /// ```
+    pub fn retract_ap<GV, GF, GA>(self) -> GA
+    where
+        ApLayerT<F>: Pointed<Pointed = Erased> + WithPointed<Vec<Erased>, WithPointed = GV>,
+        GV: PureA<Pointed = Vec<Erased>>
+            + Functor<Function<Erased, Vec<Erased>>, Mapped = GF>
+            + Functor<A, Mapped = GA>,
+        GF: Applicative<Erased, Vec<Erased>, WithA = ApLayerT<F>, WithB = GV>,
+        GA: Pointed<Pointed = A>,
+    {
+        self.run_ap(identity)
+    }
+}
+
+impl<F, A> Pointed for Ap<F, A>
+where
+    F: WithPointed<Erased>,
+    A: Term,
+{
+    type Pointed = A;
+}
+
+impl<F, A, B> WithPointed<B> for Ap<F, A>
+where
+    F: WithPointed<Erased>,
+    A: Term,
+    B: Term,
+{
+    type WithPointed = Ap<F, B>;
+}
+
+impl<F, A, B> Functor<B> for Ap<F, A>
+where
+    F: WithPointed<Erased>,
+    A: Term,
+    B: Term,
+{
+    type Mapped = Ap<F, B>;
+
+    fn fmap(self, f: impl FunctionT<A, B>) -> Ap<F, B> {
+        Ap {
+            effects: self.effects,
+            run: self.run.compose_clone(f.to_function()).boxed(),
+        }
+    }
+}
+
+impl<F, A> PureA for Ap<F, A>
+where
+    F: WithPointed<Erased>,
+    A: Term,
+{
+    fn pure_a(t: A) -> Self {
+        Ap {
+            effects: vec![],
+            run: r#const(t).boxed(),
+        }
+    }
+}
+
+impl<F, G, A, B> AppA<A, B> for Ap<F, G>
+where
+    F: WithPointed<Erased>,
+    G: Term + FunctionT<A, B>,
+    A: Term,
+    B: Term,
+{
+    type WithA = Ap<F, A>;
+    type WithB = Ap<F, B>;
+
+    fn app_a(self, a: Ap<F, A>) -> Ap<F, B> {
+        let split = self.effects.len();
+        let mut effects = self.effects;
+        effects.extend(a.effects);
+
+        let run_f = self.run;
+        let run_a = a.run;
+
+        Ap {
+            effects,
+            run: (move |mut xs: Vec<Erased>| {
+                let ys = xs.split_off(split);
+                run_f(xs)(run_a(ys))
+            })
+            .boxed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        base::data::{monoid::Sum, tuple::pair::Pair},
+        derive_pointed, derive_with_pointed,
+        prelude::*,
+    };
+
+    use super::{Ap, ApLayerT};
+
+    #[derive(Clone)]
+    struct Field<N>
+    where
+        N: Term,
+    {
+        name: &'static str,
+        parse: Function<&'static str, Option<N>>,
+    }
+
+    derive_pointed!(Field<(N)>);
+    derive_with_pointed!(Field<(N)>);
+
+    impl<N, M> Functor<M> for Field<N>
+    where
+        N: Term,
+        M: Term,
+    {
+        type Mapped = Field<M>;
+
+        fn fmap(self, f: impl FunctionT<N, M>) -> Field<M> {
+            let f = f.to_function();
+            let parse = self.parse;
+            Field {
+                name: self.name,
+                parse: (move |s| parse(s).fmap(f)).boxed(),
+            }
+        }
+    }
+
+    fn field<A>(name: &'static str) -> Ap<Field<()>, A>
+    where
+        A: Term + std::str::FromStr,
+    {
+        Ap::lift_ap(Field {
+            name,
+            parse: (|s: &'static str| s.parse().ok()).boxed(),
+        })
+    }
+
+    fn form() -> Ap<Field<()>, (String, usize)> {
+        Pair::pair.lift_a2()(field("name"), field("age"))
+    }
+
+    fn lookup(
+        input: Vec<(&'static str, &'static str)>,
+    ) -> impl FunctionT<ApLayerT<Field<()>>, Option<Erased>> {
+        move |f: ApLayerT<Field<()>>| {
+            input
+                .into_iter()
+                .find(|(k, _)| *k == f.name)
+                .and_then(|(_, v)| (f.parse)(v))
+        }
+    }
+
+    #[test]
+    fn test_run_ap() {
+        let out: Option<(String, usize)> =
+            form().run_ap(lookup(vec![("age", "32"), ("name", "Alice")]));
+        assert_eq!(out, Some(("Alice".to_string(), 32)));
+
+        let out: Option<(String, usize)> =
+            form().run_ap(lookup(vec![("age", "old"), ("name", "Alice")]));
+        assert_eq!(out, None);
+
+        let out: Option<usize> = Ap::<Field<()>, usize>::pure_a(4).run_ap(lookup(vec![]));
+        assert_eq!(out, Some(4));
+    }
+
+    #[test]
+    fn test_run_ap_() {
+        let names = form().run_ap_(|f: ApLayerT<Field<()>>| vec![f.name]);
+        assert_eq!(names, vec!["name", "age"]);
+
+        let count = form()
+            .fmap(|(name, age)| format!("{name} {age}"))
+            .run_ap_(|_| Sum(1));
+        assert_eq!(count, Sum(2));
+    }
+
+    #[test]
+    fn test_retract_ap() {
+        let out: Option<usize> = Pair::pair.lift_a2()(
+            Ap::<Option<()>, usize>::lift_ap(Some(2)),
+            Ap::<Option<()>, usize>::lift_ap(Some(3)),
+        )
+        .fmap(|(a, b): (usize, usize)| a * b)
+        .retract_ap();
+        assert_eq!(out, Some(6));
+
+        let out: Option<usize> = Ap::<Option<()>, usize>::lift_ap(None)
+            .fmap(|a: usize| a + 1)
+            .retract_ap();
+        assert_eq!(out, None);
+    }
+
+    #[test]
+    fn test_applicative_laws() {
+        let run = |ap: Ap<Option<()>, usize>| -> Option<usize> { ap.retract_ap() };
+        let v = || Ap::<Option<()>, usize>::lift_ap(Some(3));
+
+        // Identity
+        assert_eq!(
+            run(Ap::<Option<()>, fn(usize) -> usize>::pure_a(identity).app_a(v())),
+            run(v())
+        );
+
+        // Homomorphism
+        let f = |x: usize| x * 2;
+        assert_eq!(
+            run(Ap::<Option<()>, _>::pure_a(f).app_a(PureA::pure_a(4))),
+            run(PureA::pure_a(f(4)))
+        );
+    }
+}
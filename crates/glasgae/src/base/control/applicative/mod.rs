@@ -72,6 +72,8 @@
 //! ```
 //! (which implies that pure and <*> satisfy the applicative functor laws).
 
+pub mod free;
+
 use crate::{base::data::function::bifunction::BifunT, prelude::*};
 
 /// Lift a value.
@@ -0,0 +1,456 @@
+//! Running IO actions asynchronously, and waiting for their results.
+//!
+//! Based on [Control.Concurrent.Async](https://hackage.haskell.org/package/async/docs/Control-Concurrent-Async.html).
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use glasgae::{base::control::concurrent::{asynchronous::*, *}, prelude::*};
+//! let slow = thread_delay(Duration::from_secs(60)).then_m(IO::return_m("slow"));
+//! let fast = thread_delay(Duration::from_millis(1)).then_m(IO::return_m(1));
+//!
+//! assert_eq!(unsafe { race(slow, fast).run() }, Right(1));
+//!
+//! let io = map_concurrently(vec![1, 2, 3], |n| IO::return_m(n * 10));
+//! assert_eq!(unsafe { io.run() }, vec![10, 20, 30]);
+//! ```
+
+use crate::{
+    base::control::exception::{
+        mask, on_exception, throw, uninterruptible_mask_, Restore, SomeException,
+    },
+    derive_pointed, derive_with_pointed,
+    prelude::*,
+};
+
+use super::{
+    fork_finally, kill_thread,
+    stm::{
+        atomically, new_empty_tmvar_io, or_else, put_tmvar, read_tmvar, retry, throw_stm,
+        try_read_tmvar, TMVar, STM,
+    },
+    ThreadId,
+};
+
+/// An asynchronous action started by [`async_io`].
+pub struct Async<A>
+where
+    A: Term,
+{
+    thread: ThreadId,
+    result: TMVar<Either<SomeException, A>>,
+}
+
+impl<A> Async<A>
+where
+    A: Term,
+{
+    /// The thread running the action.
+    pub fn thread_id(&self) -> ThreadId {
+        self.thread.clone()
+    }
+}
+
+impl<A> Clone for Async<A>
+where
+    A: Term,
+{
+    fn clone(&self) -> Self {
+        Async {
+            thread: self.thread.clone(),
+            result: self.result.clone(),
+        }
+    }
+}
+
+/// Two `Async`s are equal if they refer to the same action.
+impl<A> PartialEq for Async<A>
+where
+    A: Term,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.thread == other.thread
+    }
+}
+
+impl<A> Eq for Async<A> where A: Term {}
+
+impl<A> std::fmt::Debug for Async<A>
+where
+    A: Term,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Async").field(&self.thread).finish()
+    }
+}
+
+/// Run `io` in a new thread, returning an [`Async`] that can be used to wait for its result.
+///
+/// The new thread inherits the masking state of the current one.
+pub fn async_io<A>(io: IO<A>) -> IO<Async<A>>
+where
+    A: Term,
+{
+    async_using(io)
+}
+
+/// Fork `io` with [`fork_finally`], recording its outcome in the [`Async`]'s result.
+fn async_using<A>(io: IO<A>) -> IO<Async<A>>
+where
+    A: Term,
+{
+    new_empty_tmvar_io().chain_m(move |result: TMVar<Either<SomeException, A>>| {
+        let put = {
+            let result = result.clone();
+            move |outcome| atomically(put_tmvar(result.clone(), outcome))
+        };
+        fork_finally(io.clone(), put).fmap(move |thread| Async {
+            thread,
+            result: result.clone(),
+        })
+    })
+}
+
+/// Run `io` asynchronously and pass the [`Async`] to `f`,
+/// cancelling it once `f` returns or throws.
+///
+/// Both `io` and `f` run with the masking state of the caller;
+/// only forking the thread and cancelling it are masked.
+pub fn with_async<A, B>(io: IO<A>, f: impl FunctionT<Async<A>, IO<B>>) -> IO<B>
+where
+    A: Term,
+    B: Term,
+{
+    let f = f.to_function();
+    mask(move |restore: Restore| {
+        let f = f.clone();
+        async_using(restore.restore(io.clone())).chain_m(move |a| {
+            let cancel_ = {
+                let a = a.clone();
+                move || uninterruptible_mask_(cancel(a.clone()))
+            };
+            on_exception(restore.restore(f(a)), cancel_())
+                .chain_m(move |b| cancel_().then_m(IO::return_m(b)))
+        })
+    })
+}
+
+/// Wait for an [`Async`] to finish within a transaction,
+/// returning its result or the exception it threw.
+pub fn wait_catch_stm<A>(a: Async<A>) -> STM<Either<SomeException, A>>
+where
+    A: Term,
+{
+    read_tmvar(a.result)
+}
+
+/// Wait for an [`Async`] to finish within a transaction,
+/// rethrowing any exception it threw.
+pub fn wait_stm<A>(a: Async<A>) -> STM<A>
+where
+    A: Term,
+{
+    wait_catch_stm(a).chain_m(|result| match result {
+        Left(e) => throw_stm(e),
+        Right(a) => STM::return_m(a),
+    })
+}
+
+/// Check whether an [`Async`] has finished within a transaction.
+pub fn poll_stm<A>(a: Async<A>) -> STM<Option<Either<SomeException, A>>>
+where
+    A: Term,
+{
+    try_read_tmvar(a.result)
+}
+
+/// Wait for an [`Async`] to finish, returning its result or the exception it threw.
+pub fn wait_catch<A>(a: Async<A>) -> IO<Either<SomeException, A>>
+where
+    A: Term,
+{
+    atomically(wait_catch_stm(a))
+}
+
+/// Wait for an [`Async`] to finish, rethrowing any exception it threw.
+pub fn wait<A>(a: Async<A>) -> IO<A>
+where
+    A: Term,
+{
+    wait_catch(a).chain_m(|result| match result {
+        Left(e) => throw(e),
+        Right(a) => IO::return_m(a),
+    })
+}
+
+/// Check whether an [`Async`] has finished, without waiting.
+pub fn poll<A>(a: Async<A>) -> IO<Option<Either<SomeException, A>>>
+where
+    A: Term,
+{
+    atomically(poll_stm(a))
+}
+
+/// Kill the thread running an [`Async`], and wait for it to finish.
+///
+/// The thread only finishes once the kill has been raised
+/// by an interruptible operation;
+/// see the [module documentation](super) for details.
+/// Cancelling an `Async` that has already finished has no effect.
+pub fn cancel<A>(a: Async<A>) -> IO<()>
+where
+    A: Term,
+{
+    kill_thread(a.thread_id())
+        .then_m(wait_catch(a))
+        .fmap(|_| ())
+}
+
+/// Wait for either of two [`Async`]s to finish,
+/// returning the result of the first, or rethrowing its exception.
+pub fn wait_either<A, B>(a: Async<A>, b: Async<B>) -> IO<Either<A, B>>
+where
+    A: Term,
+    B: Term,
+{
+    atomically(or_else(wait_stm(a).fmap(Left), wait_stm(b).fmap(Right)))
+}
+
+/// Wait for both of two [`Async`]s to finish, returning both of their results.
+///
+/// If either throws, the exception is rethrown immediately,
+/// without waiting for the other.
+pub fn wait_both<A, B>(a: Async<A>, b: Async<B>) -> IO<(A, B)>
+where
+    A: Term,
+    B: Term,
+{
+    let first = or_else(wait_stm(a), wait_stm(b.clone()).then_m(retry()));
+    atomically(first.chain_m(move |a| wait_stm(b.clone()).fmap(move |b| (a.clone(), b))))
+}
+
+/// Run two actions concurrently, returning the result of whichever finishes first.
+///
+/// The other is cancelled.
+/// If the first to finish throws, the exception is rethrown.
+pub fn race<A, B>(left: IO<A>, right: IO<B>) -> IO<Either<A, B>>
+where
+    A: Term,
+    B: Term,
+{
+    with_async(left, move |a| {
+        with_async(right.clone(), move |b| wait_either(a.clone(), b))
+    })
+}
+
+/// Run two actions concurrently, returning both of their results.
+///
+/// If either throws, the other is cancelled and the exception is rethrown.
+pub fn concurrently<A, B>(left: IO<A>, right: IO<B>) -> IO<(A, B)>
+where
+    A: Term,
+    B: Term,
+{
+    with_async(left, move |a| {
+        with_async(right.clone(), move |b| wait_both(a.clone(), b))
+    })
+}
+
+/// Map an action over every element of a traversable structure,
+/// running the actions concurrently.
+///
+/// If any of the actions throws, the others are cancelled and the exception is rethrown.
+pub fn map_concurrently<T, A, B, I, TB>(t: T, f: impl FunctionT<A, IO<B>>) -> IO<TB>
+where
+    T: TraverseT<
+        Concurrently<B>,
+        I,
+        Concurrently<TB>,
+        Pointed = A,
+        Mapped = Concurrently<B>,
+        Traversed = Concurrently<TB>,
+    >,
+    A: Term,
+    B: Term,
+    TB: Term,
+{
+    let f = f.to_function();
+    t.traverse_t(move |a| Concurrently(f(a))).run_concurrently()
+}
+
+/// An [`IO`] action whose applicative instance runs effects concurrently.
+///
+/// Combining two `Concurrently` values with [`AppA::app_a`]
+/// runs both with [`concurrently`].
+#[derive(Clone)]
+pub struct Concurrently<A>(pub IO<A>)
+where
+    A: Term;
+
+impl<A> Concurrently<A>
+where
+    A: Term,
+{
+    pub fn run_concurrently(self) -> IO<A> {
+        self.0
+    }
+}
+
+derive_pointed!(Concurrently<(A)>);
+derive_with_pointed!(Concurrently<(A)>);
+
+impl<A, B> Functor<B> for Concurrently<A>
+where
+    A: Term,
+    B: Term,
+{
+    type Mapped = Concurrently<B>;
+
+    fn fmap(self, f: impl FunctionT<A, B>) -> Concurrently<B> {
+        Concurrently(self.0.fmap(f))
+    }
+}
+
+impl<A> PureA for Concurrently<A>
+where
+    A: Term,
+{
+    fn pure_a(t: Self::Pointed) -> Self {
+        Concurrently(IO::pure_a(t))
+    }
+}
+
+impl<F, A, B> AppA<A, B> for Concurrently<F>
+where
+    F: Term + FunctionT<A, B>,
+    A: Term,
+    B: Term,
+{
+    type WithA = Concurrently<A>;
+    type WithB = Concurrently<B>;
+
+    fn app_a(self, a: Concurrently<A>) -> Concurrently<B> {
+        Concurrently(concurrently(self.0, a.0).fmap(|(f, a)| f(a)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::base::control::{
+        concurrent::{
+            new_empty_mvar,
+            stm::{check, modify_tvar, new_tvar_io, read_tvar},
+            thread_delay, try_put_mvar, try_take_mvar,
+        },
+        exception::{catch, finally, get_masking_state, ErrorCall, MaskingState},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_wait() {
+        let io = async_io(IO::return_m(1)).chain_m(|a| {
+            let failing = async_io(throw::<usize, _>(ErrorCall::new("failed")));
+            wait(a.clone()).chain_m(move |result| {
+                let a = a.clone();
+                failing.clone().chain_m(move |failing| {
+                    let a = a.clone();
+                    wait_catch(failing).chain_m(move |caught| {
+                        poll(a.clone()).fmap(move |polled| {
+                            (
+                                result,
+                                match caught {
+                                    Left(e) => Some(e.show()),
+                                    Right(_) => None,
+                                },
+                                polled.map(Either::is_right),
+                            )
+                        })
+                    })
+                })
+            })
+        });
+        assert_eq!(
+            unsafe { io.run() },
+            (1, Some("failed".to_string()), Some(true))
+        );
+    }
+
+    #[test]
+    fn test_race() {
+        // The loser is cancelled, and has finished by the time race returns.
+        let io = new_empty_mvar().chain_m(|cancelled| {
+            let slow = finally(
+                thread_delay(Duration::from_secs(60)),
+                try_put_mvar(cancelled.clone(), ()).fmap(|_| ()),
+            );
+            let fast = thread_delay(Duration::from_millis(1)).then_m(IO::return_m('a'));
+            race(slow, fast).chain_m(move |winner| {
+                try_take_mvar(cancelled.clone()).fmap(move |cancelled| (winner, cancelled))
+            })
+        });
+        assert_eq!(unsafe { io.run() }, (Right('a'), Some(())));
+    }
+
+    #[test]
+    fn test_concurrently() {
+        let io = concurrently(IO::return_m(1), IO::return_m("two"));
+        assert_eq!(unsafe { io.run() }, (1, "two"));
+
+        // An exception in one action cancels the other.
+        let io = concurrently(
+            thread_delay(Duration::from_secs(60)),
+            throw::<(), _>(ErrorCall::new("failed")),
+        )
+        .fmap(|_| String::new());
+        let io = catch(io, |e: ErrorCall| IO::return_m(e.0));
+        assert_eq!(unsafe { io.run() }, "failed");
+    }
+
+    #[test]
+    fn test_map_concurrently() {
+        // Every action waits for all of the others to start,
+        // so this only finishes if they run concurrently.
+        let io = new_tvar_io(0).chain_m(|started| {
+            map_concurrently(vec![1, 2, 3, 4], move |n: usize| {
+                let started = started.clone();
+                atomically(modify_tvar(started.clone(), |s| s + 1))
+                    .then_m(atomically(read_tvar(started).chain_m(|s| check(s == 4))))
+                    .then_m(IO::return_m(n * n))
+            })
+        });
+        assert_eq!(unsafe { io.run() }, vec![1, 4, 9, 16]);
+
+        let io = map_concurrently(Some(3), |n: usize| IO::return_m(n + 1));
+        assert_eq!(unsafe { io.run() }, Some(4));
+    }
+
+    #[test]
+    fn test_masking_state() {
+        // Actions run with the caller's masking state, not the one used to fork them.
+        let io =
+            race(get_masking_state(), thread_delay(Duration::from_secs(60))).chain_m(|raced| {
+                concurrently(get_masking_state(), get_masking_state())
+                    .fmap(move |both| (raced, both))
+            });
+        assert_eq!(
+            unsafe { io.run() },
+            (
+                Left(MaskingState::Unmasked),
+                (MaskingState::Unmasked, MaskingState::Unmasked)
+            )
+        );
+
+        let io = with_async(IO::return_m(()), |_| get_masking_state());
+        assert_eq!(unsafe { io.run() }, MaskingState::Unmasked);
+    }
+
+    #[test]
+    fn test_concurrently_applicative() {
+        let sum = |a: usize, b: usize| a + b;
+        let io = sum.lift_a2()(Concurrently(IO::return_m(1)), Concurrently(IO::return_m(2)))
+            .run_concurrently();
+        assert_eq!(unsafe { io.run() }, 3);
+    }
+}
@@ -0,0 +1,215 @@
+//! Unbounded channels.
+//!
+//! Based on [Control.Concurrent.Chan](https://hackage.haskell.org/package/base/docs/Control-Concurrent-Chan.html).
+
+use crate::{base::control::exception::mask_, prelude::*};
+
+use super::{modify_mvar, new_empty_mvar, new_mvar, put_mvar, read_mvar, take_mvar, MVar};
+
+/// The position in a [`Chan`] at which the next item will be written.
+type Stream<T> = MVar<ChItem<T>>;
+
+/// An item in a [`Chan`], along with the position of the item after it.
+#[derive(Clone)]
+struct ChItem<T>(T, Stream<T>)
+where
+    T: Term;
+
+/// An unbounded FIFO channel.
+///
+/// Writing never blocks, and reading blocks until an item is available.
+/// Clones refer to the same channel, and compete to read its items;
+/// use [`dup_chan`] to receive a copy of every item instead.
+pub struct Chan<T>
+where
+    T: Term,
+{
+    read: MVar<Stream<T>>,
+    write: MVar<Stream<T>>,
+}
+
+impl<T> Clone for Chan<T>
+where
+    T: Term,
+{
+    fn clone(&self) -> Self {
+        Chan {
+            read: self.read.clone(),
+            write: self.write.clone(),
+        }
+    }
+}
+
+/// Two `Chan`s are equal if they read from the same position of the same channel.
+impl<T> PartialEq for Chan<T>
+where
+    T: Term,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.read == other.read
+    }
+}
+
+impl<T> Eq for Chan<T> where T: Term {}
+
+impl<T> std::fmt::Debug for Chan<T>
+where
+    T: Term,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Chan").finish_non_exhaustive()
+    }
+}
+
+/// Build a new, empty [`Chan`].
+pub fn new_chan<T>() -> IO<Chan<T>>
+where
+    T: Term,
+{
+    new_empty_mvar().chain_m(|hole: Stream<T>| {
+        new_mvar(hole.clone()).chain_m(move |read| {
+            new_mvar(hole.clone()).fmap(move |write| Chan {
+                read: read.clone(),
+                write,
+            })
+        })
+    })
+}
+
+/// Write an item to a [`Chan`].
+pub fn write_chan<T>(chan: Chan<T>, t: T) -> IO<()>
+where
+    T: Term,
+{
+    new_empty_mvar().chain_m(move |new_hole: Stream<T>| {
+        let write = chan.write.clone();
+        let t = t.clone();
+        mask_(take_mvar(write.clone()).chain_m(move |old_hole| {
+            put_mvar(old_hole, ChItem(t.clone(), new_hole.clone()))
+                .then_m(put_mvar(write.clone(), new_hole.clone()))
+        }))
+    })
+}
+
+/// Read the next item from a [`Chan`].
+///
+/// Blocks while the channel is empty.
+pub fn read_chan<T>(chan: Chan<T>) -> IO<T>
+where
+    T: Term,
+{
+    modify_mvar(chan.read, |read_end| {
+        read_mvar(read_end).fmap(|ChItem(t, new_read_end)| (new_read_end, t))
+    })
+}
+
+/// Duplicate a [`Chan`].
+///
+/// The new channel starts empty, and receives every item written
+/// to either channel from then on.
+pub fn dup_chan<T>(chan: Chan<T>) -> IO<Chan<T>>
+where
+    T: Term,
+{
+    read_mvar(chan.write.clone()).chain_m(move |hole| {
+        let write = chan.write.clone();
+        new_mvar(hole).fmap(move |read| Chan {
+            read,
+            write: write.clone(),
+        })
+    })
+}
+
+/// Write every item of `ts` to a [`Chan`], in order.
+pub fn write_list_to_chan<T>(chan: Chan<T>, ts: Vec<T>) -> IO<()>
+where
+    T: Term,
+{
+    ts.into_iter()
+        .map(|t| write_chan(chan.clone(), t))
+        .fold(IO::return_m(()), |acc, next| acc.then_m(next))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::base::control::concurrent::{fork_finally, fork_io, kill_thread, thread_delay};
+
+    use super::*;
+
+    fn read_n<T: Term>(chan: Chan<T>, n: usize) -> IO<Vec<T>> {
+        (0..n).fold(IO::return_m(vec![]), |acc, _| {
+            let chan = chan.clone();
+            acc.chain_m(move |mut ts: Vec<T>| {
+                read_chan(chan.clone()).fmap(move |t| {
+                    ts.push(t);
+                    ts.clone()
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn test_chan() {
+        let io = new_chan().chain_m(|chan| {
+            write_chan(chan.clone(), 1).chain_m(move |_| {
+                let chan = chan.clone();
+                dup_chan(chan.clone()).chain_m(move |dup| {
+                    let chan = chan.clone();
+                    write_list_to_chan(chan.clone(), vec![2, 3]).then_m(
+                        read_n(chan.clone(), 3).chain_m(move |original| {
+                            read_n(dup.clone(), 2).fmap(move |dup| (original, dup))
+                        }),
+                    )
+                })
+            })
+        });
+        assert_eq!(unsafe { io.run() }, (vec![1, 2, 3], vec![2, 3]));
+    }
+
+    #[test]
+    fn test_concurrent_writers() {
+        let io = new_chan().chain_m(|chan| {
+            let writers = (0..4)
+                .map(|w| {
+                    fork_io(write_list_to_chan(
+                        chan.clone(),
+                        (0..25).map(|i| w * 25 + i).collect(),
+                    ))
+                })
+                .fold(IO::return_m(()), |acc, next| acc.then_m(next).fmap(|_| ()));
+            writers.then_m(read_n(chan, 100))
+        });
+
+        let mut received = unsafe { io.run() };
+        received.sort();
+        assert_eq!(received, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_kill_reader() {
+        // A reader killed while blocked must not lose its place in the channel.
+        let io = new_chan().chain_m(|chan: Chan<usize>| {
+            new_empty_mvar().chain_m(move |done| {
+                let chan = chan.clone();
+                fork_finally(read_chan(chan.clone()), {
+                    let done = done.clone();
+                    move |outcome: Either<_, usize>| put_mvar(done.clone(), outcome.is_left())
+                })
+                .chain_m(move |tid| {
+                    let chan = chan.clone();
+                    thread_delay(Duration::from_millis(10))
+                        .then_m(kill_thread(tid))
+                        .then_m(take_mvar(done.clone()))
+                        .chain_m(move |killed| {
+                            write_chan(chan.clone(), 7)
+                                .then_m(read_chan(chan.clone()))
+                                .fmap(move |t| (killed, t))
+                        })
+                })
+            })
+        });
+        assert_eq!(unsafe { io.run() }, (true, 7));
+    }
+}
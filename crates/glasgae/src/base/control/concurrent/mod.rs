@@ -0,0 +1,438 @@
+//! Concurrent threads of execution in the IO monad.
+//!
+//! Based on [Control.Concurrent](https://hackage.haskell.org/package/base/docs/Control-Concurrent.html).
+//!
+//! Each thread started by [`fork_io`] runs on its own OS thread.
+//! Threads communicate through shared [`MVar`]s and [`Chan`]s.
+//!
+//! ```
+//! # use glasgae::{base::control::concurrent::*, prelude::*};
+//! let io = new_empty_mvar().chain_m(|result| {
+//!     fork_io(put_mvar(result.clone(), (1..=10).sum::<usize>())).then_m(take_mvar(result))
+//! });
+//!
+//! assert_eq!(unsafe { io.run() }, 55);
+//! ```
+//!
+//! # Interruption
+//!
+//! OS threads cannot be safely interrupted at arbitrary points,
+//! so an exception thrown by [`kill_thread`] is raised in the target thread
+//! when it next blocks in an interruptible operation,
+//! such as [`thread_delay`], [`take_mvar`] or [`read_chan`].
+//! Operations that can complete without blocking are not interrupted.
+//!
+//! Blocking operations run under [`uninterruptible_mask`](crate::base::control::exception::uninterruptible_mask)
+//! are never interrupted;
+//! a pending exception is instead raised by the next interruptible operation
+//! after the mask is lifted.
+
+pub mod asynchronous;
+mod chan;
+mod mvar;
+pub mod stm;
+
+pub use chan::*;
+pub use mvar::*;
+
+use std::{
+    panic::catch_unwind,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Condvar, Mutex, MutexGuard, PoisonError, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    base::control::exception::{
+        get_masking_state, mask, r#try, AsyncException, Exception, MaskingState, MonadMask,
+        SomeException,
+    },
+    prelude::*,
+};
+
+/// Woken when the thread blocked on it is killed.
+trait Interrupt: Send + Sync {
+    fn interrupt(&self);
+}
+
+struct ThreadState {
+    thread: std::thread::Thread,
+    killed: AtomicBool,
+    blocked_on: Mutex<Option<Arc<dyn Interrupt>>>,
+}
+
+/// Identifies a thread of execution.
+///
+/// Returned by [`fork_io`] and [`my_thread_id`].
+#[derive(Clone)]
+pub struct ThreadId(Arc<ThreadState>);
+
+thread_local! {
+    static CURRENT: ThreadId = ThreadId(Arc::new(ThreadState {
+        thread: std::thread::current(),
+        killed: AtomicBool::new(false),
+        blocked_on: Mutex::new(None),
+    }));
+}
+
+impl ThreadId {
+    fn current() -> ThreadId {
+        CURRENT.with(Clone::clone)
+    }
+
+    fn set_blocked_on(&self, interrupt: Option<Arc<dyn Interrupt>>) {
+        *self
+            .0
+            .blocked_on
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = interrupt;
+    }
+
+    /// Returns `true` and clears the kill if this thread has been killed
+    /// and interruption is currently permitted.
+    ///
+    /// Must be called from the thread itself.
+    fn take_kill(&self) -> bool {
+        let state = unsafe { get_masking_state().run() };
+        state != MaskingState::MaskedUninterruptible && self.0.killed.swap(false, Ordering::SeqCst)
+    }
+}
+
+fn throw_killed() -> ! {
+    AsyncException::ThreadKilled.to_exception().rethrow()
+}
+
+/// Two `ThreadId`s are equal if they identify the same thread.
+impl PartialEq for ThreadId {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ThreadId {}
+
+impl std::hash::Hash for ThreadId {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.thread.id().hash(state)
+    }
+}
+
+impl std::fmt::Debug for ThreadId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.thread.id().fmt(f)
+    }
+}
+
+impl Show for ThreadId {
+    fn show(self) -> String {
+        format!("{self:?}")
+    }
+}
+
+/// Clears the interruptible operation of the current thread when dropped.
+struct BlockedGuard(ThreadId);
+
+impl Drop for BlockedGuard {
+    fn drop(&mut self) {
+        self.0.set_blocked_on(None)
+    }
+}
+
+/// Wait on `cond` until `ready` holds for the value guarded by `mutex`.
+///
+/// If the current thread is killed while waiting, the lock is released
+/// and [`AsyncException::ThreadKilled`] is raised.
+/// `interrupt` must notify `cond` while holding `mutex`.
+fn wait_until<'a, T>(
+    interrupt: Arc<dyn Interrupt>,
+    mutex: &'a Mutex<T>,
+    cond: &Condvar,
+    ready: impl Fn(&T) -> bool,
+) -> MutexGuard<'a, T> {
+    let mut guard = mutex.lock().unwrap_or_else(PoisonError::into_inner);
+    if ready(&guard) {
+        return guard;
+    }
+
+    let me = ThreadId::current();
+    me.set_blocked_on(Some(interrupt));
+    let _blocked = BlockedGuard(me.clone());
+
+    loop {
+        if ready(&guard) {
+            return guard;
+        }
+
+        if me.take_kill() {
+            drop(guard);
+            throw_killed()
+        }
+
+        guard = cond.wait(guard).unwrap_or_else(PoisonError::into_inner);
+    }
+}
+
+/// The handler installed by [`set_uncaught_exception_handler`], if any.
+static UNCAUGHT_EXCEPTION_HANDLER: RwLock<Option<Function<SomeException, IO<()>>>> =
+    RwLock::new(None);
+
+/// Install a handler for exceptions that escape threads started by [`fork_io`].
+///
+/// The handler is global, replacing any installed before it,
+/// and runs in the thread that raised the exception.
+/// [`AsyncException::ThreadKilled`] is never passed to it.
+///
+/// By default, exceptions thrown with [`throw`](crate::base::control::exception::throw)
+/// are printed to standard error,
+/// while other panics are left to the panic hook that already reported them.
+pub fn set_uncaught_exception_handler(handler: impl FunctionT<SomeException, IO<()>>) -> IO<()> {
+    let handler = handler.to_function();
+    IO::new(move || {
+        *UNCAUGHT_EXCEPTION_HANDLER
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(handler.boxed())
+    })
+}
+
+/// Report an exception that escaped the current thread.
+fn report_uncaught(payload: Box<dyn std::any::Any + Send>) {
+    let reported = payload.is::<SomeException>();
+    let e = SomeException::from_panic(payload);
+    if e.is::<AsyncException>() {
+        return;
+    }
+
+    let handler = UNCAUGHT_EXCEPTION_HANDLER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+
+    match handler {
+        Some(handler) => {
+            // An exception escaping the handler has nowhere left to go.
+            let _ = catch_unwind(move || unsafe { handler(e).run() });
+        }
+        None if reported => eprintln!("{}: {}", ThreadId::current().show(), e.display_exception()),
+        None => (),
+    }
+}
+
+/// Run `io` in a new thread, returning its [`ThreadId`].
+///
+/// The new thread inherits the masking state of the current one.
+/// Exceptions that escape `io` are passed to the handler
+/// installed by [`set_uncaught_exception_handler`].
+pub fn fork_io(io: IO<()>) -> IO<ThreadId> {
+    get_masking_state().chain_m(move |state| {
+        let io = io.clone().with_masking_state(state);
+        IO::new(move || {
+            let (sender, receiver) = mpsc::channel();
+            std::thread::spawn(move || {
+                sender.send(ThreadId::current()).ok();
+                if let Err(payload) = catch_unwind(move || unsafe { io.run() }) {
+                    report_uncaught(payload)
+                }
+            });
+            receiver.recv().expect("forked thread has started")
+        })
+    })
+}
+
+/// Run `io` in a new thread, and pass its outcome to `and_then` once it finishes.
+///
+/// `and_then` runs in the new thread, even if `io` was killed.
+pub fn fork_finally<A>(
+    io: IO<A>,
+    and_then: impl FunctionT<Either<SomeException, A>, IO<()>>,
+) -> IO<ThreadId>
+where
+    A: Term,
+{
+    let and_then = and_then.to_function();
+    mask(move |restore| {
+        fork_io(r#try::<SomeException, _>(restore.restore(io.clone())).chain_m(and_then.clone()))
+    })
+}
+
+/// The [`ThreadId`] of the current thread.
+pub fn my_thread_id() -> IO<ThreadId> {
+    IO::new(ThreadId::current)
+}
+
+/// Raise [`AsyncException::ThreadKilled`] in the target thread.
+///
+/// Killing the current thread raises the exception immediately.
+/// Otherwise, this returns without waiting for the exception to be raised;
+/// see the [module documentation](self) for when that happens.
+/// Killing a thread that has finished has no effect.
+pub fn kill_thread(tid: ThreadId) -> IO<()> {
+    IO::new(move || {
+        if tid == ThreadId::current() {
+            throw_killed()
+        }
+
+        tid.0.killed.store(true, Ordering::SeqCst);
+        tid.0.thread.unpark();
+
+        let blocked_on = tid
+            .0
+            .blocked_on
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+
+        if let Some(interrupt) = blocked_on {
+            interrupt.interrupt()
+        }
+    })
+}
+
+/// Suspend the current thread for the given duration.
+///
+/// This is an interruptible operation.
+pub fn thread_delay(duration: Duration) -> IO<()> {
+    IO::new(move || {
+        let me = ThreadId::current();
+        let deadline = Instant::now() + duration;
+        loop {
+            if me.take_kill() {
+                throw_killed()
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            std::thread::park_timeout(deadline - now);
+        }
+    })
+}
+
+/// Offer the rest of the current thread's time slice to other threads.
+pub fn yield_() -> IO<()> {
+    IO::new(std::thread::yield_now)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        base::control::exception::{
+            throw, uninterruptible_mask_, AsyncException, ErrorCall, SomeException,
+        },
+        prelude::*,
+    };
+
+    use super::{
+        fork_finally, fork_io, kill_thread, my_thread_id, new_empty_mvar, put_mvar,
+        set_uncaught_exception_handler, take_mvar, thread_delay, try_put_mvar, try_take_mvar,
+    };
+
+    #[test]
+    fn test_fork_io() {
+        let io = new_empty_mvar().chain_m(|mvar| {
+            my_thread_id().chain_m(move |parent| {
+                let child = my_thread_id().chain_m({
+                    let mvar = mvar.clone();
+                    move |child| put_mvar(mvar.clone(), (parent.clone(), child))
+                });
+                fork_io(child).chain_m(move |forked| {
+                    take_mvar(mvar.clone())
+                        .fmap(move |(parent, child)| (parent != child, child == forked))
+                })
+            })
+        });
+        assert_eq!(unsafe { io.run() }, (true, true));
+    }
+
+    #[test]
+    fn test_kill_thread() {
+        let io = new_empty_mvar().chain_m(|done| {
+            let sleeper = thread_delay(Duration::from_secs(60));
+            fork_finally(sleeper, {
+                let done = done.clone();
+                move |outcome: Either<SomeException, ()>| {
+                    put_mvar(
+                        done.clone(),
+                        matches!(outcome, Left(e) if e.is::<AsyncException>()),
+                    )
+                }
+            })
+            .chain_m(move |tid| kill_thread(tid).then_m(take_mvar(done.clone())))
+        });
+        assert!(unsafe { io.run() });
+    }
+
+    #[test]
+    fn test_kill_uninterruptible() {
+        // The kill is deferred until the masked delay has finished,
+        // and raised by the next interruptible operation.
+        let io = new_empty_mvar().chain_m(|started| {
+            new_empty_mvar().chain_m(move |delayed| {
+                let started = started.clone();
+                new_empty_mvar().chain_m(move |killed| {
+                    let worker = put_mvar(started.clone(), ())
+                        .then_m(uninterruptible_mask_(
+                            thread_delay(Duration::from_millis(50))
+                                .then_m(put_mvar(delayed.clone(), ())),
+                        ))
+                        .then_m(thread_delay(Duration::from_secs(60)));
+
+                    let delayed = delayed.clone();
+                    fork_finally(worker, {
+                        let killed = killed.clone();
+                        move |outcome: Either<SomeException, ()>| {
+                            put_mvar(killed.clone(), outcome.is_left())
+                        }
+                    })
+                    .chain_m(move |tid| {
+                        let delayed = delayed.clone();
+                        take_mvar(started.clone())
+                            .then_m(kill_thread(tid))
+                            .then_m(take_mvar(killed.clone()))
+                            .chain_m(move |killed| {
+                                try_take_mvar(delayed.clone())
+                                    .fmap(move |delayed| (killed, delayed))
+                            })
+                    })
+                })
+            })
+        });
+        assert_eq!(unsafe { io.run() }, (true, Some(())));
+    }
+
+    #[test]
+    fn test_uncaught_exception() {
+        // The handler is global, and stays installed for every other test in the binary,
+        // so only report the exception thrown here.
+        let io = new_empty_mvar().chain_m(|reported| {
+            set_uncaught_exception_handler({
+                let reported = reported.clone();
+                move |e: SomeException| match e.show() {
+                    e if e == "test_uncaught_exception" => {
+                        try_put_mvar(reported.clone(), e).fmap(|_| ())
+                    }
+                    _ => IO::return_m(()),
+                }
+            })
+            .then_m(fork_io(throw(ErrorCall::new("test_uncaught_exception"))))
+            .then_m(take_mvar(reported))
+        });
+        assert_eq!(unsafe { io.run() }, "test_uncaught_exception");
+    }
+
+    #[test]
+    fn test_kill_self() {
+        let io = new_empty_mvar().chain_m(|done| {
+            let suicide = my_thread_id().chain_m(kill_thread).then_m(IO::return_m(()));
+            fork_finally(suicide, {
+                let done = done.clone();
+                move |outcome: Either<SomeException, ()>| put_mvar(done.clone(), outcome.is_left())
+            })
+            .then_m(take_mvar(done))
+        });
+        assert!(unsafe { io.run() });
+    }
+}
@@ -0,0 +1,312 @@
+//! Synchronising variables.
+//!
+//! Based on [Control.Concurrent.MVar](https://hackage.haskell.org/package/base/docs/Control-Concurrent-MVar.html).
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+use crate::{
+    base::control::exception::{mask, mask_, on_exception},
+    prelude::*,
+};
+
+use super::{wait_until, Interrupt};
+
+struct MVarState<T> {
+    value: Mutex<Option<T>>,
+    changed: Condvar,
+}
+
+impl<T> MVarState<T> {
+    fn lock(&self) -> MutexGuard<'_, Option<T>> {
+        self.value.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Interrupt for MVarState<T>
+where
+    T: Send,
+{
+    fn interrupt(&self) {
+        let _guard = self.lock();
+        self.changed.notify_all()
+    }
+}
+
+/// A synchronising variable, which is either empty or full.
+///
+/// Taking from an empty `MVar` blocks until another thread puts a value into it,
+/// and putting into a full one blocks until it is taken.
+/// Clones refer to the same variable.
+pub struct MVar<T>(Arc<MVarState<T>>);
+
+impl<T> MVar<T>
+where
+    T: Term,
+{
+    fn new(value: Option<T>) -> Self {
+        MVar(Arc::new(MVarState {
+            value: Mutex::new(value),
+            changed: Condvar::new(),
+        }))
+    }
+
+    /// Block until `ready` holds for the contents, then lock them.
+    fn wait_until(&self, ready: impl Fn(&Option<T>) -> bool) -> MutexGuard<'_, Option<T>> {
+        wait_until(self.0.clone(), &self.0.value, &self.0.changed, ready)
+    }
+}
+
+impl<T> Clone for MVar<T> {
+    fn clone(&self) -> Self {
+        MVar(self.0.clone())
+    }
+}
+
+/// Two `MVar`s are equal if they refer to the same variable.
+impl<T> PartialEq for MVar<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T> Eq for MVar<T> {}
+
+impl<T> std::fmt::Debug for MVar<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MVar").field(&*self.0.lock()).finish()
+    }
+}
+
+/// Build a new, empty [`MVar`].
+pub fn new_empty_mvar<T>() -> IO<MVar<T>>
+where
+    T: Term,
+{
+    IO::new(|| MVar::new(None))
+}
+
+/// Build a new [`MVar`] holding `t`.
+pub fn new_mvar<T>(t: T) -> IO<MVar<T>>
+where
+    T: Term,
+{
+    IO::new(move || MVar::new(Some(t)))
+}
+
+/// Take the contents of an [`MVar`], leaving it empty.
+///
+/// Blocks while the `MVar` is empty.
+pub fn take_mvar<T>(mvar: MVar<T>) -> IO<T>
+where
+    T: Term,
+{
+    IO::new(move || {
+        let t = mvar.wait_until(Option::is_some).take().unwrap();
+        mvar.0.changed.notify_all();
+        t
+    })
+}
+
+/// Put a value into an [`MVar`].
+///
+/// Blocks while the `MVar` is full.
+pub fn put_mvar<T>(mvar: MVar<T>, t: T) -> IO<()>
+where
+    T: Term,
+{
+    IO::new(move || {
+        *mvar.wait_until(Option::is_none) = Some(t);
+        mvar.0.changed.notify_all();
+    })
+}
+
+/// Read the contents of an [`MVar`] without taking them.
+///
+/// Blocks while the `MVar` is empty.
+/// Unlike a [`take_mvar`] followed by a [`put_mvar`],
+/// no other thread can put a value into the `MVar` in between.
+pub fn read_mvar<T>(mvar: MVar<T>) -> IO<T>
+where
+    T: Term,
+{
+    IO::new(move || mvar.wait_until(Option::is_some).clone().unwrap())
+}
+
+/// Take the contents of an [`MVar`] if it is full, without blocking.
+pub fn try_take_mvar<T>(mvar: MVar<T>) -> IO<Option<T>>
+where
+    T: Term,
+{
+    IO::new(move || {
+        let t = mvar.0.lock().take();
+        mvar.0.changed.notify_all();
+        t
+    })
+}
+
+/// Put a value into an [`MVar`] if it is empty, without blocking.
+///
+/// Returns `true` if the value was put.
+pub fn try_put_mvar<T>(mvar: MVar<T>, t: T) -> IO<bool>
+where
+    T: Term,
+{
+    IO::new(move || {
+        let mut guard = mvar.0.lock();
+        if guard.is_some() {
+            return false;
+        }
+        *guard = Some(t);
+        mvar.0.changed.notify_all();
+        true
+    })
+}
+
+/// Read the contents of an [`MVar`] if it is full, without blocking.
+pub fn try_read_mvar<T>(mvar: MVar<T>) -> IO<Option<T>>
+where
+    T: Term,
+{
+    IO::new(move || mvar.0.lock().clone())
+}
+
+/// Returns `true` if an [`MVar`] is currently empty.
+///
+/// The result may be out of date as soon as it is returned.
+pub fn is_empty_mvar<T>(mvar: MVar<T>) -> IO<bool>
+where
+    T: Term,
+{
+    IO::new(move || mvar.0.lock().is_none())
+}
+
+/// Take the contents of an [`MVar`], and put back the first value returned by `f`.
+///
+/// The original contents are put back if `f` throws.
+/// This is only atomic if no other thread puts into the `MVar` without first taking from it.
+pub fn modify_mvar<T, B>(mvar: MVar<T>, f: impl FunctionT<T, IO<(T, B)>>) -> IO<B>
+where
+    T: Term,
+    B: Term,
+{
+    let f = f.to_function();
+    mask(move |restore| {
+        let mvar = mvar.clone();
+        let f = f.clone();
+        take_mvar(mvar.clone()).chain_m(move |t| {
+            let mvar = mvar.clone();
+            on_exception(restore.restore(f(t.clone())), put_mvar(mvar.clone(), t))
+                .chain_m(move |(t, b)| put_mvar(mvar.clone(), t).then_m(IO::return_m(b)))
+        })
+    })
+}
+
+/// Take the contents of an [`MVar`], and put back the value returned by `f`.
+///
+/// The original contents are put back if `f` throws.
+pub fn modify_mvar_<T>(mvar: MVar<T>, f: impl FunctionT<T, IO<T>>) -> IO<()>
+where
+    T: Term,
+{
+    let f = f.to_function();
+    modify_mvar(mvar, move |t| f(t).fmap(|t| (t, ())))
+}
+
+/// Take the contents of an [`MVar`], pass them to `f`, and put them back afterwards,
+/// even if `f` throws.
+pub fn with_mvar<T, B>(mvar: MVar<T>, f: impl FunctionT<T, IO<B>>) -> IO<B>
+where
+    T: Term,
+    B: Term,
+{
+    let f = f.to_function();
+    modify_mvar(mvar, move |t: T| f(t.clone()).fmap(move |b| (t.clone(), b)))
+}
+
+/// Replace the contents of an [`MVar`], returning the old contents.
+pub fn swap_mvar<T>(mvar: MVar<T>, new: T) -> IO<T>
+where
+    T: Term,
+{
+    mask_(
+        take_mvar(mvar.clone())
+            .chain_m(move |old| put_mvar(mvar.clone(), new.clone()).then_m(IO::return_m(old))),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::base::control::{
+        concurrent::fork_io,
+        exception::{catch, throw, ErrorCall},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_mvar() {
+        let io = new_mvar(1).chain_m(|mvar| {
+            try_put_mvar(mvar.clone(), 2).chain_m(move |put_full| {
+                let mvar = mvar.clone();
+                read_mvar(mvar.clone())
+                    .chain_m(move |read| {
+                        let mvar = mvar.clone();
+                        swap_mvar(mvar.clone(), 3).chain_m(move |swapped| {
+                            take_mvar(mvar.clone()).chain_m({
+                                let mvar = mvar.clone();
+                                move |taken| {
+                                    try_take_mvar(mvar.clone())
+                                        .fmap(move |empty| (read, swapped, taken, empty))
+                                }
+                            })
+                        })
+                    })
+                    .fmap(move |rest| (put_full, rest))
+            })
+        });
+        assert_eq!(unsafe { io.run() }, (false, (1, 1, 3, None)));
+    }
+
+    #[test]
+    fn test_handoff() {
+        // Each value is put by one thread and taken by the other,
+        // so the consumer sees every value exactly once and in order.
+        let io = new_empty_mvar().chain_m(|mvar| {
+            let producer = (0..100)
+                .map(|i| put_mvar(mvar.clone(), i))
+                .fold(IO::return_m(()), |acc, next| acc.then_m(next));
+
+            let consumer = (0..100).fold(IO::return_m(vec![]), |acc, _| {
+                let mvar = mvar.clone();
+                acc.chain_m(move |mut received: Vec<usize>| {
+                    take_mvar(mvar.clone()).fmap(move |i| {
+                        received.push(i);
+                        received.clone()
+                    })
+                })
+            });
+
+            fork_io(producer).then_m(consumer)
+        });
+        assert_eq!(unsafe { io.run() }, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_modify_mvar() {
+        let io = new_mvar(10).chain_m(|mvar| {
+            let failing = modify_mvar_(mvar.clone(), |_: usize| throw(ErrorCall::new("modify")));
+            catch(failing, |_: ErrorCall| IO::return_m(()))
+                .then_m(modify_mvar(mvar.clone(), |n| IO::return_m((n + 1, n * 2))))
+                .chain_m(move |doubled| {
+                    with_mvar(mvar.clone(), move |n| IO::return_m((doubled, n))).chain_m({
+                        let mvar = mvar.clone();
+                        move |out| is_empty_mvar(mvar.clone()).fmap(move |empty| (out, empty))
+                    })
+                })
+        });
+        assert_eq!(unsafe { io.run() }, ((20, 11), false));
+    }
+}
@@ -0,0 +1,679 @@
+//! Software transactional memory.
+//!
+//! Based on [Control.Monad.STM](https://hackage.haskell.org/package/stm/docs/Control-Monad-STM.html)
+//! and [Control.Concurrent.STM.TVar](https://hackage.haskell.org/package/stm/docs/Control-Concurrent-STM-TVar.html).
+//!
+//! A computation of type [`STM<A>`] reads and writes shared [`TVar`]s,
+//! and is run as a single atomic transaction by [`atomically`].
+//! Other threads never observe a transaction half-way through,
+//! and a transaction never observes the effects of another half-way through.
+//!
+//! ```
+//! # use glasgae::{base::control::concurrent::{stm::*, *}, prelude::*};
+//! let io = new_tvar_io(0).chain_m(|counter| {
+//!     let increment = atomically(modify_tvar(counter.clone(), |n| n + 1));
+//!     let wait_for_ten = atomically(read_tvar(counter.clone()).chain_m(|n| check(n >= 10)));
+//!
+//!     (0..10)
+//!         .map(|_| fork_io(increment.clone()))
+//!         .fold(IO::return_m(()), |acc, next| acc.then_m(next).fmap(|_| ()))
+//!         .then_m(wait_for_ten)
+//!         .then_m(read_tvar_io(counter))
+//! });
+//!
+//! assert_eq!(unsafe { io.run() }, 10);
+//! ```
+//!
+//! # Implementation
+//!
+//! Transactions run optimistically against a thread-local log,
+//! and are validated against a global version clock before their writes are committed.
+//! A transaction that reads a [`TVar`] written since it started is restarted immediately,
+//! so it never acts upon an inconsistent view of memory.
+//!
+//! A transaction that calls [`retry`] blocks until another transaction
+//! commits a write to one of the [`TVar`]s it has read, then runs again.
+//! Blocking is interruptible by [`kill_thread`](super::kill_thread).
+
+mod tchan;
+mod tmvar;
+mod tqueue;
+
+pub use tchan::*;
+pub use tmvar::*;
+pub use tqueue::*;
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::HashMap,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use crate::{
+    base::control::exception::{throw, Exception, SomeException},
+    derive_pointed, derive_with_pointed,
+    prelude::*,
+};
+
+use super::{wait_until, Interrupt};
+
+/// A computation that runs as part of an atomic transaction.
+///
+/// Use [`atomically`] to run it.
+#[derive(Clone)]
+pub struct STM<A>(IO<A>)
+where
+    A: Term;
+
+impl<A> STM<A>
+where
+    A: Term,
+{
+    fn new(f: impl NullaryT<A>) -> Self {
+        STM(IO::new(f))
+    }
+
+    /// Run the computation against the log of the current transaction.
+    fn run(self) -> A {
+        unsafe { self.0.run() }
+    }
+}
+
+derive_pointed!(STM<(A)>);
+derive_with_pointed!(STM<(A)>);
+
+impl<A, B> Functor<B> for STM<A>
+where
+    A: Term,
+    B: Term,
+{
+    type Mapped = STM<B>;
+
+    fn fmap(self, f: impl FunctionT<A, B>) -> STM<B> {
+        STM(self.0.fmap(f))
+    }
+}
+
+impl<A> PureA for STM<A>
+where
+    A: Term,
+{
+    fn pure_a(t: Self::Pointed) -> Self {
+        STM(IO::pure_a(t))
+    }
+}
+
+impl<F, A, B> AppA<A, B> for STM<F>
+where
+    F: Term + FunctionT<A, B>,
+    A: Term,
+    B: Term,
+{
+    type WithA = STM<A>;
+    type WithB = STM<B>;
+
+    fn app_a(self, a: STM<A>) -> STM<B> {
+        STM(self.0.app_a(a.0))
+    }
+}
+
+impl<A> ReturnM for STM<A> where A: Term {}
+
+impl<A, B> ChainM<B> for STM<A>
+where
+    A: Term,
+    B: Term,
+{
+    type Chained = STM<B>;
+
+    fn chain_m(self, f: impl FunctionT<A, STM<B>>) -> STM<B> {
+        let f = f.to_function();
+        STM(self.0.chain_m(move |a| f(a).0))
+    }
+}
+
+/// Unwinds out of a transaction that called [`retry`].
+struct Retry;
+
+/// Unwinds out of a transaction that observed an inconsistent view of memory.
+struct Restart;
+
+/// The global version clock.
+///
+/// Incremented by every transaction that commits a write,
+/// and notified so that transactions blocked in [`retry`] can check their reads.
+struct Clock {
+    version: Mutex<u64>,
+    committed: Condvar,
+}
+
+static CLOCK: Clock = Clock {
+    version: Mutex::new(0),
+    committed: Condvar::new(),
+};
+
+impl Clock {
+    fn lock(&self) -> MutexGuard<'_, u64> {
+        self.version.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+struct ClockInterrupt;
+
+impl Interrupt for ClockInterrupt {
+    fn interrupt(&self) {
+        let _guard = CLOCK.lock();
+        CLOCK.committed.notify_all()
+    }
+}
+
+/// A type-erased [`TVar`], as stored in a transaction log.
+trait DynTVar: Send + Sync {
+    fn version(&self) -> u64;
+    fn commit(&self, value: &(dyn Any + Send + Sync), version: u64);
+}
+
+/// A value written by a transaction, to be committed to a [`TVar`].
+type Value = Arc<dyn Any + Send + Sync>;
+
+/// The reads and writes of a running transaction.
+#[derive(Clone)]
+struct TLog {
+    /// The value of the global clock when the transaction started.
+    read_version: u64,
+    reads: HashMap<usize, (Arc<dyn DynTVar>, u64)>,
+    writes: HashMap<usize, (Arc<dyn DynTVar>, Value)>,
+}
+
+impl TLog {
+    /// Returns `true` if none of the [`TVar`]s read by the transaction have since been written.
+    fn is_valid(&self) -> bool {
+        self.reads
+            .values()
+            .all(|(tvar, version)| tvar.version() == *version)
+    }
+}
+
+thread_local! {
+    static LOG: RefCell<Option<TLog>> = const { RefCell::new(None) };
+}
+
+/// Run `f` against the log of the current transaction.
+fn with_log<T>(f: impl FnOnce(&mut TLog) -> T) -> T {
+    LOG.with(|log| {
+        f(log
+            .borrow_mut()
+            .as_mut()
+            .expect("STM computations can only be run by atomically"))
+    })
+}
+
+/// Clears the log of the current thread when dropped.
+struct LogGuard;
+
+impl Drop for LogGuard {
+    fn drop(&mut self) {
+        LOG.with(|log| log.borrow_mut().take());
+    }
+}
+
+/// Perform a series of [`STM`] actions atomically.
+///
+/// Panics if called from within another transaction.
+pub fn atomically<A>(stm: STM<A>) -> IO<A>
+where
+    A: Term,
+{
+    IO::new(move || loop {
+        let read_version = *CLOCK.lock();
+        LOG.with(|log| {
+            let mut log = log.borrow_mut();
+            assert!(log.is_none(), "atomically cannot be nested");
+            *log = Some(TLog {
+                read_version,
+                reads: HashMap::new(),
+                writes: HashMap::new(),
+            });
+        });
+
+        let guard = LogGuard;
+        let result = catch_unwind(AssertUnwindSafe(|| stm.clone().run()));
+        let log = with_log(|log| log.clone());
+        drop(guard);
+
+        match result {
+            Ok(a) => {
+                let mut version = CLOCK.lock();
+                if !log.is_valid() {
+                    continue;
+                }
+
+                if !log.writes.is_empty() {
+                    *version += 1;
+                    for (tvar, value) in log.writes.values() {
+                        tvar.commit(value.as_ref(), *version);
+                    }
+                    CLOCK.committed.notify_all();
+                }
+
+                return a;
+            }
+            Err(payload) if payload.is::<Restart>() => continue,
+            Err(payload) if payload.is::<Retry>() => {
+                let _version = wait_until(
+                    Arc::new(ClockInterrupt),
+                    &CLOCK.version,
+                    &CLOCK.committed,
+                    |_| !log.is_valid(),
+                );
+            }
+            Err(payload) => {
+                // An exception raised from an inconsistent view of memory is spurious.
+                let _version = CLOCK.lock();
+                if log.is_valid() {
+                    resume_unwind(payload)
+                }
+            }
+        }
+    })
+}
+
+/// Abandon the current transaction, and run it again once
+/// one of the [`TVar`]s it has read is written by another transaction.
+pub fn retry<A>() -> STM<A>
+where
+    A: Term,
+{
+    STM::new(|| resume_unwind(Box::new(Retry)))
+}
+
+/// Run `a`, running `b` instead if `a` calls [`retry`].
+///
+/// The writes made by `a` are discarded before `b` runs.
+/// If `b` also calls [`retry`], so does the whole computation.
+pub fn or_else<A>(a: STM<A>, b: STM<A>) -> STM<A>
+where
+    A: Term,
+{
+    STM::new(move || {
+        let writes = with_log(|log| log.writes.clone());
+        match catch_unwind(AssertUnwindSafe(|| a.run())) {
+            Ok(a) => a,
+            Err(payload) if payload.is::<Retry>() => {
+                // Keep the reads made by `a`, so that the transaction
+                // is woken if they change.
+                with_log(|log| log.writes = writes);
+                b.run()
+            }
+            Err(payload) => resume_unwind(payload),
+        }
+    })
+}
+
+/// [`retry`] unless `b` is `true`.
+pub fn check(b: bool) -> STM<()> {
+    if b {
+        STM::return_m(())
+    } else {
+        retry()
+    }
+}
+
+/// Throw an exception from within a transaction.
+///
+/// The writes made by the transaction are discarded,
+/// and the exception is propagated out of [`atomically`].
+pub fn throw_stm<A, E>(e: E) -> STM<A>
+where
+    A: Term,
+    E: Exception,
+{
+    STM(throw(e))
+}
+
+/// Run `stm`, handling exceptions of type `E` with `handler`.
+///
+/// The writes made by `stm` are discarded before `handler` runs.
+pub fn catch_stm<A, E>(stm: STM<A>, handler: impl FunctionT<E, STM<A>>) -> STM<A>
+where
+    A: Term,
+    E: Exception,
+{
+    let handler = handler.to_function();
+    STM::new(move || {
+        let writes = with_log(|log| log.writes.clone());
+        match catch_unwind(AssertUnwindSafe(|| stm.run())) {
+            Ok(a) => a,
+            Err(payload) if payload.is::<Retry>() || payload.is::<Restart>() => {
+                resume_unwind(payload)
+            }
+            Err(payload) => match E::from_exception(SomeException::from_panic(payload)) {
+                Ok(e) => {
+                    with_log(|log| log.writes = writes);
+                    handler(e).run()
+                }
+                Err(e) => e.rethrow(),
+            },
+        }
+    })
+}
+
+struct TVarCell<T> {
+    value: T,
+    version: u64,
+}
+
+struct TVarState<T> {
+    id: usize,
+    cell: Mutex<TVarCell<T>>,
+}
+
+impl<T> TVarState<T> {
+    fn lock(&self) -> MutexGuard<'_, TVarCell<T>> {
+        self.cell.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> DynTVar for TVarState<T>
+where
+    T: Term,
+{
+    fn version(&self) -> u64 {
+        self.lock().version
+    }
+
+    fn commit(&self, value: &(dyn Any + Send + Sync), version: u64) {
+        let mut cell = self.lock();
+        cell.value = value
+            .downcast_ref::<T>()
+            .expect("TVar written with its own type")
+            .clone();
+        cell.version = version;
+    }
+}
+
+/// A shared variable that can be read and written by [`STM`] transactions.
+///
+/// Clones refer to the same variable.
+pub struct TVar<T>(Arc<TVarState<T>>);
+
+impl<T> Clone for TVar<T> {
+    fn clone(&self) -> Self {
+        TVar(self.0.clone())
+    }
+}
+
+/// Two `TVar`s are equal if they refer to the same variable.
+impl<T> PartialEq for TVar<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T> Eq for TVar<T> {}
+
+impl<T> std::fmt::Debug for TVar<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TVar").field(&self.0.id).finish()
+    }
+}
+
+impl<T> TVar<T>
+where
+    T: Term,
+{
+    fn new(t: T) -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        TVar(Arc::new(TVarState {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            cell: Mutex::new(TVarCell {
+                value: t,
+                version: 0,
+            }),
+        }))
+    }
+}
+
+/// Build a new [`TVar`] within a transaction.
+pub fn new_tvar<T>(t: T) -> STM<TVar<T>>
+where
+    T: Term,
+{
+    STM::new(move || TVar::new(t))
+}
+
+/// Build a new [`TVar`] outside of a transaction.
+pub fn new_tvar_io<T>(t: T) -> IO<TVar<T>>
+where
+    T: Term,
+{
+    IO::new(move || TVar::new(t))
+}
+
+/// Read the value of a [`TVar`].
+pub fn read_tvar<T>(tvar: TVar<T>) -> STM<T>
+where
+    T: Term,
+{
+    STM::new(move || {
+        let read = with_log(|log| {
+            if let Some((_, value)) = log.writes.get(&tvar.0.id) {
+                return Some(value.downcast_ref::<T>().unwrap().clone());
+            }
+
+            let cell = tvar.0.lock();
+            if cell.version > log.read_version {
+                return None;
+            }
+
+            log.reads.insert(tvar.0.id, (tvar.0.clone(), cell.version));
+            Some(cell.value.clone())
+        });
+
+        read.unwrap_or_else(|| resume_unwind(Box::new(Restart)))
+    })
+}
+
+/// Read the value of a [`TVar`] outside of a transaction.
+///
+/// Equivalent to `atomically(read_tvar(tvar))`, but faster.
+pub fn read_tvar_io<T>(tvar: TVar<T>) -> IO<T>
+where
+    T: Term,
+{
+    IO::new(move || tvar.0.lock().value.clone())
+}
+
+/// Write a new value into a [`TVar`].
+pub fn write_tvar<T>(tvar: TVar<T>, t: T) -> STM<()>
+where
+    T: Term,
+{
+    STM::new(move || {
+        with_log(|log| {
+            log.writes.insert(tvar.0.id, (tvar.0.clone(), Arc::new(t)));
+        })
+    })
+}
+
+/// Mutate the contents of a [`TVar`].
+pub fn modify_tvar<T>(tvar: TVar<T>, f: impl FunctionT<T, T>) -> STM<()>
+where
+    T: Term,
+{
+    let f = f.to_function();
+    read_tvar(tvar.clone()).chain_m(move |t| write_tvar(tvar.clone(), f(t)))
+}
+
+/// Replace the contents of a [`TVar`], returning the old contents.
+pub fn swap_tvar<T>(tvar: TVar<T>, new: T) -> STM<T>
+where
+    T: Term,
+{
+    read_tvar(tvar.clone())
+        .chain_m(move |old| write_tvar(tvar.clone(), new.clone()).then_m(STM::return_m(old)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::base::control::{
+        concurrent::{fork_io, new_empty_mvar, put_mvar, take_mvar},
+        exception::{catch, ErrorCall},
+    };
+
+    use super::*;
+
+    /// Run `n` threads concurrently, and wait for them all to finish.
+    fn concurrently_n(n: usize, thread: impl Fn(usize) -> IO<()>) -> IO<()> {
+        let threads: Vec<IO<()>> = (0..n).map(thread).collect();
+        new_empty_mvar().chain_m(move |done| {
+            let fork = threads
+                .iter()
+                .map(|io| fork_io(io.clone().then_m(put_mvar(done.clone(), ()))))
+                .fold(IO::return_m(()), |acc, next| acc.then_m(next).fmap(|_| ()));
+            let join = (0..n).fold(IO::return_m(()), |acc, _| {
+                acc.then_m(take_mvar(done.clone()))
+            });
+            fork.then_m(join)
+        })
+    }
+
+    fn transfer(from: TVar<i64>, to: TVar<i64>, amount: i64) -> STM<()> {
+        read_tvar(from.clone()).chain_m(move |balance| {
+            check(balance >= amount)
+                .then_m(write_tvar(from.clone(), balance - amount))
+                .then_m(modify_tvar(to.clone(), move |b| b + amount))
+        })
+    }
+
+    #[test]
+    fn test_bank_transfer() {
+        let accounts: Vec<TVar<i64>> = (0..5).map(|_| unsafe { new_tvar_io(100).run() }).collect();
+
+        let io = concurrently_n(8, |t| {
+            let accounts = accounts.clone();
+            (0..200).fold(IO::return_m(()), move |acc, i| {
+                // Transfer between pseudo-randomly chosen accounts,
+                // skipping transfers that the source account cannot cover.
+                let seed = t * 7919 + i * 104729;
+                let from = accounts[seed % 5].clone();
+                let to = accounts[(seed / 5 + 1 + seed % 5) % 5].clone();
+                let amount = (seed % 37) as i64;
+                acc.then_m(atomically(or_else(
+                    transfer(from, to, amount),
+                    STM::return_m(()),
+                )))
+            })
+        });
+        unsafe { io.run() };
+
+        let balances: Vec<i64> = accounts
+            .into_iter()
+            .map(|account| unsafe { read_tvar_io(account).run() })
+            .collect();
+        assert_eq!(balances.iter().sum::<i64>(), 500);
+        assert!(balances.iter().all(|b| *b >= 0));
+    }
+
+    #[test]
+    fn test_retry_blocks() {
+        // The transfer blocks until the deposit makes it possible.
+        let io = new_tvar_io(0).chain_m(|from| {
+            new_tvar_io(0).chain_m(move |to| {
+                let from = from.clone();
+                new_empty_mvar().chain_m(move |done| {
+                    let transfer = atomically(transfer(from.clone(), to.clone(), 50))
+                        .then_m(put_mvar(done.clone(), ()));
+                    let to = to.clone();
+                    fork_io(transfer)
+                        .then_m(atomically(write_tvar(from.clone(), 80)))
+                        .then_m(take_mvar(done.clone()))
+                        .then_m(atomically(
+                            read_tvar(from.clone())
+                                .chain_m(move |a| read_tvar(to.clone()).fmap(move |b| (a, b))),
+                        ))
+                })
+            })
+        });
+        assert_eq!(unsafe { io.run() }, (30, 50));
+    }
+
+    #[test]
+    fn test_or_else_discards_writes() {
+        let io = new_tvar_io(1).chain_m(|tvar| {
+            let first = write_tvar(tvar.clone(), 2).then_m(retry());
+            let second = read_tvar(tvar.clone()).fmap(|n| n * 10);
+            atomically(or_else(first, second))
+        });
+        assert_eq!(unsafe { io.run() }, 10);
+    }
+
+    #[test]
+    fn test_exceptions() {
+        let io = new_tvar_io(1).chain_m(|tvar| {
+            let caught = catch_stm(
+                write_tvar(tvar.clone(), 2).then_m(throw_stm(ErrorCall::new("caught"))),
+                |e: ErrorCall| STM::return_m(e.0),
+            );
+            let thrown = write_tvar(tvar.clone(), 3).then_m(throw_stm(ErrorCall::new("thrown")));
+
+            atomically(caught).chain_m(move |caught| {
+                catch(atomically(thrown.clone()), |e: ErrorCall| IO::return_m(e.0)).chain_m({
+                    let tvar = tvar.clone();
+                    move |thrown| {
+                        read_tvar_io(tvar.clone()).fmap(move |n| (caught.clone(), thrown, n))
+                    }
+                })
+            })
+        });
+        assert_eq!(
+            unsafe { io.run() },
+            ("caught".to_string(), "thrown".to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn test_dining_philosophers() {
+        const PHILOSOPHERS: usize = 5;
+        const MEALS: usize = 100;
+
+        let forks: Vec<TMVar<()>> = (0..PHILOSOPHERS)
+            .map(|_| unsafe { new_tmvar_io(()).run() })
+            .collect();
+        let meals: Vec<TVar<usize>> = (0..PHILOSOPHERS)
+            .map(|_| unsafe { new_tvar_io(0).run() })
+            .collect();
+
+        let io = concurrently_n(PHILOSOPHERS, |p| {
+            let left = forks[p].clone();
+            let right = forks[(p + 1) % PHILOSOPHERS].clone();
+            let eaten = meals[p].clone();
+
+            // Picking up both forks in one transaction means
+            // no philosopher ever holds a single fork, so there is no deadlock.
+            let pick_up = take_tmvar(left.clone()).then_m(take_tmvar(right.clone()));
+            let put_down = put_tmvar(left, ()).then_m(put_tmvar(right, ()));
+            let eat = modify_tvar(eaten, |n| n + 1);
+
+            (0..MEALS).fold(IO::return_m(()), move |acc, _| {
+                acc.then_m(atomically(pick_up.clone()))
+                    .then_m(atomically(eat.clone()))
+                    .then_m(atomically(put_down.clone()))
+            })
+        });
+        unsafe { io.run() };
+
+        for tvar in meals {
+            assert_eq!(unsafe { read_tvar_io(tvar).run() }, MEALS);
+        }
+        for fork in forks {
+            assert!(!unsafe { atomically(is_empty_tmvar(fork)).run() });
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Transactional unbounded channels.
+//!
+//! Based on [Control.Concurrent.STM.TChan](https://hackage.haskell.org/package/stm/docs/Control-Concurrent-STM-TChan.html).
+
+use crate::prelude::*;
+
+use super::{new_tvar, new_tvar_io, read_tvar, retry, write_tvar, TVar, STM};
+
+/// The position in a [`TChan`] at which the next item will be written.
+type TVarList<T> = TVar<TList<T>>;
+
+#[derive(Clone)]
+enum TList<T> {
+    TNil,
+    TCons(T, TVarList<T>),
+}
+
+use TList::*;
+
+/// An unbounded FIFO channel.
+///
+/// Clones refer to the same channel, and compete to read its items;
+/// use [`dup_tchan`] to receive a copy of every item instead.
+pub struct TChan<T> {
+    read: TVar<TVarList<T>>,
+    write: TVar<TVarList<T>>,
+}
+
+impl<T> Clone for TChan<T> {
+    fn clone(&self) -> Self {
+        TChan {
+            read: self.read.clone(),
+            write: self.write.clone(),
+        }
+    }
+}
+
+/// Two `TChan`s are equal if they read from the same position of the same channel.
+impl<T> PartialEq for TChan<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.read == other.read
+    }
+}
+
+impl<T> Eq for TChan<T> {}
+
+impl<T> std::fmt::Debug for TChan<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TChan").field(&self.read).finish()
+    }
+}
+
+/// Build a new, empty [`TChan`].
+pub fn new_tchan<T>() -> STM<TChan<T>>
+where
+    T: Term,
+{
+    new_tvar(TNil).chain_m(|hole| {
+        new_tvar(hole.clone()).chain_m(move |read| {
+            new_tvar(hole.clone()).fmap(move |write| TChan {
+                read: read.clone(),
+                write,
+            })
+        })
+    })
+}
+
+/// Build a new, empty [`TChan`] outside of a transaction.
+pub fn new_tchan_io<T>() -> IO<TChan<T>>
+where
+    T: Term,
+{
+    new_tvar_io(TNil).chain_m(|hole| {
+        new_tvar_io(hole.clone()).chain_m(move |read| {
+            new_tvar_io(hole.clone()).fmap(move |write| TChan {
+                read: read.clone(),
+                write,
+            })
+        })
+    })
+}
+
+/// Build a new [`TChan`] that is only ever written to.
+///
+/// Items are only retained for channels created from it with [`dup_tchan`],
+/// and reading from it directly retries forever.
+pub fn new_broadcast_tchan<T>() -> STM<TChan<T>>
+where
+    T: Term,
+{
+    new_tvar(TNil).chain_m(|write_hole| {
+        new_tvar(TNil).chain_m(move |read_hole| {
+            let write_hole = write_hole.clone();
+            new_tvar(read_hole).chain_m(move |read| {
+                new_tvar(write_hole.clone()).fmap(move |write| TChan {
+                    read: read.clone(),
+                    write,
+                })
+            })
+        })
+    })
+}
+
+/// Write an item to a [`TChan`].
+pub fn write_tchan<T>(chan: TChan<T>, t: T) -> STM<()>
+where
+    T: Term,
+{
+    read_tvar(chan.write.clone()).chain_m(move |list_end| {
+        let write = chan.write.clone();
+        let t = t.clone();
+        new_tvar(TNil).chain_m(move |new_list_end| {
+            write_tvar(list_end.clone(), TCons(t.clone(), new_list_end.clone()))
+                .then_m(write_tvar(write.clone(), new_list_end))
+        })
+    })
+}
+
+/// Read the next item from a [`TChan`].
+///
+/// Retries while the channel is empty.
+pub fn read_tchan<T>(chan: TChan<T>) -> STM<T>
+where
+    T: Term,
+{
+    try_read_tchan(chan).chain_m(|t| match t {
+        Some(t) => STM::return_m(t),
+        None => retry(),
+    })
+}
+
+/// Read the next item from a [`TChan`], if it is not empty.
+pub fn try_read_tchan<T>(chan: TChan<T>) -> STM<Option<T>>
+where
+    T: Term,
+{
+    read_tvar(chan.read.clone()).chain_m(move |list_head| {
+        let read = chan.read.clone();
+        read_tvar(list_head).chain_m(move |head| match head {
+            TNil => STM::return_m(None),
+            TCons(t, tail) => write_tvar(read.clone(), tail).then_m(STM::return_m(Some(t))),
+        })
+    })
+}
+
+/// Read the next item from a [`TChan`] without removing it.
+///
+/// Retries while the channel is empty.
+pub fn peek_tchan<T>(chan: TChan<T>) -> STM<T>
+where
+    T: Term,
+{
+    read_tvar(chan.read)
+        .chain_m(read_tvar)
+        .chain_m(|head| match head {
+            TNil => retry(),
+            TCons(t, _) => STM::return_m(t),
+        })
+}
+
+/// Duplicate a [`TChan`].
+///
+/// The new channel starts empty, and receives every item written
+/// to either channel from then on.
+pub fn dup_tchan<T>(chan: TChan<T>) -> STM<TChan<T>>
+where
+    T: Term,
+{
+    read_tvar(chan.write.clone()).chain_m(move |hole| {
+        let write = chan.write.clone();
+        new_tvar(hole).fmap(move |read| TChan {
+            read,
+            write: write.clone(),
+        })
+    })
+}
+
+/// Returns `true` if a [`TChan`] is empty.
+pub fn is_empty_tchan<T>(chan: TChan<T>) -> STM<bool>
+where
+    T: Term,
+{
+    read_tvar(chan.read)
+        .chain_m(read_tvar)
+        .fmap(|head| matches!(head, TNil))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::base::control::concurrent::{
+        fork_io,
+        stm::{atomically, or_else},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_tchan() {
+        let io = atomically(new_broadcast_tchan()).chain_m(|broadcast| {
+            atomically(dup_tchan(broadcast.clone())).chain_m(move |listener| {
+                let broadcast = broadcast.clone();
+                let listen = (0..3).fold(IO::return_m(vec![]), move |acc, _| {
+                    let listener = listener.clone();
+                    acc.chain_m(move |mut received: Vec<usize>| {
+                        atomically(read_tchan(listener.clone())).fmap(move |t| {
+                            received.push(t);
+                            received.clone()
+                        })
+                    })
+                });
+                let broadcast_all = (0..3).fold(STM::return_m(()), {
+                    let broadcast = broadcast.clone();
+                    move |acc, i| acc.then_m(write_tchan(broadcast.clone(), i))
+                });
+                fork_io(atomically(broadcast_all))
+                    .then_m(listen)
+                    .chain_m(move |received| {
+                        atomically(or_else(
+                            read_tchan(broadcast.clone()).fmap(Some),
+                            STM::return_m(None),
+                        ))
+                        .fmap(move |unread| (received.clone(), unread))
+                    })
+            })
+        });
+        assert_eq!(unsafe { io.run() }, (vec![0, 1, 2], None));
+    }
+
+    #[test]
+    fn test_peek_tchan() {
+        let io = new_tchan_io().chain_m(|chan| {
+            atomically(
+                write_tchan(chan.clone(), 'a')
+                    .then_m(peek_tchan(chan.clone()))
+                    .chain_m(move |peeked| {
+                        let chan = chan.clone();
+                        read_tchan(chan.clone()).chain_m(move |read| {
+                            is_empty_tchan(chan.clone()).fmap(move |empty| (peeked, read, empty))
+                        })
+                    }),
+            )
+        });
+        assert_eq!(unsafe { io.run() }, ('a', 'a', true));
+    }
+}
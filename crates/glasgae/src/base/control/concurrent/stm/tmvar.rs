@@ -0,0 +1,146 @@
+//! Transactional synchronising variables.
+//!
+//! Based on [Control.Concurrent.STM.TMVar](https://hackage.haskell.org/package/stm/docs/Control-Concurrent-STM-TMVar.html).
+
+use crate::prelude::*;
+
+use super::{new_tvar, new_tvar_io, read_tvar, retry, write_tvar, TVar, STM};
+
+/// The [`STM`] counterpart of an [`MVar`](crate::base::control::concurrent::MVar),
+/// which is either empty or full.
+///
+/// Operations that would block instead [`retry`].
+/// Clones refer to the same variable.
+pub struct TMVar<T>(TVar<Option<T>>);
+
+impl<T> Clone for TMVar<T> {
+    fn clone(&self) -> Self {
+        TMVar(self.0.clone())
+    }
+}
+
+/// Two `TMVar`s are equal if they refer to the same variable.
+impl<T> PartialEq for TMVar<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Eq for TMVar<T> {}
+
+impl<T> std::fmt::Debug for TMVar<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TMVar").field(&self.0).finish()
+    }
+}
+
+/// Build a new [`TMVar`] holding `t`.
+pub fn new_tmvar<T>(t: T) -> STM<TMVar<T>>
+where
+    T: Term,
+{
+    new_tvar(Some(t)).fmap(TMVar)
+}
+
+/// Build a new, empty [`TMVar`].
+pub fn new_empty_tmvar<T>() -> STM<TMVar<T>>
+where
+    T: Term,
+{
+    new_tvar(None).fmap(TMVar)
+}
+
+/// Build a new [`TMVar`] holding `t`, outside of a transaction.
+pub fn new_tmvar_io<T>(t: T) -> IO<TMVar<T>>
+where
+    T: Term,
+{
+    new_tvar_io(Some(t)).fmap(TMVar)
+}
+
+/// Build a new, empty [`TMVar`] outside of a transaction.
+pub fn new_empty_tmvar_io<T>() -> IO<TMVar<T>>
+where
+    T: Term,
+{
+    new_tvar_io(None).fmap(TMVar)
+}
+
+/// Take the contents of a [`TMVar`], leaving it empty.
+///
+/// Retries while the `TMVar` is empty.
+pub fn take_tmvar<T>(tmvar: TMVar<T>) -> STM<T>
+where
+    T: Term,
+{
+    read_tvar(tmvar.0.clone()).chain_m(move |t| match t {
+        Some(t) => write_tvar(tmvar.0.clone(), None).then_m(STM::return_m(t)),
+        None => retry(),
+    })
+}
+
+/// Put a value into a [`TMVar`].
+///
+/// Retries while the `TMVar` is full.
+pub fn put_tmvar<T>(tmvar: TMVar<T>, t: T) -> STM<()>
+where
+    T: Term,
+{
+    read_tvar(tmvar.0.clone()).chain_m(move |old| match old {
+        Some(_) => retry(),
+        None => write_tvar(tmvar.0.clone(), Some(t.clone())),
+    })
+}
+
+/// Read the contents of a [`TMVar`] without taking them.
+///
+/// Retries while the `TMVar` is empty.
+pub fn read_tmvar<T>(tmvar: TMVar<T>) -> STM<T>
+where
+    T: Term,
+{
+    read_tvar(tmvar.0).chain_m(|t| match t {
+        Some(t) => STM::return_m(t),
+        None => retry(),
+    })
+}
+
+/// Read the contents of a [`TMVar`] if it is full, without taking them.
+pub fn try_read_tmvar<T>(tmvar: TMVar<T>) -> STM<Option<T>>
+where
+    T: Term,
+{
+    read_tvar(tmvar.0)
+}
+
+/// Take the contents of a [`TMVar`] if it is full.
+pub fn try_take_tmvar<T>(tmvar: TMVar<T>) -> STM<Option<T>>
+where
+    T: Term,
+{
+    read_tvar(tmvar.0.clone()).chain_m(move |t| match t {
+        Some(t) => write_tvar(tmvar.0.clone(), None).then_m(STM::return_m(Some(t))),
+        None => STM::return_m(None),
+    })
+}
+
+/// Put a value into a [`TMVar`] if it is empty.
+///
+/// Returns `true` if the value was put.
+pub fn try_put_tmvar<T>(tmvar: TMVar<T>, t: T) -> STM<bool>
+where
+    T: Term,
+{
+    read_tvar(tmvar.0.clone()).chain_m(move |old| match old {
+        Some(_) => STM::return_m(false),
+        None => write_tvar(tmvar.0.clone(), Some(t.clone())).then_m(STM::return_m(true)),
+    })
+}
+
+/// Returns `true` if a [`TMVar`] is empty.
+pub fn is_empty_tmvar<T>(tmvar: TMVar<T>) -> STM<bool>
+where
+    T: Term,
+{
+    read_tvar(tmvar.0).fmap(|t| t.is_none())
+}
@@ -0,0 +1,171 @@
+//! Transactional FIFO queues.
+//!
+//! Based on [Control.Concurrent.STM.TQueue](https://hackage.haskell.org/package/stm/docs/Control-Concurrent-STM-TQueue.html).
+
+use crate::{base::data::collection::list::seq::Seq, prelude::*};
+
+use super::{new_tvar, new_tvar_io, read_tvar, retry, write_tvar, TVar, STM};
+
+/// An unbounded FIFO queue.
+///
+/// Items are pushed onto the back of a write sequence and popped from the front of a read sequence,
+/// which is refilled with the write sequence once it runs dry,
+/// so reads and writes rarely conflict with one another.
+/// Both are persistent [`Seq`]s, so each operation is amortized O(1).
+/// Clones refer to the same queue.
+pub struct TQueue<T> {
+    read: TVar<Seq<T>>,
+    write: TVar<Seq<T>>,
+}
+
+impl<T> Clone for TQueue<T> {
+    fn clone(&self) -> Self {
+        TQueue {
+            read: self.read.clone(),
+            write: self.write.clone(),
+        }
+    }
+}
+
+/// Two `TQueue`s are equal if they refer to the same queue.
+impl<T> PartialEq for TQueue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.read == other.read
+    }
+}
+
+impl<T> Eq for TQueue<T> {}
+
+impl<T> std::fmt::Debug for TQueue<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TQueue").field(&self.read).finish()
+    }
+}
+
+/// Build a new, empty [`TQueue`].
+pub fn new_tqueue<T>() -> STM<TQueue<T>>
+where
+    T: Term,
+{
+    new_tvar(Seq::new()).chain_m(|read| {
+        new_tvar(Seq::new()).fmap(move |write| TQueue {
+            read: read.clone(),
+            write,
+        })
+    })
+}
+
+/// Build a new, empty [`TQueue`] outside of a transaction.
+pub fn new_tqueue_io<T>() -> IO<TQueue<T>>
+where
+    T: Term,
+{
+    new_tvar_io(Seq::new()).chain_m(|read| {
+        new_tvar_io(Seq::new()).fmap(move |write| TQueue {
+            read: read.clone(),
+            write,
+        })
+    })
+}
+
+/// Push an item onto the back of a [`TQueue`].
+pub fn write_tqueue<T>(queue: TQueue<T>, t: T) -> STM<()>
+where
+    T: Term,
+{
+    read_tvar(queue.write.clone())
+        .chain_m(move |write: Seq<T>| write_tvar(queue.write.clone(), write.snoc(t.clone())))
+}
+
+/// Pop an item from the front of a [`TQueue`].
+///
+/// Retries while the queue is empty.
+pub fn read_tqueue<T>(queue: TQueue<T>) -> STM<T>
+where
+    T: Term,
+{
+    try_read_tqueue(queue).chain_m(|t| match t {
+        Some(t) => STM::return_m(t),
+        None => retry(),
+    })
+}
+
+/// Pop an item from the front of a [`TQueue`], if it is not empty.
+pub fn try_read_tqueue<T>(queue: TQueue<T>) -> STM<Option<T>>
+where
+    T: Term,
+{
+    read_tvar(queue.read.clone()).chain_m(move |read: Seq<T>| {
+        if let Some((t, read)) = read.uncons() {
+            return write_tvar(queue.read.clone(), read).then_m(STM::return_m(Some(t)));
+        }
+
+        let queue = queue.clone();
+        read_tvar(queue.write.clone()).chain_m(move |write: Seq<T>| match write.uncons() {
+            Some((t, read)) => write_tvar(queue.write.clone(), Seq::new())
+                .then_m(write_tvar(queue.read.clone(), read))
+                .then_m(STM::return_m(Some(t))),
+            None => STM::return_m(None),
+        })
+    })
+}
+
+/// Remove and return every item in a [`TQueue`], front first.
+pub fn flush_tqueue<T>(queue: TQueue<T>) -> STM<Vec<T>>
+where
+    T: Term,
+{
+    read_tvar(queue.read.clone()).chain_m(move |read: Seq<T>| {
+        let queue = queue.clone();
+        read_tvar(queue.write.clone()).chain_m(move |write: Seq<T>| {
+            let items = read.clone().concat(write).into_iter().collect::<Vec<_>>();
+            write_tvar(queue.read.clone(), Seq::new())
+                .then_m(write_tvar(queue.write.clone(), Seq::new()))
+                .then_m(STM::return_m(items))
+        })
+    })
+}
+
+/// Returns `true` if a [`TQueue`] is empty.
+pub fn is_empty_tqueue<T>(queue: TQueue<T>) -> STM<bool>
+where
+    T: Term,
+{
+    read_tvar(queue.read.clone()).chain_m(move |read: Seq<T>| {
+        if read.is_empty() {
+            read_tvar(queue.write.clone()).fmap(|write| write.is_empty())
+        } else {
+            STM::return_m(false)
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::base::control::concurrent::stm::atomically;
+
+    use super::*;
+
+    #[test]
+    fn test_tqueue() {
+        let io = new_tqueue_io().chain_m(|queue| {
+            let stm = write_tqueue(queue.clone(), 1)
+                .then_m(write_tqueue(queue.clone(), 2))
+                .then_m(read_tqueue(queue.clone()))
+                .chain_m({
+                    let queue = queue.clone();
+                    move |first| {
+                        let queue = queue.clone();
+                        write_tqueue(queue.clone(), 3)
+                            .then_m(flush_tqueue(queue.clone()))
+                            .chain_m(move |rest| {
+                                is_empty_tqueue(queue.clone())
+                                    .fmap(move |empty| (first, rest.clone(), empty))
+                            })
+                    }
+                });
+            atomically(stm)
+        });
+        assert_eq!(unsafe { io.run() }, (1, vec![2, 3], true));
+    }
+}
@@ -0,0 +1,503 @@
+//! Resource safety in the presence of exceptions.
+//!
+//! Based on the bracket family from
+//! [Control.Exception](https://hackage.haskell.org/package/base/docs/Control-Exception.html#g:13)
+//! and [Control.Monad.Catch](https://hackage.haskell.org/package/exceptions/docs/Control-Monad-Catch.html#t:MonadMask).
+//!
+//! The combinators here are generic over [`MonadBracket`] and [`MonadMask`],
+//! so they can be used from transformer stacks over [`IO`] as well as from [`IO`] itself:
+//!
+//! ```
+//! # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+//! # use glasgae::{base::control::exception::{bracket, catch, throw}, prelude::*};
+//! let released = Arc::new(AtomicUsize::new(0));
+//!
+//! let io = bracket(
+//!     IO::return_m(1),
+//!     {
+//!         let released = released.clone();
+//!         move |_| IO::new(move || released.fetch_add(1, Ordering::SeqCst))
+//!     },
+//!     |_: usize| throw::<usize, _>("oh no".to_string()),
+//! );
+//!
+//! assert_eq!(unsafe { catch(io, |_: String| IO::return_m(0)).run() }, 0);
+//! assert_eq!(released.load(Ordering::SeqCst), 1);
+//! ```
+//!
+//! # Masking
+//!
+//! Rust has no asynchronous exceptions, so the masking state has no effect on
+//! whether a panic can interrupt a computation.
+//! It is tracked per-thread so that [`mask`] and its [`Restore`] function compose
+//! the same way as their Haskell counterparts, and can be queried with [`get_masking_state`]
+//! by operations that choose to honour it.
+
+use std::{
+    cell::Cell,
+    panic::{catch_unwind, resume_unwind},
+};
+
+use crate::{
+    base::data::function::bifunction::BifunT, derive_pointed, derive_with_pointed, prelude::*,
+};
+
+use ExitCase::*;
+
+/// Describes how a computation guarded by [`MonadBracket::on_failure`] exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ExitCase<A> {
+    /// The computation produced a value.
+    ExitCaseSuccess(A),
+    /// The computation unwound with an exception.
+    ExitCaseException,
+    /// The computation short-circuited without a value,
+    /// such as a [`Left`] inside [`ExceptT`](crate::transformers::except::ExceptT).
+    ExitCaseAbort,
+}
+
+derive_pointed!(ExitCase<(A)>);
+derive_with_pointed!(ExitCase<(A)>);
+
+impl<A, B> Functor<B> for ExitCase<A>
+where
+    A: Term,
+    B: Term,
+{
+    type Mapped = ExitCase<B>;
+
+    fn fmap(self, f: impl FunctionT<A, B>) -> ExitCase<B> {
+        match self {
+            ExitCaseSuccess(a) => ExitCaseSuccess(f(a)),
+            ExitCaseException => ExitCaseException,
+            ExitCaseAbort => ExitCaseAbort,
+        }
+    }
+}
+
+/// Whether asynchronous interruption is currently permitted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MaskingState {
+    /// Interruption is permitted.
+    #[default]
+    Unmasked,
+    /// Interruption is only permitted inside interruptible operations.
+    MaskedInterruptible,
+    /// Interruption is not permitted.
+    MaskedUninterruptible,
+}
+
+thread_local! {
+    static MASKING_STATE: Cell<MaskingState> = const { Cell::new(MaskingState::Unmasked) };
+}
+
+/// Resets the masking state of the current thread when dropped,
+/// including during unwinding.
+struct MaskingGuard(MaskingState);
+
+impl MaskingGuard {
+    fn set(state: MaskingState) -> Self {
+        MaskingGuard(MASKING_STATE.with(|cell| cell.replace(state)))
+    }
+}
+
+impl Drop for MaskingGuard {
+    fn drop(&mut self) {
+        MASKING_STATE.with(|cell| cell.set(self.0))
+    }
+}
+
+/// Returns the [`MaskingState`] of the current thread.
+pub fn get_masking_state() -> IO<MaskingState> {
+    IO::new(|| MASKING_STATE.with(Cell::get))
+}
+
+/// Restores the masking state that was in effect outside of an enclosing [`mask`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Restore(MaskingState);
+
+impl Restore {
+    /// Run `m` with the masking state from outside the enclosing [`mask`].
+    pub fn restore<M>(self, m: M) -> M
+    where
+        M: MonadMask,
+    {
+        m.with_masking_state(self.0)
+    }
+}
+
+/// Monads which can run computations under a [`MaskingState`].
+pub trait MonadMask: Term {
+    /// Run the computation produced by `f` with the masking state raised to at least `state`.
+    ///
+    /// `f` receives a [`Restore`] that reinstates the masking state in effect beforehand.
+    fn mask_with(state: MaskingState, f: impl FunctionT<Restore, Self>) -> Self;
+
+    /// Run this computation with the masking state set to `state`.
+    fn with_masking_state(self, state: MaskingState) -> Self;
+}
+
+/// Monads which can run a cleanup action when a computation fails to produce a value.
+///
+/// `MC` is the type of the cleanup action.
+pub trait MonadBracket<MC: Term>: MonadMask + Pointed {
+    /// Run this computation; if it throws or aborts, run `cleanup` before propagating the failure.
+    ///
+    /// `cleanup` is only ever passed [`ExitCaseException`] or [`ExitCaseAbort`].
+    /// Its result is discarded.
+    fn on_failure(self, cleanup: impl FunctionT<ExitCase<Self::Pointed>, MC>) -> Self;
+}
+
+impl<A> MonadMask for IO<A>
+where
+    A: Term,
+{
+    fn mask_with(state: MaskingState, f: impl FunctionT<Restore, Self>) -> Self {
+        let f = f.to_function();
+        IO::new(move || {
+            let outer = MASKING_STATE.with(Cell::get);
+            let _guard = MaskingGuard::set(outer.max(state));
+            unsafe { f(Restore(outer)).run() }
+        })
+    }
+
+    fn with_masking_state(self, state: MaskingState) -> Self {
+        IO::new(move || {
+            let _guard = MaskingGuard::set(state);
+            unsafe { self.run() }
+        })
+    }
+}
+
+impl<A, C> MonadBracket<IO<C>> for IO<A>
+where
+    A: Term,
+    C: Term,
+{
+    fn on_failure(self, cleanup: impl FunctionT<ExitCase<A>, IO<C>>) -> Self {
+        let cleanup = cleanup.to_function();
+        IO::new(move || match catch_unwind(move || unsafe { self.run() }) {
+            Ok(a) => a,
+            Err(e) => {
+                unsafe { cleanup(ExitCaseException).run() };
+                resume_unwind(e)
+            }
+        })
+    }
+}
+
+/// Execute a computation with the masking state raised to [`MaskingState::MaskedInterruptible`].
+///
+/// The [`Restore`] passed to `f` can be used to run part of it with the outer state.
+pub fn mask<M>(f: impl FunctionT<Restore, M>) -> M
+where
+    M: MonadMask,
+{
+    M::mask_with(MaskingState::MaskedInterruptible, f)
+}
+
+/// Like [`mask`], but does not pass a [`Restore`] to the computation.
+pub fn mask_<M>(m: M) -> M
+where
+    M: MonadMask,
+{
+    mask(|_| m)
+}
+
+/// Like [`mask`], but raises the masking state to [`MaskingState::MaskedUninterruptible`].
+pub fn uninterruptible_mask<M>(f: impl FunctionT<Restore, M>) -> M
+where
+    M: MonadMask,
+{
+    M::mask_with(MaskingState::MaskedUninterruptible, f)
+}
+
+/// Like [`uninterruptible_mask`], but does not pass a [`Restore`] to the computation.
+pub fn uninterruptible_mask_<M>(m: M) -> M
+where
+    M: MonadMask,
+{
+    uninterruptible_mask(|_| m)
+}
+
+/// Acquire a resource, use it, and release it, passing the [`ExitCase`] of the use to the release action.
+///
+/// Acquisition and release run masked, while the use runs with the outer masking state.
+/// The release action runs however the use exits.
+pub fn general_bracket<MR, R, MB, B, MC>(
+    acquire: MR,
+    release: impl BifunT<R, ExitCase<B>, MC>,
+    use_: impl FunctionT<R, MB>,
+) -> MB
+where
+    MR: ChainM<B, Pointed = R, Chained = MB>,
+    MB: MonadBracket<MC> + Monad<B, Pointed = B, Chained = MB>,
+    MC: ChainM<B, Chained = MB>,
+    R: Term,
+    B: Term,
+{
+    let release = release.to_bifun();
+    let use_ = use_.to_function();
+    mask(move |restore| {
+        acquire.chain_m(move |r| {
+            restore
+                .restore(use_(r.clone()))
+                .on_failure({
+                    let release = release.clone();
+                    let r = r.clone();
+                    move |exit_case| release(r, exit_case)
+                })
+                .chain_m(move |b| {
+                    release(r, ExitCaseSuccess(b.clone())).chain_m(|_| ReturnM::return_m(b))
+                })
+        })
+    })
+}
+
+/// Acquire a resource, use it, and release it, even if the use fails.
+pub fn bracket<MR, R, MB, B, MC>(
+    acquire: MR,
+    release: impl FunctionT<R, MC>,
+    use_: impl FunctionT<R, MB>,
+) -> MB
+where
+    MR: ChainM<B, Pointed = R, Chained = MB>,
+    MB: MonadBracket<MC> + Monad<B, Pointed = B, Chained = MB>,
+    MC: ChainM<B, Chained = MB>,
+    R: Term,
+    B: Term,
+{
+    let release = release.to_function();
+    general_bracket(acquire, |r, _| release(r), use_)
+}
+
+/// A variant of [`bracket`] where the return value of the acquire action is not required.
+pub fn bracket_<MR, R, MB, B, MC>(acquire: MR, release: MC, use_: MB) -> MB
+where
+    MR: ChainM<B, Pointed = R, Chained = MB>,
+    MB: MonadBracket<MC> + Monad<B, Pointed = B, Chained = MB>,
+    MC: Term + ChainM<B, Chained = MB>,
+    R: Term,
+    B: Term,
+{
+    bracket(acquire, |_| release, |_| use_)
+}
+
+/// Like [`bracket`], but only runs the release action if the use fails.
+pub fn bracket_on_error<MR, R, MB, B, MC>(
+    acquire: MR,
+    release: impl FunctionT<R, MC>,
+    use_: impl FunctionT<R, MB>,
+) -> MB
+where
+    MR: ChainM<B, Pointed = R, Chained = MB>,
+    MB: MonadBracket<MC>,
+    MC: Term,
+    R: Term,
+    B: Term,
+{
+    let release = release.to_function();
+    let use_ = use_.to_function();
+    mask(move |restore| {
+        acquire.chain_m(move |r| {
+            restore
+                .restore(use_(r.clone()))
+                .on_failure(move |_| release(r))
+        })
+    })
+}
+
+/// Run `body`, then run `finalizer` whether or not `body` failed.
+pub fn finally<MB, B, MC>(body: MB, finalizer: MC) -> MB
+where
+    MB: MonadBracket<MC> + Monad<B, Pointed = B, Chained = MB>,
+    MC: Term + ChainM<B, Chained = MB>,
+    B: Term,
+{
+    mask(move |restore| {
+        restore
+            .restore(body)
+            .on_failure({
+                let finalizer = finalizer.clone();
+                move |_| finalizer
+            })
+            .chain_m(move |b| finalizer.chain_m(|_| ReturnM::return_m(b)))
+    })
+}
+
+/// Run `body`, running `what` only if `body` fails.
+///
+/// The failure is propagated once `what` has run.
+pub fn on_exception<MB, MC>(body: MB, what: MC) -> MB
+where
+    MB: MonadBracket<MC>,
+    MC: Term,
+{
+    body.on_failure(|_| what)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        base::control::exception::{catch, throw},
+        prelude::*,
+        transformers::{except::ExceptT, state::StateT},
+    };
+
+    use super::{
+        bracket, bracket_on_error, finally, get_masking_state, mask, uninterruptible_mask,
+        ExitCase::{self, *},
+        MaskingState::{self, *},
+    };
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    fn log(log: &Log, msg: &str) -> IO<()> {
+        let log = log.clone();
+        let msg = msg.to_string();
+        IO::new(move || log.lock().unwrap().push(msg))
+    }
+
+    fn entries(log: &Log) -> Vec<String> {
+        log.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn test_bracket_io() {
+        let l = Log::default();
+
+        let io = bracket(
+            log(&l, "acquire").then_m(IO::return_m(2)),
+            {
+                let l = l.clone();
+                move |r: usize| log(&l, &format!("release {r}"))
+            },
+            |r| IO::return_m(r * 10),
+        );
+        assert_eq!(unsafe { io.run() }, 20);
+        assert_eq!(entries(&l), vec!["acquire", "release 2"]);
+
+        let l = Log::default();
+        let io = bracket(
+            IO::return_m(3),
+            {
+                let l = l.clone();
+                move |r: usize| log(&l, &format!("release {r}"))
+            },
+            |_| throw::<usize, _>("use".to_string()),
+        );
+        let io = catch(io, |e: String| IO::return_m(e.len()));
+        assert_eq!(unsafe { io.run() }, 3);
+        assert_eq!(entries(&l), vec!["release 3"]);
+    }
+
+    #[test]
+    fn test_bracket_on_error_and_finally() {
+        let l = Log::default();
+        let io = bracket_on_error(
+            IO::return_m(()),
+            {
+                let l = l.clone();
+                move |_| log(&l, "cleanup")
+            },
+            |_| IO::return_m(1),
+        );
+        assert_eq!(unsafe { io.run() }, 1);
+        assert!(entries(&l).is_empty());
+
+        let io = finally(throw::<usize, _>("body".to_string()), log(&l, "finalizer"));
+        let io = catch(io, |_: String| IO::return_m(0));
+        assert_eq!(unsafe { io.run() }, 0);
+        assert_eq!(entries(&l), vec!["finalizer"]);
+    }
+
+    #[test]
+    fn test_bracket_state() {
+        let l = Log::default();
+
+        let release = {
+            let l = l.clone();
+            move |_: ()| {
+                StateT::lift_io(log(&l, "release")).then_m(
+                    StateT::<usize, IO<((), usize)>>::modify_m(|s| IO::return_m(s + 100)),
+                )
+            }
+        };
+
+        let m: StateT<usize, IO<(usize, usize)>> =
+            bracket(StateT::new(|s: usize| ((), s + 1)), release.clone(), |_| {
+                StateT::new(|s: usize| (s, s * 2))
+            });
+        assert_eq!(unsafe { m.run_t(1).run() }, (2, 104));
+
+        let m: StateT<usize, IO<(usize, usize)>> =
+            bracket(StateT::new(|s: usize| ((), s + 1)), release, |_| {
+                StateT::lift_io(throw::<usize, _>("use".to_string()))
+            });
+        let io = catch(m.run_t(1), |_: String| IO::return_m((0, 0)));
+        assert_eq!(unsafe { io.run() }, (0, 0));
+        assert_eq!(entries(&l), vec!["release", "release"]);
+    }
+
+    #[test]
+    fn test_bracket_except() {
+        type M<A> = ExceptT<IO<Either<String, A>>>;
+
+        let exits = Arc::new(Mutex::new(vec![]));
+        let release = {
+            let exits = exits.clone();
+            move |_: (), exit_case: ExitCase<usize>| -> M<()> {
+                ExceptT::lift_io(IO::new(move || exits.lock().unwrap().push(exit_case)))
+            }
+        };
+
+        let m: M<usize> =
+            super::general_bracket(M::return_m(()), release.clone(), |_| M::return_m(4));
+        assert_eq!(unsafe { m.run_t().run() }, Right(4));
+
+        let m: M<usize> = super::general_bracket(M::return_m(()), release, |_| {
+            ExceptT::new(Left("abort".to_string()))
+        });
+        assert_eq!(unsafe { m.run_t().run() }, Left("abort".to_string()));
+
+        assert_eq!(
+            exits.lock().unwrap().clone(),
+            vec![ExitCaseSuccess(4), ExitCaseAbort]
+        );
+    }
+
+    #[test]
+    fn test_mask() {
+        let states: IO<(MaskingState, MaskingState, MaskingState, MaskingState)> =
+            mask(|restore| {
+                get_masking_state().chain_m(move |masked| {
+                    restore
+                        .restore(get_masking_state())
+                        .chain_m(move |restored| {
+                            uninterruptible_mask(move |restore| {
+                                get_masking_state().chain_m(move |uninterruptible| {
+                                    restore.restore(get_masking_state()).fmap(
+                                        move |inner_restored| {
+                                            (masked, restored, uninterruptible, inner_restored)
+                                        },
+                                    )
+                                })
+                            })
+                        })
+                })
+            });
+
+        assert_eq!(
+            unsafe { states.run() },
+            (
+                MaskedInterruptible,
+                Unmasked,
+                MaskedUninterruptible,
+                MaskedInterruptible
+            )
+        );
+        assert_eq!(unsafe { get_masking_state().run() }, Unmasked);
+    }
+}
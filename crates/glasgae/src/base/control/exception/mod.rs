@@ -0,0 +1,442 @@
+//! Exceptions in the IO monad.
+//!
+//! Based on [Control.Exception](https://hackage.haskell.org/package/base/docs/Control-Exception.html).
+//!
+//! Exceptions are thrown as panics carrying a [`SomeException`] payload,
+//! and caught by unwinding back to the nearest enclosing handler of a matching type.
+//! Panics raised by other means, such as [`panic!()`], are caught as [`ErrorCall`]s.
+//!
+//! ```
+//! # use glasgae::{base::control::exception::*, prelude::*};
+//! let io = throw::<usize, _>(ErrorCall::new("oh no")).then_m(IO::return_m(1));
+//!
+//! let io = catches(
+//!     io,
+//!     vec![
+//!         Handler::new(|_: IOException| IO::return_m(2)),
+//!         Handler::new(|e: ErrorCall| IO::return_m(e.show().len())),
+//!     ],
+//! );
+//!
+//! assert_eq!(unsafe { io.run() }, 5);
+//! ```
+
+mod bracket;
+
+pub use bracket::*;
+
+use std::{
+    any::Any,
+    io::ErrorKind,
+    panic::{catch_unwind, resume_unwind},
+    path::{Path, PathBuf},
+};
+
+use crate::{base::data::term::TermBase, prelude::*};
+
+/// Any type that may be thrown as an exception.
+///
+/// The default implementations treat the type as a direct child of [`SomeException`].
+/// Types that want to catch several kinds of exception can override [`Exception::from_exception`].
+pub trait Exception: Term + Show {
+    /// Wrap the exception in a [`SomeException`].
+    fn to_exception(self) -> SomeException {
+        SomeException(Box::new(self))
+    }
+
+    /// Recover an exception of this type from a [`SomeException`],
+    /// handing it back unchanged if it does not match.
+    fn from_exception(e: SomeException) -> Result<Self, SomeException> {
+        e.downcast()
+    }
+
+    /// Render the exception in a human-friendly format.
+    fn display_exception(self) -> String {
+        self.show()
+    }
+}
+
+/// Object-safe view of an [`Exception`].
+trait DynException: TermBase {
+    fn clone_dyn(&self) -> Box<dyn DynException>;
+    fn show_dyn(&self) -> String;
+    fn display_dyn(&self) -> String;
+    fn as_any(&self) -> &dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<E> DynException for E
+where
+    E: Exception,
+{
+    fn clone_dyn(&self) -> Box<dyn DynException> {
+        Box::new(self.clone())
+    }
+
+    fn show_dyn(&self) -> String {
+        self.clone().show()
+    }
+
+    fn display_dyn(&self) -> String {
+        self.clone().display_exception()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// The SomeException type is the root of the exception type hierarchy.
+///
+/// When an exception of type e is thrown, behind the scenes it is encapsulated in a SomeException.
+pub struct SomeException(Box<dyn DynException>);
+
+impl SomeException {
+    /// Recover the concrete exception, handing `self` back if it is of a different type.
+    pub fn downcast<E>(self) -> Result<E, SomeException>
+    where
+        E: Exception,
+    {
+        if self.is::<E>() {
+            Ok(*self.0.into_any().downcast().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Returns `true` if the wrapped exception is of type `E`.
+    pub fn is<E>(&self) -> bool
+    where
+        E: Exception,
+    {
+        self.0.as_any().is::<E>()
+    }
+
+    /// Convert a panic payload into an exception.
+    ///
+    /// Payloads thrown by [`throw`] are returned as-is,
+    /// while string payloads from [`panic!()`] become [`ErrorCall`]s.
+    pub fn from_panic(payload: Box<dyn Any + Send>) -> SomeException {
+        let payload = match payload.downcast::<SomeException>() {
+            Ok(e) => return *e,
+            Err(payload) => payload,
+        };
+
+        let payload = match payload.downcast::<String>() {
+            Ok(s) => return ErrorCall(*s).to_exception(),
+            Err(payload) => payload,
+        };
+
+        match payload.downcast::<&'static str>() {
+            Ok(s) => ErrorCall::new(*s).to_exception(),
+            Err(_) => ErrorCall::new("Box<dyn Any>").to_exception(),
+        }
+    }
+
+    /// Resume unwinding with this exception as the payload.
+    pub fn rethrow(self) -> ! {
+        resume_unwind(Box::new(self))
+    }
+}
+
+impl Clone for SomeException {
+    fn clone(&self) -> Self {
+        SomeException(self.0.clone_dyn())
+    }
+}
+
+impl std::fmt::Debug for SomeException {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SomeException")
+            .field(&self.0.show_dyn())
+            .finish()
+    }
+}
+
+impl Show for SomeException {
+    fn show(self) -> String {
+        self.0.show_dyn()
+    }
+}
+
+impl Exception for SomeException {
+    fn to_exception(self) -> SomeException {
+        self
+    }
+
+    fn from_exception(e: SomeException) -> Result<Self, SomeException> {
+        Ok(e)
+    }
+
+    fn display_exception(self) -> String {
+        self.0.display_dyn()
+    }
+}
+
+/// Thrown by [`panic!()`] and friends.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ErrorCall(pub String);
+
+impl ErrorCall {
+    pub fn new(message: impl Into<String>) -> Self {
+        ErrorCall(message.into())
+    }
+}
+
+impl Show for ErrorCall {
+    fn show(self) -> String {
+        self.0
+    }
+}
+
+impl Exception for ErrorCall {}
+
+/// Catching a [`String`] also catches the message of an [`ErrorCall`].
+impl Exception for String {
+    fn from_exception(e: SomeException) -> Result<Self, SomeException> {
+        e.downcast::<String>()
+            .or_else(|e| e.downcast::<ErrorCall>().map(|ErrorCall(s)| s))
+    }
+}
+
+impl Exception for ErrorKind {}
+
+/// An I/O error, together with the operation and path that caused it.
+///
+/// Stands in for the unwind-unsafe [`std::io::Error`] type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IOException {
+    /// The category of the underlying error.
+    pub kind: ErrorKind,
+    /// The name of the operation that failed, such as `"read_file"`.
+    pub operation: String,
+    /// The path the operation was acting on, if any.
+    pub path: Option<PathBuf>,
+    /// The message of the underlying error.
+    pub description: String,
+}
+
+impl IOException {
+    /// Capture `error` as raised by `operation`.
+    pub fn new(operation: impl Into<String>, error: std::io::Error) -> Self {
+        IOException {
+            kind: error.kind(),
+            operation: operation.into(),
+            path: None,
+            description: error.to_string(),
+        }
+    }
+
+    /// Attach the path the failing operation was acting on.
+    pub fn with_path(self, path: impl AsRef<Path>) -> Self {
+        IOException {
+            path: Some(path.as_ref().to_path_buf()),
+            ..self
+        }
+    }
+
+    /// Returns a function capturing an error from `operation` on `path`,
+    /// suitable for use with [`Result::map_err`].
+    pub fn on_path(
+        operation: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> impl FnOnce(std::io::Error) -> Self {
+        let operation = operation.into();
+        let path = path.as_ref().to_path_buf();
+        move |error| IOException::new(operation, error).with_path(path)
+    }
+}
+
+impl Show for IOException {
+    fn show(self) -> String {
+        match self.path {
+            Some(path) => format!(
+                "{}: {}: {}",
+                path.display(),
+                self.operation,
+                self.description
+            ),
+            None => format!("{}: {}", self.operation, self.description),
+        }
+    }
+}
+
+impl Exception for IOException {}
+
+/// Thrown into a thread by another thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AsyncException {
+    /// Thrown by [`kill_thread`](crate::base::control::concurrent::kill_thread).
+    ThreadKilled,
+}
+
+impl Show for AsyncException {
+    fn show(self) -> String {
+        match self {
+            AsyncException::ThreadKilled => "thread killed".to_string(),
+        }
+    }
+}
+
+impl Exception for AsyncException {}
+
+/// Throw an exception inside the IO monad
+pub fn throw<T, E>(e: E) -> IO<T>
+where
+    T: Term,
+    E: Exception,
+{
+    IO::new(move || e.to_exception().rethrow())
+}
+
+/// Catch an exception inside the IO monad
+pub fn catch<E, T>(io: IO<T>, handler: impl FunctionT<E, IO<T>>) -> IO<T>
+where
+    T: Term,
+    E: Exception,
+{
+    let handler = handler.to_function();
+    IO::new(move || match catch_unwind(move || unsafe { io.run() }) {
+        Ok(t) => t,
+        Err(a) => match E::from_exception(SomeException::from_panic(a)) {
+            Ok(e) => unsafe { handler(e).run() },
+            Err(e) => e.rethrow(),
+        },
+    })
+}
+
+/// [`catch`] specialized to catch every exception.
+pub fn catch_any<T>(io: IO<T>, handler: impl FunctionT<SomeException, IO<T>>) -> IO<T>
+where
+    T: Term,
+{
+    catch(io, handler)
+}
+
+/// [`catch`] with the arguments swapped around.
+///
+/// Useful in situations where the code for the handler is shorter.
+pub fn handle<E, T>(handler: impl FunctionT<E, IO<T>>, io: IO<T>) -> IO<T>
+where
+    T: Term,
+    E: Exception,
+{
+    catch(io, handler)
+}
+
+/// A handler for one type of exception, for use with [`catches`].
+#[derive(Clone)]
+pub struct Handler<T>(Function<SomeException, Result<IO<T>, SomeException>>)
+where
+    T: Term;
+
+impl<T> Handler<T>
+where
+    T: Term,
+{
+    pub fn new<E>(handler: impl FunctionT<E, IO<T>>) -> Self
+    where
+        E: Exception,
+    {
+        let handler = handler.to_function();
+        Handler((move |e| E::from_exception(e).map(handler)).boxed())
+    }
+}
+
+/// Catch exceptions with the first of several handlers whose type matches.
+///
+/// Exceptions which match none of the handlers are propagated.
+pub fn catches<T>(io: IO<T>, handlers: Vec<Handler<T>>) -> IO<T>
+where
+    T: Term,
+{
+    catch_any(io, |e| {
+        handlers
+            .into_iter()
+            .try_fold(e, |e, Handler(handler)| match handler(e) {
+                Ok(io) => Err(io),
+                Err(e) => Ok(e),
+            })
+            .map_or_else(identity, throw)
+    })
+}
+
+/// Similar to [`catch`], but returns an Either result which is [`Right(a)`] if no exception of type e was raised,
+/// or [`Left(ex)`] if an exception of type `e` was raised and its value is `ex`.
+///
+/// If any other type of exception is raised then it will be propagated
+/// up to the next enclosing exception handler.
+/// ```text
+/// r#try(a) == catch(Right.lift_m(a), Left::compose_clone(ReturnM::return_m))
+/// ```
+pub fn r#try<E, T>(io: IO<T>) -> IO<Either<E, T>>
+where
+    T: Term,
+    E: Exception,
+{
+    catch(io.fmap(Right), |e| IO::return_m(Left(e)))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::ErrorKind;
+
+    use crate::prelude::*;
+
+    use super::{
+        catch, catch_any, catches, r#try, throw, ErrorCall, Exception, Handler, IOException,
+        SomeException,
+    };
+
+    #[test]
+    fn test_hierarchy() {
+        let e = IOException::new("open", ErrorKind::NotFound.into()).with_path("missing.txt");
+        let some = e.clone().to_exception();
+
+        assert!(some.is::<IOException>());
+        assert_eq!(
+            some.clone().display_exception(),
+            "missing.txt: open: entity not found"
+        );
+        assert_eq!(IOException::from_exception(some.clone()).ok(), Some(e));
+        assert!(ErrorCall::from_exception(some).is_err());
+    }
+
+    #[test]
+    fn test_catch() {
+        let io = catch_any(
+            throw::<usize, _>(ErrorCall::new("thrown")),
+            |e: SomeException| IO::return_m(e.show().len()),
+        );
+        assert_eq!(unsafe { io.run() }, 6);
+
+        let io = catch(IO::new(|| panic!("panicked")), |ErrorCall(s)| {
+            IO::return_m(s)
+        });
+        assert_eq!(unsafe { io.run() }, "panicked");
+
+        let io = r#try::<String, usize>(IO::new(|| panic!("{} {}", "formatted", "panic")));
+        assert_eq!(unsafe { io.run() }, Left("formatted panic".to_string()));
+    }
+
+    #[test]
+    fn test_catches() {
+        let handlers = || {
+            vec![
+                Handler::new(|_: ErrorCall| IO::return_m(1)),
+                Handler::new(|_: IOException| IO::return_m(2)),
+            ]
+        };
+
+        let io = throw(IOException::new("read", ErrorKind::Other.into()));
+        assert_eq!(unsafe { catches(io, handlers()).run() }, 2);
+
+        let io = catches(throw(ErrorKind::Other), handlers());
+        let io = catch(io, |e: ErrorKind| IO::return_m(e as usize));
+        assert_eq!(unsafe { io.run() }, ErrorKind::Other as usize);
+    }
+}
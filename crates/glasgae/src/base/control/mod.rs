@@ -3,6 +3,7 @@
 pub mod applicative;
 pub mod arrow;
 pub mod category;
+pub mod concurrent;
 pub mod monad;
 pub mod zipper;
 pub mod exception;
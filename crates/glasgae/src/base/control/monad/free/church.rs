@@ -0,0 +1,241 @@
+//! Church-encoded free monad.
+//!
+//! [`F<G, R, A>`] represents a [`Free<G, A>`] program by its fold:
+//! a function from a pure continuation `A -> R` and a layer algebra `G<R> -> R` to `R`.
+//!
+//! Binding on this representation is constant-time regardless of association,
+//! avoiding the quadratic cost of left-associated [`ChainM::chain_m`] on [`Free`].
+//! Programs can be built in [`F`], then converted back with [`F::to_free`].
+//!
+//! # Notes
+//!
+//! Haskell's `F` quantifies over the result type `r`.
+//! Rust has no rank-2 types, so the result type `R` is fixed up front
+//! (in the manner of [`ContT`](crate::transformers::cont::ContT)'s result monad),
+//! and chosen by the interpreter that will eventually run the program.
+
+use crate::{base::data::function::bifunction::Bifun, prelude::*};
+
+use super::{Free, FreeLayerT};
+
+/// A function collapsing one `G`-shaped layer of results.
+pub type AlgebraT<G, R> = Function<WithPointedT<G, R>, R>;
+
+/// The Church-encoded free monad over the functor `G`, with result type `R`.
+#[derive(Clone)]
+pub struct F<G, R, A>(Bifun<Function<A, R>, AlgebraT<G, R>, R>)
+where
+    G: WithPointed<R>,
+    R: Term,
+    A: Term;
+
+impl<G, R, A> F<G, R, A>
+where
+    G: WithPointed<R>,
+    R: Term,
+    A: Term,
+{
+    /// Construct a Church-encoded program from its fold.
+    pub fn new(f: impl BifunT<Function<A, R>, AlgebraT<G, R>, R>) -> Self {
+        F(f.boxed())
+    }
+
+    /// Run the program, given a continuation for its result and an algebra for its layers.
+    pub fn run_f(
+        self,
+        kp: impl FunctionT<A, R>,
+        kf: impl FunctionT<WithPointedT<G, R>, R>,
+    ) -> R {
+        self.0(kp.boxed(), kf.boxed())
+    }
+
+    /// Wrap a layer of programs into a single program.
+    pub fn wrap(layer: WithPointedT<G, F<G, R, A>>) -> Self
+    where
+        G: WithPointed<F<G, R, A>>,
+        WithPointedT<G, F<G, R, A>>: Functor<R, Mapped = WithPointedT<G, R>>,
+    {
+        F::new(|kp: Function<A, R>, kf: AlgebraT<G, R>| {
+            kf.clone()(layer.fmap(|m: F<G, R, A>| m.run_f(kp, kf)))
+        })
+    }
+
+    /// A version of lift that can be used with just a [`Functor`] for `G`.
+    pub fn lift_f<GA>(ga: GA) -> Self
+    where
+        GA: Functor<R, Pointed = A, Mapped = WithPointedT<G, R>>,
+    {
+        F::new(|kp: Function<A, R>, kf: AlgebraT<G, R>| kf(ga.fmap(kp)))
+    }
+
+    /// Convert a [`Free`] program into its Church encoding.
+    pub fn from_free(free: Free<G, A>) -> Self
+    where
+        G: WithPointed<Free<G, A>>,
+        FreeLayerT<G, A>: Functor<R, Mapped = WithPointedT<G, R>>,
+    {
+        F::new(|kp: Function<A, R>, kf: AlgebraT<G, R>| {
+            from_free_impl(free, kp, kf)
+        })
+    }
+
+    /// Like [`F::iter`], for monadic values.
+    pub fn iter_m(self, phi: impl FunctionT<WithPointedT<G, R>, R>) -> R
+    where
+        R: ReturnM<Pointed = A>,
+    {
+        self.run_f(ReturnM::return_m, phi)
+    }
+
+    /// Given a natural transformation from `G` to `M`, fold the program into `M`.
+    ///
+    /// As Rust lacks rank-2 types, the transformation is specialized
+    /// to the layer type it will be applied to.
+    pub fn fold_f<N>(self, nt: impl FunctionT<WithPointedT<G, R>, N>) -> R
+    where
+        N: Monad<A, Pointed = R, Chained = R>,
+        R: ReturnM<Pointed = A>,
+    {
+        let nt = nt.to_function();
+        self.run_f(ReturnM::return_m, |layer| nt(layer).chain_m(identity))
+    }
+}
+
+impl<G, A> F<G, A, A>
+where
+    G: WithPointed<A>,
+    A: Term,
+{
+    /// Tear down a Church-encoded program using iteration.
+    pub fn iter(self, phi: impl FunctionT<WithPointedT<G, A>, A>) -> A {
+        self.run_f(identity, phi)
+    }
+}
+
+impl<G, A> F<G, Free<G, A>, A>
+where
+    G: Term + WithPointed<Free<G, A>>,
+    A: Term,
+{
+    /// Convert a Church-encoded program back into a [`Free`] program.
+    pub fn to_free(self) -> Free<G, A> {
+        self.run_f(Free::Pure, Free::wrap)
+    }
+}
+
+fn from_free_impl<G, R, A>(
+    free: Free<G, A>,
+    kp: Function<A, R>,
+    kf: AlgebraT<G, R>,
+) -> R
+where
+    G: Term + WithPointed<R> + WithPointed<Free<G, A>>,
+    FreeLayerT<G, A>: Functor<R, Mapped = WithPointedT<G, R>>,
+    R: Term,
+    A: Term,
+{
+    match free {
+        Free::Pure(a) => kp(a),
+        Free::Free(fs) => kf.clone()(fs.run().fmap(|next| from_free_impl(next, kp, kf))),
+    }
+}
+
+impl<G, R, A> Pointed for F<G, R, A>
+where
+    G: WithPointed<R>,
+    R: Term,
+    A: Term,
+{
+    type Pointed = A;
+}
+
+impl<G, R, A, B> WithPointed<B> for F<G, R, A>
+where
+    G: WithPointed<R>,
+    R: Term,
+    A: Term,
+    B: Term,
+{
+    type WithPointed = F<G, R, B>;
+}
+
+impl<G, R, A, B> Functor<B> for F<G, R, A>
+where
+    G: WithPointed<R>,
+    R: Term,
+    A: Term,
+    B: Term,
+{
+    type Mapped = F<G, R, B>;
+
+    fn fmap(self, f: impl FunctionT<A, B>) -> F<G, R, B> {
+        let f = f.to_function();
+        F::new(|kp: Function<B, R>, kf| self.run_f(f.compose_clone(kp), kf))
+    }
+}
+
+impl<G, R, A> PureA for F<G, R, A>
+where
+    G: WithPointed<R>,
+    R: Term,
+    A: Term,
+{
+    fn pure_a(t: A) -> Self {
+        F::new(|kp: Function<A, R>, _| kp(t))
+    }
+}
+
+impl<G, R, H, A, B> AppA<A, B> for F<G, R, H>
+where
+    G: WithPointed<R>,
+    R: Term,
+    H: Term + FunctionT<A, B>,
+    A: Term,
+    B: Term,
+{
+    type WithA = F<G, R, A>;
+    type WithB = F<G, R, B>;
+
+    fn app_a(self, a: F<G, R, A>) -> F<G, R, B> {
+        F::new(|kp: Function<B, R>, kf: AlgebraT<G, R>| {
+            self.run_f(
+                {
+                    let kf = kf.clone();
+                    |g: H| a.run_f(g.compose_clone(kp), kf)
+                },
+                kf,
+            )
+        })
+    }
+}
+
+impl<G, R, A> ReturnM for F<G, R, A>
+where
+    G: WithPointed<R>,
+    R: Term,
+    A: Term,
+{
+}
+
+impl<G, R, A, B> ChainM<B> for F<G, R, A>
+where
+    G: WithPointed<R>,
+    R: Term,
+    A: Term,
+    B: Term,
+{
+    type Chained = F<G, R, B>;
+
+    fn chain_m(self, f: impl FunctionT<A, F<G, R, B>>) -> F<G, R, B> {
+        let f = f.to_function();
+        F::new(|kp: Function<B, R>, kf: AlgebraT<G, R>| {
+            self.run_f(
+                {
+                    let kf = kf.clone();
+                    |a| f(a).run_f(kp, kf)
+                },
+                kf,
+            )
+        })
+    }
+}
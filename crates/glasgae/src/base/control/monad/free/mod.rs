@@ -0,0 +1,544 @@
+//! The free monad over a [`Functor`].
+//!
+//! Given any functor `F`, [`Free<F, A>`] is the monad of programs built
+//! from the instructions that `F` describes.
+//!
+//! This allows a domain-specific language to be written as plain data,
+//! and later interpreted into any [`Monad`] by way of [`Free::fold_free`] or [`Free::iter_m`];
+//! for example, into [`IO`] for production, and [`State`](crate::transformers::state::State) for tests.
+//!
+//! # Functor parameter
+//!
+//! Since Rust lacks higher-kinded types, `F` is a concrete instantiation
+//! of the instruction functor (conventionally `Instr<()>`),
+//! and the layer of each node is recovered as [`FreeLayerT<F, A>`].
+//!
+//! # Example
+//!
+//! ```
+//! # use glasgae::{
+//! #     base::control::monad::free::{Free, FreeLayerT},
+//! #     derive_pointed, derive_with_pointed, prelude::*, transformers::state::State,
+//! # };
+//! #[derive(Clone)]
+//! enum Teletype<N: Term> {
+//!     Put(String, N),
+//!     Get(Function<String, N>),
+//! }
+//!
+//! derive_pointed!(Teletype<(N)>);
+//! derive_with_pointed!(Teletype<(N)>);
+//!
+//! impl<N: Term, M: Term> Functor<M> for Teletype<N> {
+//!     type Mapped = Teletype<M>;
+//!
+//!     fn fmap(self, f: impl FunctionT<N, M>) -> Teletype<M> {
+//!         match self {
+//!             Teletype::Put(s, n) => Teletype::Put(s, f(n)),
+//!             Teletype::Get(k) => Teletype::Get(k.fmap(f)),
+//!         }
+//!     }
+//! }
+//!
+//! type Program<A> = Free<Teletype<()>, A>;
+//!
+//! fn put(s: impl ToString) -> Program<()> {
+//!     Free::lift_f(Teletype::Put(s.to_string(), ()))
+//! }
+//!
+//! fn get() -> Program<String> {
+//!     Free::lift_f(Teletype::Get(identity.boxed()))
+//! }
+//!
+//! let echo = get().chain_m(put);
+//!
+//! // Interpret into a State monad over (input, output) for testing
+//! let out = echo.fold_free(|t: FreeLayerT<Teletype<()>, ()>| match t {
+//!     Teletype::Put(s, n) => State::new(move |(i, o): (String, String)| (n, (i, o + &s))),
+//!     Teletype::Get(k) => State::new(move |(i, o): (String, String)| (k(i.clone()), (i, o))),
+//! })
+//! .exec(("hello".to_string(), String::new()));
+//!
+//! assert_eq!(out.1, "hello");
+//! ```
+
+pub mod church;
+
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+
+/// The free monad over the functor `F`.
+///
+/// A value is either a [`Pure`](Free::Pure) result,
+/// or a [`Free`](Free::Free) layer of `F` containing further programs.
+pub enum Free<F, A>
+where
+    F: Term,
+    A: Term,
+{
+    Pure(A),
+    Free(FreeLayer<F, A>),
+}
+
+/// The `F`-shaped layer of a [`Free::Free`] node.
+pub type FreeLayerT<F, A> = WithPointedT<F, Free<F, A>>;
+
+/// Opaque storage for a [`FreeLayerT`].
+///
+/// Created with [`FreeLayer::new`], and unwrapped with [`FreeLayer::run`].
+pub struct FreeLayer<F, A>(Erased, PhantomData<fn() -> (F, A)>);
+
+impl<F, A> Clone for FreeLayer<F, A> {
+    fn clone(&self) -> Self {
+        FreeLayer(self.0.clone(), PhantomData)
+    }
+}
+
+impl<F, A> FreeLayer<F, A>
+where
+    F: Term + WithPointed<Free<F, A>>,
+    A: Term,
+{
+    pub fn new(layer: FreeLayerT<F, A>) -> Self {
+        FreeLayer(layer.boxed(), PhantomData)
+    }
+
+    pub fn run(self) -> FreeLayerT<F, A> {
+        self.0.downcast()
+    }
+}
+
+impl<F, A> Clone for Free<F, A>
+where
+    F: Term,
+    A: Term,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Free::Pure(a) => Free::Pure(a.clone()),
+            Free::Free(fs) => Free::Free(fs.clone()),
+        }
+    }
+}
+
+impl<F, A> Free<F, A>
+where
+    F: Term,
+    A: Term,
+{
+    /// Construct a [`Free::Free`] node from a layer of `F`.
+    pub fn wrap(layer: FreeLayerT<F, A>) -> Self
+    where
+        F: WithPointed<Free<F, A>>,
+    {
+        Free::Free(FreeLayer::new(layer))
+    }
+
+    /// Inspect the outermost step of the program,
+    /// returning either its result or its next layer.
+    pub fn resume(self) -> Either<FreeLayerT<F, A>, A>
+    where
+        F: WithPointed<Free<F, A>>,
+    {
+        match self {
+            Free::Pure(a) => Right(a),
+            Free::Free(fs) => Left(fs.run()),
+        }
+    }
+
+    /// A version of lift that can be used with just a [`Functor`] for `F`.
+    pub fn lift_f<FA>(fa: FA) -> Self
+    where
+        FA: Functor<Free<F, A>, Pointed = A, Mapped = FreeLayerT<F, A>>,
+        F: WithPointed<Free<F, A>>,
+    {
+        Free::wrap(fa.fmap(Free::Pure))
+    }
+
+    /// Given a natural transformation from `F` to `M`,
+    /// this gives a canonical monoidal natural transformation from `Free<F, _>` to `M`.
+    ///
+    /// As Rust lacks rank-2 types, the transformation is specialized
+    /// to the layer type it will be applied to.
+    ///
+    /// ```text
+    /// fold_free(nt)(Pure(a)) == return_m(a)
+    /// fold_free(nt)(Free(fs)) == nt(fs).chain_m(fold_free(nt))
+    /// ```
+    pub fn fold_free<M, N>(self, nt: impl FunctionT<FreeLayerT<F, A>, N>) -> M
+    where
+        F: WithPointed<Free<F, A>>,
+        N: Monad<A, Pointed = Free<F, A>, Chained = M>,
+        M: ReturnM<Pointed = A>,
+    {
+        match self {
+            Free::Pure(a) => ReturnM::return_m(a),
+            Free::Free(fs) => {
+                let nt = nt.to_function();
+                nt.clone()(fs.run()).chain_m(|next| next.fold_free(nt))
+            }
+        }
+    }
+
+    /// Tear down a free monad using iteration.
+    pub fn iter(self, phi: impl FunctionT<WithPointedT<FreeLayerT<F, A>, A>, A>) -> A
+    where
+        F: WithPointed<Free<F, A>>,
+        FreeLayerT<F, A>: Functor<A>,
+    {
+        match self {
+            Free::Pure(a) => a,
+            Free::Free(fs) => {
+                let phi = phi.to_function();
+                phi.clone()(fs.run().fmap(|next: Free<F, A>| next.iter(phi)))
+            }
+        }
+    }
+
+    /// Like [`Free::iter`], for monadic values.
+    pub fn iter_m<M>(self, phi: impl FunctionT<WithPointedT<FreeLayerT<F, A>, M>, M>) -> M
+    where
+        F: WithPointed<Free<F, A>>,
+        FreeLayerT<F, A>: Functor<M>,
+        M: ReturnM<Pointed = A>,
+    {
+        match self {
+            Free::Pure(a) => ReturnM::return_m(a),
+            Free::Free(fs) => {
+                let phi = phi.to_function();
+                phi.clone()(fs.run().fmap(|next: Free<F, A>| next.iter_m(phi)))
+            }
+        }
+    }
+
+    /// Lift a natural transformation from `F` to `G` into a natural transformation from `Free<F, _>` to `Free<G, _>`.
+    ///
+    /// As with [`Free::fold_free`], the transformation is specialized to the layer it is applied to.
+    pub fn hoist_free<G>(
+        self,
+        nt: impl FunctionT<FreeLayerT<F, A>, WithPointedT<G, Free<F, A>>>,
+    ) -> Free<G, A>
+    where
+        F: WithPointed<Free<F, A>>,
+        G: Term + WithPointed<Free<F, A>> + WithPointed<Free<G, A>>,
+        WithPointedT<G, Free<F, A>>: Functor<Free<G, A>, Mapped = FreeLayerT<G, A>>,
+    {
+        match self {
+            Free::Pure(a) => Free::Pure(a),
+            Free::Free(fs) => {
+                let nt = nt.to_function();
+                Free::wrap(nt.clone()(fs.run()).fmap(|next: Free<F, A>| next.hoist_free(nt)))
+            }
+        }
+    }
+
+    /// Tear down a free monad whose functor is itself the target monad.
+    ///
+    /// ```text
+    /// retract == fold_free(identity)
+    /// ```
+    pub fn retract<M>(self) -> M
+    where
+        F: WithPointed<Free<F, A>>,
+        FreeLayerT<F, A>: Monad<A, Pointed = Free<F, A>, Chained = M>,
+        M: ReturnM<Pointed = A>,
+    {
+        self.fold_free(identity)
+    }
+}
+
+impl<F, A> Pointed for Free<F, A>
+where
+    F: Term,
+    A: Term,
+{
+    type Pointed = A;
+}
+
+impl<F, A, B> WithPointed<B> for Free<F, A>
+where
+    F: Term,
+    A: Term,
+    B: Term,
+{
+    type WithPointed = Free<F, B>;
+}
+
+impl<F, A, B> Functor<B> for Free<F, A>
+where
+    F: Term + WithPointed<Free<F, A>> + WithPointed<Free<F, B>>,
+    FreeLayerT<F, A>: Functor<Free<F, B>, Mapped = FreeLayerT<F, B>>,
+    FreeLayerT<F, B>: Functor<Free<F, A>, Mapped = FreeLayerT<F, A>>,
+    A: Term,
+    B: Term,
+{
+    type Mapped = Free<F, B>;
+
+    fn fmap(self, f: impl FunctionT<A, B>) -> Free<F, B> {
+        match self {
+            Free::Pure(a) => Free::Pure(f(a)),
+            Free::Free(fs) => {
+                let f = f.to_function();
+                Free::wrap(fs.run().fmap(|next: Free<F, A>| next.fmap(f)))
+            }
+        }
+    }
+}
+
+impl<F, A> PureA for Free<F, A>
+where
+    F: Term,
+    A: Term,
+{
+    fn pure_a(t: Self::Pointed) -> Self {
+        Free::Pure(t)
+    }
+}
+
+impl<F, G, A, B> AppA<A, B> for Free<F, G>
+where
+    F: Term
+        + WithPointed<Free<F, G>>
+        + WithPointed<Free<F, A>>
+        + WithPointed<Free<F, B>>,
+    FreeLayerT<F, G>: Functor<Free<F, B>, Mapped = FreeLayerT<F, B>>,
+    FreeLayerT<F, B>: Functor<Free<F, G>, Mapped = FreeLayerT<F, G>>,
+    FreeLayerT<F, A>: Functor<Free<F, B>, Mapped = FreeLayerT<F, B>>,
+    FreeLayerT<F, B>: Functor<Free<F, A>, Mapped = FreeLayerT<F, A>>,
+    G: Term + FunctionT<A, B>,
+    A: Term,
+    B: Term,
+{
+    type WithA = Free<F, A>;
+    type WithB = Free<F, B>;
+
+    fn app_a(self, a: Free<F, A>) -> Free<F, B> {
+        match self {
+            Free::Pure(f) => a.fmap(f),
+            Free::Free(fs) => Free::wrap(fs.run().fmap(|next: Free<F, G>| next.app_a(a))),
+        }
+    }
+}
+
+impl<F, A> ReturnM for Free<F, A>
+where
+    F: Term,
+    A: Term,
+{
+}
+
+impl<F, A, B> ChainM<B> for Free<F, A>
+where
+    F: Term + WithPointed<Free<F, A>> + WithPointed<Free<F, B>>,
+    FreeLayerT<F, A>: Functor<Free<F, B>, Mapped = FreeLayerT<F, B>>,
+    FreeLayerT<F, B>: Functor<Free<F, A>, Mapped = FreeLayerT<F, A>>,
+    A: Term,
+    B: Term,
+{
+    type Chained = Free<F, B>;
+
+    fn chain_m(self, f: impl FunctionT<A, Free<F, B>>) -> Free<F, B> {
+        match self {
+            Free::Pure(a) => f(a),
+            Free::Free(fs) => {
+                let f = f.to_function();
+                Free::wrap(fs.run().fmap(|next: Free<F, A>| next.chain_m(f)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        base::data::io_ref::{modify_io_ref, new_io_ref, read_io_ref, IORef},
+        derive_pointed, derive_with_pointed,
+        prelude::*,
+        transformers::{state::State, writer::Writer},
+    };
+
+    use super::{church::F, Free, FreeLayerT};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Toy<N> {
+        Output(char, N),
+        Bell(N),
+        Done,
+    }
+
+    derive_pointed!(Toy<(N)>);
+    derive_with_pointed!(Toy<(N)>);
+
+    impl<N, M> Functor<M> for Toy<N>
+    where
+        N: Term,
+        M: Term,
+    {
+        type Mapped = Toy<M>;
+
+        fn fmap(self, f: impl FunctionT<N, M>) -> Toy<M> {
+            match self {
+                Toy::Output(c, n) => Toy::Output(c, f(n)),
+                Toy::Bell(n) => Toy::Bell(f(n)),
+                Toy::Done => Toy::Done,
+            }
+        }
+    }
+
+    type Program<A> = Free<Toy<()>, A>;
+
+    fn output(c: char) -> Program<()> {
+        Free::lift_f(Toy::Output(c, ()))
+    }
+
+    fn bell() -> Program<()> {
+        Free::lift_f(Toy::Bell(()))
+    }
+
+    fn done<A: Term>() -> Program<A> {
+        Free::wrap(Toy::Done)
+    }
+
+    fn program() -> Program<()> {
+        output('a').then_m(bell()).then_m(output('b')).then_m(done())
+    }
+
+    fn show_layer(t: Toy<String>) -> String {
+        match t {
+            Toy::Output(c, n) => format!("output {c}\n{n}"),
+            Toy::Bell(n) => format!("bell\n{n}"),
+            Toy::Done => "done".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_iter() {
+        let out = program().fmap(|_| String::new()).iter(show_layer);
+        assert_eq!(out, "output a\nbell\noutput b\ndone");
+    }
+
+    #[test]
+    fn test_fold_free_state() {
+        let out = output('a')
+            .then_m(bell())
+            .then_m(output('b'))
+            .fold_free(|t: FreeLayerT<Toy<()>, ()>| match t {
+                Toy::Output(c, n) => State::new(move |s: String| (n, format!("{s}{c}"))),
+                Toy::Bell(n) => State::new(move |s: String| (n, format!("{s}!"))),
+                Toy::Done => unreachable!(),
+            })
+            .exec(String::new());
+
+        assert_eq!(out, "a!b");
+    }
+
+    #[test]
+    fn test_fold_free_io() {
+        // Interpret into IO, logging to an IORef rather than the terminal
+        let io = new_io_ref(String::new()).chain_m(|log: IORef<String>| {
+            let log_ = log.clone();
+            output('a')
+                .then_m(bell())
+                .fold_free(move |t: FreeLayerT<Toy<()>, ()>| match t {
+                    Toy::Output(c, n) => {
+                        modify_io_ref(log_.clone(), move |s| format!("{s}{c}")).replace(n)
+                    }
+                    Toy::Bell(n) => modify_io_ref(log_.clone(), |s| format!("{s}!")).replace(n),
+                    Toy::Done => unreachable!(),
+                })
+                .then_m(read_io_ref(log))
+        });
+
+        assert_eq!(unsafe { io.run() }, "a!");
+    }
+
+    fn render<A: Term + std::fmt::Debug>(p: Program<A>) -> String {
+        p.fmap(|a| format!("pure {a:?}")).iter(show_layer)
+    }
+
+    #[test]
+    fn test_monad_laws() {
+        let f = |c: char| output(c).replace(c);
+        let g = |c: char| bell().replace(c.to_ascii_uppercase());
+
+        // Left identity
+        assert_eq!(render(Program::return_m('x').chain_m(f)), render(f('x')));
+
+        // Right identity
+        assert_eq!(
+            render(f('x').chain_m(ReturnM::return_m)),
+            render(f('x'))
+        );
+
+        // Associativity
+        assert_eq!(
+            render(f('x').chain_m(f).chain_m(g)),
+            render(f('x').chain_m(move |x| f(x).chain_m(g)))
+        );
+
+        assert_eq!(render(f('x').chain_m(g)), "output x\nbell\npure 'X'");
+    }
+
+    #[test]
+    fn test_hoist_free() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Log<N>(String, N);
+
+        derive_pointed!(Log<(N)>);
+        derive_with_pointed!(Log<(N)>);
+
+        impl<N, M> Functor<M> for Log<N>
+        where
+            N: Term,
+            M: Term,
+        {
+            type Mapped = Log<M>;
+
+            fn fmap(self, f: impl FunctionT<N, M>) -> Log<M> {
+                Log(self.0, f(self.1))
+            }
+        }
+
+        let out = output('a')
+            .then_m(bell())
+            .hoist_free::<Log<()>>(|t| match t {
+                Toy::Output(c, n) => Log(c.to_string(), n),
+                Toy::Bell(n) => Log("bell".to_string(), n),
+                Toy::Done => unreachable!(),
+            })
+            .fold_free(|Log(s, n)| Writer::tell(vec![s]).replace(n))
+            .exec();
+
+        assert_eq!(out, vec!["a".to_string(), "bell".to_string()]);
+    }
+
+    #[test]
+    fn test_church() {
+        // Left-associated binds are constant-time under the Church encoding
+        let program = (0..1000).fold(F::<Toy<()>, _, ()>::return_m(()), |acc, i| {
+            acc.then_m(F::lift_f(Toy::Output(
+                char::from_digit(i % 10, 10).unwrap(),
+                (),
+            )))
+        });
+
+        let out = program
+            .to_free()
+            .fold_free(|t: FreeLayerT<Toy<()>, ()>| match t {
+                Toy::Output(c, n) => State::new(move |s: String| (n, format!("{s}{c}"))),
+                _ => unreachable!(),
+            })
+            .exec(String::new());
+
+        assert_eq!(out.len(), 1000);
+        assert!(out.starts_with("0123456789"));
+    }
+
+    #[test]
+    fn test_church_roundtrip() {
+        let out = F::from_free(program().fmap(|_| String::new()))
+            .iter(show_layer);
+        assert_eq!(out, "output a\nbell\noutput b\ndone");
+    }
+}
@@ -40,8 +40,10 @@
 //!
 //! The instances of Monad for lists and Maybe defined in the Prelude satisfy these laws.
 
+pub mod free;
 pub mod io;
 pub mod morph;
+pub mod st;
 
 use crate::{
     base::data::{collection::list::vec::push, function::bifunction::BifunT},
@@ -0,0 +1,482 @@
+//! The strict state-transformer monad.
+//!
+//! Based on [Control.Monad.ST](https://hackage.haskell.org/package/base/docs/Control-Monad-ST.html),
+//! [Data.STRef](https://hackage.haskell.org/package/base/docs/Data-STRef.html)
+//! and [Data.Array.ST](https://hackage.haskell.org/package/array/docs/Data-Array-ST.html).
+//!
+//! A computation of type [`ST<S, A>`] may allocate and mutate references and arrays
+//! in place, and returns a value of type `A`.
+//! The type parameter `S` brands every reference created by the computation,
+//! and [`run_st`] only accepts computations that are generic over it,
+//! which makes it impossible for a reference to escape into the result.
+//! The mutation is thus unobservable, and [`run_st`] is a pure function.
+//!
+//! Since Rust has no rank-2 types, computations passed to [`run_st`]
+//! are written as implementations of [`RunST`]:
+//!
+//! ```
+//! # use glasgae::{base::control::monad::st::*, prelude::*};
+//! struct Sum(Vec<usize>);
+//!
+//! impl RunST<usize> for Sum {
+//!     fn st<S: Term>(self) -> ST<S, usize> {
+//!         new_st_ref(0).chain_m(|total| {
+//!             self.0
+//!                 .into_iter()
+//!                 .map(|n| modify_st_ref(total.clone(), move |t| t + n))
+//!                 .fold(ST::return_m(()), |acc, next| acc.then_m(next))
+//!                 .then_m(read_st_ref(total))
+//!         })
+//!     }
+//! }
+//!
+//! assert_eq!(run_st(Sum(vec![1, 2, 3, 4])), 10);
+//! ```
+//!
+//! Attempting to return a reference from the computation fails to compile,
+//! as the result type cannot mention the brand:
+//!
+//! ```compile_fail
+//! # use glasgae::{base::control::monad::st::*, prelude::*};
+//! struct Escape;
+//!
+//! impl<S: Term> RunST<STRef<S, usize>> for Escape {
+//!     fn st<S2: Term>(self) -> ST<S2, STRef<S, usize>> {
+//!         new_st_ref(0)
+//!     }
+//! }
+//! ```
+
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{
+    base::data::io_ref::{modify_io_ref, new_io_ref, read_io_ref, write_io_ref, IORef},
+    derive_pointed, derive_with_pointed,
+    prelude::*,
+};
+
+/// A computation which may mutate references branded with `S`,
+/// before returning a value of type `A`.
+pub struct ST<S, A>(IO<A>, PhantomData<S>)
+where
+    A: Term;
+
+impl<S, A> ST<S, A>
+where
+    A: Term,
+{
+    fn new(f: impl NullaryT<A>) -> Self {
+        ST(IO::new(f), PhantomData)
+    }
+}
+
+impl<S, A> Clone for ST<S, A>
+where
+    A: Term,
+{
+    fn clone(&self) -> Self {
+        ST(self.0.clone(), PhantomData)
+    }
+}
+
+derive_pointed!(ST<S, (A)>);
+derive_with_pointed!(ST<S, (A)>);
+
+impl<S, A, B> Functor<B> for ST<S, A>
+where
+    S: Term,
+    A: Term,
+    B: Term,
+{
+    type Mapped = ST<S, B>;
+
+    fn fmap(self, f: impl FunctionT<A, B>) -> Self::Mapped {
+        ST(self.0.fmap(f), PhantomData)
+    }
+}
+
+impl<S, A> PureA for ST<S, A>
+where
+    S: Term,
+    A: Term,
+{
+    fn pure_a(t: Self::Pointed) -> Self {
+        ST(IO::pure_a(t), PhantomData)
+    }
+}
+
+impl<S, F, A, B> AppA<A, B> for ST<S, F>
+where
+    S: Term,
+    F: Term + FunctionT<A, B>,
+    A: Term,
+    B: Term,
+{
+    type WithA = ST<S, A>;
+    type WithB = ST<S, B>;
+
+    fn app_a(self, a: ST<S, A>) -> ST<S, B> {
+        ST(self.0.app_a(a.0), PhantomData)
+    }
+}
+
+impl<S, A> ReturnM for ST<S, A>
+where
+    S: Term,
+    A: Term,
+{
+}
+
+impl<S, A, B> ChainM<B> for ST<S, A>
+where
+    S: Term,
+    A: Term,
+    B: Term,
+{
+    type Chained = ST<S, B>;
+
+    fn chain_m(self, f: impl FunctionT<A, ST<S, B>>) -> ST<S, B> {
+        let f = f.to_function();
+        ST(self.0.chain_m(move |a| f(a).0), PhantomData)
+    }
+}
+
+/// A computation which can be run by [`run_st`]
+/// for any choice of the brand `S`.
+pub trait RunST<A>
+where
+    A: Term,
+{
+    fn st<S: Term>(self) -> ST<S, A>;
+}
+
+/// The brand with which [`st_to_io`] runs computations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RealWorld {}
+
+/// Run a state-transformer computation, returning its result.
+///
+/// No reference created by the computation can outlive it,
+/// so the result depends only on the computation itself.
+pub fn run_st<A>(st: impl RunST<A>) -> A
+where
+    A: Term,
+{
+    // The computation only mutates state that it allocated,
+    // and which is unreachable once it returns.
+    unsafe { st.st::<RealWorld>().0.run() }
+}
+
+/// Embed a state-transformer computation in the [`IO`] monad.
+pub fn st_to_io<A>(st: ST<RealWorld, A>) -> IO<A>
+where
+    A: Term,
+{
+    st.0
+}
+
+/// A mutable reference in the [`ST`] monad.
+pub struct STRef<S, T>(IORef<T>, PhantomData<S>);
+
+impl<S, T> Clone for STRef<S, T> {
+    fn clone(&self) -> Self {
+        STRef(self.0.clone(), PhantomData)
+    }
+}
+
+/// Two `STRef`s are equal if they refer to the same variable.
+impl<S, T> PartialEq for STRef<S, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<S, T> Eq for STRef<S, T> {}
+
+impl<S, T> std::fmt::Debug for STRef<S, T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("STRef").field(&self.0).finish()
+    }
+}
+
+/// Build a new [`STRef`].
+pub fn new_st_ref<S, T>(t: T) -> ST<S, STRef<S, T>>
+where
+    S: Term,
+    T: Term,
+{
+    ST(new_io_ref(t).fmap(|r| STRef(r, PhantomData)), PhantomData)
+}
+
+/// Read the value of an [`STRef`].
+pub fn read_st_ref<S, T>(r: STRef<S, T>) -> ST<S, T>
+where
+    S: Term,
+    T: Term,
+{
+    ST(read_io_ref(r.0), PhantomData)
+}
+
+/// Write a new value into an [`STRef`].
+pub fn write_st_ref<S, T>(r: STRef<S, T>, t: T) -> ST<S, ()>
+where
+    S: Term,
+    T: Term,
+{
+    ST(write_io_ref(r.0, t), PhantomData)
+}
+
+/// Mutate the contents of an [`STRef`].
+pub fn modify_st_ref<S, T>(r: STRef<S, T>, f: impl FunctionT<T, T>) -> ST<S, ()>
+where
+    S: Term,
+    T: Term,
+{
+    ST(modify_io_ref(r.0, f), PhantomData)
+}
+
+/// A mutable, fixed-length array in the [`ST`] monad.
+///
+/// Elements are read and written in place,
+/// without copying the rest of the array.
+/// Indexing out of bounds panics.
+pub struct STArray<S, T>(Arc<Mutex<Vec<T>>>, PhantomData<S>);
+
+impl<S, T> STArray<S, T> {
+    fn lock(&self) -> MutexGuard<'_, Vec<T>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<S, T> Clone for STArray<S, T> {
+    fn clone(&self) -> Self {
+        STArray(self.0.clone(), PhantomData)
+    }
+}
+
+/// Two `STArray`s are equal if they refer to the same array.
+impl<S, T> PartialEq for STArray<S, T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<S, T> Eq for STArray<S, T> {}
+
+impl<S, T> std::fmt::Debug for STArray<S, T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("STArray").field(&*self.lock()).finish()
+    }
+}
+
+/// Build a new [`STArray`] of length `len`, with every element set to `t`.
+pub fn new_st_array<S, T>(len: usize, t: T) -> ST<S, STArray<S, T>>
+where
+    S: Term,
+    T: Term,
+{
+    thaw_st_array(vec![t; len])
+}
+
+/// Build a new [`STArray`] holding the elements of `ts`.
+pub fn thaw_st_array<S, T>(ts: Vec<T>) -> ST<S, STArray<S, T>>
+where
+    S: Term,
+    T: Term,
+{
+    ST::new(move || STArray(Arc::new(Mutex::new(ts)), PhantomData))
+}
+
+/// Copy the current elements of an [`STArray`] into an immutable [`Vec`].
+pub fn freeze_st_array<S, T>(arr: STArray<S, T>) -> ST<S, Vec<T>>
+where
+    S: Term,
+    T: Term,
+{
+    ST::new(move || arr.lock().clone())
+}
+
+/// The number of elements in an [`STArray`].
+pub fn st_array_len<S, T>(arr: STArray<S, T>) -> ST<S, usize>
+where
+    S: Term,
+    T: Term,
+{
+    ST::new(move || arr.lock().len())
+}
+
+/// Read the element of an [`STArray`] at index `i`.
+pub fn read_st_array<S, T>(arr: STArray<S, T>, i: usize) -> ST<S, T>
+where
+    S: Term,
+    T: Term,
+{
+    ST::new(move || arr.lock()[i].clone())
+}
+
+/// Write a new value into an [`STArray`] at index `i`.
+pub fn write_st_array<S, T>(arr: STArray<S, T>, i: usize, t: T) -> ST<S, ()>
+where
+    S: Term,
+    T: Term,
+{
+    ST::new(move || arr.lock()[i] = t)
+}
+
+/// Mutate the element of an [`STArray`] at index `i`.
+///
+/// The element is left unchanged if `f` panics.
+pub fn modify_st_array<S, T>(arr: STArray<S, T>, i: usize, f: impl FunctionT<T, T>) -> ST<S, ()>
+where
+    S: Term,
+    T: Term,
+{
+    let f = f.to_function();
+    ST::new(move || {
+        let mut guard = arr.lock();
+        guard[i] = f(guard[i].clone());
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Sequence a list of computations, discarding their results.
+    fn sequence_<S: Term>(sts: impl IntoIterator<Item = ST<S, ()>>) -> ST<S, ()> {
+        sts.into_iter()
+            .fold(ST::return_m(()), |acc, next| acc.then_m(next))
+    }
+
+    struct Sieve(usize);
+
+    impl RunST<Vec<usize>> for Sieve {
+        fn st<S: Term>(self) -> ST<S, Vec<usize>> {
+            let n = self.0;
+            new_st_array(n + 1, true).chain_m(move |arr| {
+                sequence_((2..=n).map(|i| {
+                    let arr = arr.clone();
+                    read_st_array(arr.clone(), i).chain_m(move |prime| {
+                        if prime {
+                            sequence_(
+                                (i * i..=n)
+                                    .step_by(i)
+                                    .map(|j| write_st_array(arr.clone(), j, false)),
+                            )
+                        } else {
+                            ST::return_m(())
+                        }
+                    })
+                }))
+                .then_m(freeze_st_array(arr))
+                .fmap(|flags: Vec<bool>| {
+                    flags
+                        .into_iter()
+                        .enumerate()
+                        .skip(2)
+                        .filter_map(|(i, prime)| prime.then_some(i))
+                        .collect()
+                })
+            })
+        }
+    }
+
+    #[test]
+    fn test_sieve() {
+        assert_eq!(run_st(Sieve(30)), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    }
+
+    /// Find the representative of `i`, compressing the path to it.
+    fn find<S: Term>(parent: STArray<S, usize>, i: usize) -> ST<S, usize> {
+        read_st_array(parent.clone(), i).chain_m(move |p| {
+            if p == i {
+                ST::return_m(i)
+            } else {
+                let parent = parent.clone();
+                find(parent.clone(), p).chain_m(move |root| {
+                    write_st_array(parent.clone(), i, root).then_m(ST::return_m(root))
+                })
+            }
+        })
+    }
+
+    fn union<S: Term>(parent: STArray<S, usize>, a: usize, b: usize) -> ST<S, ()> {
+        find(parent.clone(), a).chain_m(move |ra| {
+            let parent = parent.clone();
+            find(parent.clone(), b).chain_m(move |rb| write_st_array(parent.clone(), ra, rb))
+        })
+    }
+
+    /// Count the connected components of a graph.
+    struct Components(usize, Vec<(usize, usize)>);
+
+    impl RunST<usize> for Components {
+        fn st<S: Term>(self) -> ST<S, usize> {
+            let Components(n, edges) = self;
+            thaw_st_array((0..n).collect()).chain_m(move |parent| {
+                let unions = edges
+                    .clone()
+                    .into_iter()
+                    .map(|(a, b)| union(parent.clone(), a, b));
+                let roots = (0..n).map(|i| find(parent.clone(), i).fmap(move |root| root == i));
+
+                sequence_(unions).then_m(roots.fold(ST::return_m(0), |acc, is_root| {
+                    acc.chain_m(move |count| {
+                        is_root
+                            .clone()
+                            .fmap(move |is_root| count + is_root as usize)
+                    })
+                }))
+            })
+        }
+    }
+
+    #[test]
+    fn test_union_find() {
+        let edges = vec![(0, 1), (1, 2), (3, 4), (5, 5), (2, 0)];
+        assert_eq!(run_st(Components(7, edges)), 4);
+        assert_eq!(run_st(Components(3, vec![])), 3);
+    }
+
+    struct Swap;
+
+    impl RunST<(Vec<char>, char, usize)> for Swap {
+        fn st<S: Term>(self) -> ST<S, (Vec<char>, char, usize)> {
+            thaw_st_array(vec!['a', 'b', 'c']).chain_m(|arr| {
+                read_st_array(arr.clone(), 0).chain_m(move |first| {
+                    let arr = arr.clone();
+                    modify_st_array(arr.clone(), 2, |c: char| c.to_ascii_uppercase())
+                        .then_m(write_st_array(arr.clone(), 0, 'z'))
+                        .then_m(st_array_len(arr.clone()))
+                        .chain_m(move |len| {
+                            freeze_st_array(arr.clone()).fmap(move |elems| (elems, first, len))
+                        })
+                })
+            })
+        }
+    }
+
+    #[test]
+    fn test_st_array() {
+        assert_eq!(run_st(Swap), (vec!['z', 'b', 'C'], 'a', 3));
+    }
+
+    #[test]
+    fn test_st_to_io() {
+        let io = st_to_io(new_st_ref(1).chain_m(|r| {
+            write_st_ref(r.clone(), 2)
+                .then_m(modify_st_ref(r.clone(), |n| n * 10))
+                .then_m(read_st_ref(r))
+        }));
+        assert_eq!(unsafe { io.run() }, 20);
+    }
+}
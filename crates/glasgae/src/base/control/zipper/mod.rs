@@ -3,28 +3,39 @@
 //! Based on the zipper described in [Final Zipper](https://okmij.org/ftp/continuations/zipper.html) by Oleg Kiselyov.
 
 mod travel;
+mod zip_move;
 mod zip_travel;
+mod zip_traversable;
 
 pub use travel::*;
+pub use zip_move::*;
 pub use zip_travel::*;
+pub use zip_traversable::*;
 
 use crate::transformers::cont::Cont;
 use crate::{derive_applicative, derive_functor, derive_pointed, derive_with_pointed, prelude::*};
 
+/// A zipper over a structure of type `R`, focused on a value of type `T`.
+///
+/// Moving the zipper resumes the traversal that produced it,
+/// optionally replacing the current focus, and in the direction `D`.
+/// Once the traversal has finished, the zipper holds the rebuilt structure.
 #[derive(Clone)]
-pub enum Zipper<T, D>
+pub enum Zipper<T, D, R = T>
 where
     T: Term,
     D: Term,
+    R: Term,
 {
-    Zipper(T, Function<(Option<T>, D), Zipper<T, D>>),
-    ZipDone(T),
+    Zipper(T, Function<(Option<T>, D), Zipper<T, D, R>>),
+    ZipDone(R),
 }
 
-impl<T, D> std::fmt::Debug for Zipper<T, D>
+impl<T, D, R> std::fmt::Debug for Zipper<T, D, R>
 where
     T: Term + std::fmt::Debug,
     D: Term,
+    R: Term + std::fmt::Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -34,35 +45,101 @@ where
     }
 }
 
-impl<T, D> Zipper<T, D>
+impl<T, D, R> Zipper<T, D, R>
 where
     T: Term,
     D: Term,
+    R: Term,
 {
-    pub fn zip(a: T, f: impl FunctionT<(Option<T>, D), Zipper<T, D>> + 'static) -> Self {
+    pub fn zip(a: T, f: impl FunctionT<(Option<T>, D), Zipper<T, D, R>> + 'static) -> Self {
         Zipper::Zipper(a, f.boxed())
     }
 
-    pub fn done(t: T) -> Self {
-        Zipper::ZipDone(t)
+    pub fn done(r: R) -> Self {
+        Zipper::ZipDone(r)
     }
 
     // Returns Ok if the zipper is done, Err otherwise
-    pub fn try_unwrap(self) -> Result<T, T> {
+    pub fn try_unwrap(self) -> Result<R, T> {
         match self {
             Zipper::Zipper(t, _) => Err(t),
-            Zipper::ZipDone(t) => Ok(t),
+            Zipper::ZipDone(r) => Ok(r),
         }
     }
 
     // Returns the contents of the zipper, panics if the zipper is not done
-    pub fn unwrap(self) -> T {
+    pub fn unwrap(self) -> R {
         match self {
             Zipper::Zipper(_, _) => panic!("Zipper is not Done"),
-            Zipper::ZipDone(t) => t,
+            Zipper::ZipDone(r) => r,
+        }
+    }
+
+    /// Returns the current focus, or `None` if the zipper is done.
+    pub fn focus(&self) -> Option<&T> {
+        match self {
+            Zipper::Zipper(t, _) => Some(t),
+            Zipper::ZipDone(_) => None,
+        }
+    }
+
+    /// Returns `true` if the traversal has finished.
+    pub fn is_done(&self) -> bool {
+        matches!(self, Zipper::ZipDone(_))
+    }
+}
+
+impl<T, D, R> Zipper<T, D, R>
+where
+    T: Term,
+    D: Term + Default,
+    R: Term,
+{
+    /// Move to the next focus, leaving the current one unchanged.
+    ///
+    /// Has no effect on a finished zipper.
+    pub fn next(self) -> Self {
+        match self {
+            Zipper::Zipper(_, k) => k((None, Default::default())),
+            done => done,
         }
     }
 
+    /// Replace the current focus with `t`, and move to the next one.
+    ///
+    /// Has no effect on a finished zipper.
+    pub fn replace(self, t: T) -> Self {
+        match self {
+            Zipper::Zipper(_, k) => k((Some(t), Default::default())),
+            done => done,
+        }
+    }
+
+    /// Move forward by `n` foci, leaving them unchanged.
+    pub fn skip(self, n: usize) -> Self {
+        (0..n).fold(self, |z, _| z.next())
+    }
+
+    /// Move past every remaining focus, leaving them unchanged.
+    pub fn to_end(self) -> Self {
+        let mut z = self;
+        while !z.is_done() {
+            z = z.next();
+        }
+        z
+    }
+
+    /// Finish the traversal, and return the rebuilt structure.
+    pub fn rebuild(self) -> R {
+        self.to_end().unwrap()
+    }
+}
+
+impl<T, D> Zipper<T, D>
+where
+    T: Term,
+    D: Term,
+{
     // Returns the contents of the zipper, regardless of whether it is done or not
     pub fn unwrap_unchecked(self) -> T {
         match self {
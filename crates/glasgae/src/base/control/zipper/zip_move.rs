@@ -0,0 +1,70 @@
+use crate::{prelude::*, transformers::cont::Cont};
+
+use super::Zipper;
+
+pub trait ZipMove<D, M>: Term {
+    fn zip_move(self, dir: D) -> M;
+}
+
+impl<M, T, D, R> ZipMove<D, M> for Zipper<T, D, R>
+where
+    M: ReturnM<Pointed = Zipper<T, D, R>>,
+    T: Term,
+    D: Term,
+    R: Term,
+{
+    fn zip_move(self, dir: D) -> M {
+        match self {
+            Zipper::Zipper(_, n) => ReturnM::return_m(n((None, dir))),
+            done => ReturnM::return_m(done),
+        }
+    }
+}
+
+impl<M, T, D> ZipMove<D, M> for Cont<T>
+where
+    Self: Monad<T, Pointed = T, Chained = M>,
+    M: ReturnM<Pointed = T>,
+    T: ZipMove<D, M>,
+    D: Term,
+{
+    fn zip_move(self, dir: D) -> M {
+        self.chain_m(|t| t.zip_move(dir))
+    }
+}
+
+pub trait ZipAllTheWay<M, T, D>: Term
+where
+    T: Term,
+{
+    fn zip_all_the_way(self, dir: D, f: impl FunctionT<T, Option<T>>) -> M;
+}
+
+impl<M, T, D, R> ZipAllTheWay<M, T, D> for Zipper<T, D, R>
+where
+    M: ReturnM<Pointed = Zipper<T, D, R>>,
+    T: Term,
+    D: Term,
+    R: Term,
+{
+    fn zip_all_the_way(self, dir: D, f: impl FunctionT<T, Option<T>>) -> M {
+        let f = f.to_function();
+        match self {
+            Zipper::Zipper(t, k) => k((f.clone()(t), dir.clone())).zip_all_the_way(dir, f),
+            Zipper::ZipDone(r) => ReturnM::return_m(Zipper::done(r)),
+        }
+    }
+}
+
+impl<M, T, D> ZipAllTheWay<M, T::Pointed, D> for Cont<T>
+where
+    Self: Monad<T, Pointed = T, Chained = M>,
+    M: ReturnM<Pointed = T>,
+    T: Pointed + ZipAllTheWay<M, T::Pointed, D>,
+    D: Term,
+{
+    fn zip_all_the_way(self, dir: D, f: impl FunctionT<T::Pointed, Option<T::Pointed>>) -> M {
+        let f = f.to_function();
+        self.chain_m(|t| t.zip_all_the_way(dir, f))
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{prelude::*, transformers::cont::Cont};
+
+use super::Zipper;
+
+/// The continuation used to suspend a traversal of `T` at each of its `A` elements.
+pub type ZipTraversableT<A, T, B = A> = Cont<Zipper<A, (), T>, B>;
+
+/// Build a zipper over the elements of any traversable structure.
+///
+/// The structure is traversed with a [`Cont`] action that suspends at each element,
+/// capturing the remainder of the traversal up to an enclosing reset
+/// as the continuation of the zipper.
+///
+/// Since the traversal itself decides where to go next,
+/// the zipper has no meaningful direction, and moves with `()`.
+///
+/// ```
+/// # use glasgae::{base::control::zipper::zip_traversable, prelude::*};
+/// let zipper = zip_traversable(vec![1, 2, 3]);
+/// assert_eq!(zipper.focus(), Some(&1));
+///
+/// let zipper = zipper.next().replace(4);
+/// assert_eq!(zipper.focus(), Some(&3));
+/// assert_eq!(zipper.rebuild(), vec![1, 4, 3]);
+/// ```
+pub fn zip_traversable<T, A, I>(t: T) -> Zipper<A, (), T>
+where
+    T: TraverseT<
+        ZipTraversableT<A, T>,
+        I,
+        ZipTraversableT<A, T, T>,
+        Pointed = A,
+        Mapped = ZipTraversableT<A, T>,
+        Traversed = ZipTraversableT<A, T, T>,
+    >,
+    A: Term,
+{
+    t.traverse_t(|a: A| {
+        Cont::shift(
+            (move |k: Function<A, Zipper<A, (), T>>| {
+                ReturnM::return_m(Zipper::zip(a.clone(), move |(a_, _): (Option<A>, ())| {
+                    k(a_.unwrap_or(a))
+                }))
+            })
+            .boxed(),
+        )
+    })
+    .chain_m(|t| ReturnM::return_m(Zipper::done(t)))
+    .reset()
+    .eval()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{base::data::tree::rose_tree::RoseTree, prelude::*};
+
+    use super::zip_traversable;
+
+    #[test]
+    fn test_zip_vec() {
+        let zipper = zip_traversable(vec![1, 2, 3, 4]);
+        assert_eq!(zipper.focus(), Some(&1));
+
+        let zipper = zipper.replace(10).skip(2);
+        assert_eq!(zipper.focus(), Some(&4));
+
+        let zipper = zipper.replace(40);
+        assert!(zipper.is_done());
+        assert_eq!(zipper.unwrap(), vec![10, 2, 3, 40]);
+
+        assert_eq!(zip_traversable(vec![1, 2]).to_end().rebuild(), vec![1, 2]);
+        assert!(zip_traversable(Vec::<usize>::new()).is_done());
+    }
+
+    #[test]
+    fn test_zip_rose_tree() {
+        let tree = RoseTree(
+            1,
+            vec![RoseTree(2, vec![RoseTree(3, vec![])]), RoseTree(4, vec![])],
+        );
+
+        // Visit every node in order, doubling the odd ones
+        let mut zipper = zip_traversable(tree);
+        let mut visited = vec![];
+        while let Some(t) = zipper.focus().copied() {
+            visited.push(t);
+            zipper = if t % 2 == 1 {
+                zipper.replace(t * 2)
+            } else {
+                zipper.next()
+            };
+        }
+
+        assert_eq!(visited, vec![1, 2, 3, 4]);
+        assert_eq!(
+            zipper.unwrap(),
+            RoseTree(
+                2,
+                vec![RoseTree(2, vec![RoseTree(6, vec![])]), RoseTree(4, vec![]),],
+            )
+        );
+    }
+
+    #[test]
+    fn test_zip_maybe() {
+        let zipper = zip_traversable(Just(1));
+        assert_eq!(zipper.focus(), Some(&1));
+        assert_eq!(zipper.replace(2).unwrap(), Just(2));
+
+        let zipper = zip_traversable(Nothing::<usize>);
+        assert!(zipper.is_done());
+        assert_eq!(zipper.rebuild(), Nothing);
+    }
+}
@@ -0,0 +1,383 @@
+//! The cofree comonad over a [`Functor`].
+//!
+//! [`Cofree<F, A>`] is a non-empty, `F`-branching structure
+//! with a value of type `A` annotating every node.
+//!
+//! As with [`Free`](crate::base::control::monad::free::Free),
+//! `F` is a concrete instantiation of the branching functor (conventionally `Branch<()>`),
+//! and the layer of each node is recovered as [`CofreeLayerT<F, A>`].
+//!
+//! [`Cofree::extract`] reads the annotation at the root of a structure,
+//! and [`Cofree::extend`] recomputes every annotation from the subtree beneath it.
+//! This is a convenient way to attach and propagate annotations, such as types or spans,
+//! across a syntax tree.
+//!
+//! A [`RoseTree<A>`] is isomorphic to `Cofree<Vec<()>, A>`,
+//! and can be converted in either direction with [`From`].
+//!
+//! # Example
+//!
+//! ```
+//! # use glasgae::{base::data::{cofree::Cofree, tree::rose_tree::RoseTree}, prelude::*};
+//! let tree: Cofree<Vec<()>, char> = RoseTree(
+//!     'a',
+//!     vec![RoseTree('b', vec![]), RoseTree('c', vec![RoseTree('d', vec![])])],
+//! )
+//! .into();
+//!
+//! // Annotate each node with the size of the subtree beneath it
+//! let sizes = tree.extend(|t: Cofree<Vec<()>, char>| t.foldr(|_, n| n + 1, 0));
+//!
+//! assert_eq!(
+//!     RoseTree::from(sizes),
+//!     RoseTree(4, vec![RoseTree(1, vec![]), RoseTree(2, vec![RoseTree(1, vec![])])])
+//! );
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{base::data::tree::rose_tree::RoseTree, prelude::*};
+
+/// The `F`-shaped layer of children beneath a [`Cofree`] node.
+pub type CofreeLayerT<F, A> = WithPointedT<F, Cofree<F, A>>;
+
+/// The cofree comonad over the functor `F`.
+pub struct Cofree<F, A>(A, Erased, PhantomData<fn() -> F>);
+
+impl<F, A> Clone for Cofree<F, A>
+where
+    A: Clone,
+{
+    fn clone(&self) -> Self {
+        Cofree(self.0.clone(), self.1.clone(), PhantomData)
+    }
+}
+
+impl<F, A> Cofree<F, A>
+where
+    F: Term + WithPointed<Cofree<F, A>>,
+    A: Term,
+{
+    /// Construct a node from its annotation and its layer of children.
+    pub fn new(a: A, children: CofreeLayerT<F, A>) -> Self {
+        Cofree(a, children.boxed(), PhantomData)
+    }
+
+    /// Extract the annotation at the root of the structure.
+    pub fn extract(self) -> A {
+        self.0
+    }
+
+    /// Returns the layer of children beneath the root.
+    pub fn unwrap(self) -> CofreeLayerT<F, A> {
+        self.1.downcast()
+    }
+
+    /// Split a node into its annotation and its layer of children.
+    pub fn run(self) -> (A, CofreeLayerT<F, A>) {
+        (self.0, self.1.downcast())
+    }
+
+    /// Annotate every node with the result of applying `f` to the subtree rooted there.
+    ///
+    /// ```text
+    /// extend(extract) == identity
+    /// extract(extend(f)(w)) == f(w)
+    /// ```
+    pub fn extend<B>(self, f: impl FunctionT<Cofree<F, A>, B>) -> Cofree<F, B>
+    where
+        F: WithPointed<Cofree<F, B>>,
+        CofreeLayerT<F, A>: Functor<Cofree<F, B>, Mapped = CofreeLayerT<F, B>>,
+        B: Term,
+    {
+        let f = f.to_function();
+        let b = f.clone()(self.clone());
+        Cofree::new(b, self.unwrap().fmap(|w: Cofree<F, A>| w.extend(f)))
+    }
+
+    /// Annotate every node with the subtree rooted there.
+    ///
+    /// ```text
+    /// duplicate == extend(identity)
+    /// ```
+    pub fn duplicate(self) -> Cofree<F, Cofree<F, A>>
+    where
+        F: WithPointed<Cofree<F, Cofree<F, A>>>,
+        CofreeLayerT<F, A>:
+            Functor<Cofree<F, Cofree<F, A>>, Mapped = CofreeLayerT<F, Cofree<F, A>>>,
+    {
+        self.extend(identity)
+    }
+
+    /// Unfold a structure from a seed, annotating each node with the seed that produced it.
+    ///
+    /// ```text
+    /// coiter(psi, a) == Cofree::new(a, psi(a).fmap(|a| coiter(psi, a)))
+    /// ```
+    pub fn coiter(psi: impl FunctionT<A, WithPointedT<F, A>>, a: A) -> Self
+    where
+        F: WithPointed<A>,
+        WithPointedT<F, A>: Functor<Cofree<F, A>, Mapped = CofreeLayerT<F, A>>,
+    {
+        let psi = psi.to_function();
+        let children = psi.clone()(a.clone()).fmap(|a: A| Cofree::coiter(psi, a));
+        Cofree::new(a, children)
+    }
+
+    /// Unfold a structure from a seed,
+    /// producing an annotation and a layer of new seeds at each node.
+    ///
+    /// ```text
+    /// unfold(f, b) == { let (a, bs) = f(b); Cofree::new(a, bs.fmap(|b| unfold(f, b))) }
+    /// ```
+    pub fn unfold<B>(f: impl FunctionT<B, (A, WithPointedT<F, B>)>, b: B) -> Self
+    where
+        F: WithPointed<B>,
+        WithPointedT<F, B>: Functor<Cofree<F, A>, Mapped = CofreeLayerT<F, A>>,
+        B: Term,
+    {
+        let f = f.to_function();
+        let (a, bs) = f.clone()(b);
+        Cofree::new(a, bs.fmap(|b: B| Cofree::unfold(f, b)))
+    }
+}
+
+impl<F, A> Pointed for Cofree<F, A>
+where
+    F: Term,
+    A: Term,
+{
+    type Pointed = A;
+}
+
+impl<F, A, B> WithPointed<B> for Cofree<F, A>
+where
+    F: Term,
+    A: Term,
+    B: Term,
+{
+    type WithPointed = Cofree<F, B>;
+}
+
+impl<F, A, B> Functor<B> for Cofree<F, A>
+where
+    F: Term + WithPointed<Cofree<F, A>> + WithPointed<Cofree<F, B>>,
+    CofreeLayerT<F, A>: Functor<Cofree<F, B>, Mapped = CofreeLayerT<F, B>>,
+    CofreeLayerT<F, B>: Functor<Cofree<F, A>, Mapped = CofreeLayerT<F, A>>,
+    A: Term,
+    B: Term,
+{
+    type Mapped = Cofree<F, B>;
+
+    fn fmap(self, f: impl FunctionT<A, B>) -> Cofree<F, B> {
+        let f = f.to_function();
+        let (a, children) = self.run();
+        Cofree::new(f.clone()(a), children.fmap(|w: Cofree<F, A>| w.fmap(f)))
+    }
+}
+
+impl<F, A, U> Foldable<U> for Cofree<F, A>
+where
+    F: Term + WithPointed<Cofree<F, A>>,
+    CofreeLayerT<F, A>: Foldable<U, Pointed = Cofree<F, A>>,
+    A: Term,
+    U: Term,
+{
+    fn foldr(self, f: impl BifunT<A, U, U>, z: U) -> U {
+        let f = f.to_bifun();
+        let (a, children) = self.run();
+        f.clone()(a, children.foldr(|w: Cofree<F, A>, acc| w.foldr(f, acc), z))
+    }
+
+    fn foldl(self, f: impl BifunT<U, A, U>, z: U) -> U {
+        let f = f.to_bifun();
+        let (a, children) = self.run();
+        let z = f.clone()(z, a);
+        children.foldl(move |acc, w: Cofree<F, A>| w.foldl(f, acc), z)
+    }
+}
+
+impl<F, A, A1, AF1, B, A2, A3> TraverseT<A1, A2, A3> for Cofree<F, A>
+where
+    F: Term + WithPointed<Cofree<F, A>> + WithPointed<Cofree<F, B>>,
+    CofreeLayerT<F, A>: TraverseT<A3, (), A2, Pointed = Cofree<F, A>, Mapped = A3, Traversed = A2>,
+    A: Term,
+    A1: Pointed<Pointed = B>
+        + Functor<Function<CofreeLayerT<F, B>, Cofree<F, B>>, Mapped = AF1>
+        + WithPointed<CofreeLayerT<F, B>, WithPointed = A2>
+        + WithPointed<Cofree<F, B>, WithPointed = A3>,
+    AF1: Applicative<CofreeLayerT<F, B>, Cofree<F, B>, WithA = A2, WithB = A3>,
+    B: Term,
+    A2: Pointed<Pointed = CofreeLayerT<F, B>>,
+    A3: Pointed<Pointed = Cofree<F, B>>,
+{
+    type Mapped = A1;
+    type Value = B;
+    type Traversed = A3;
+
+    fn traverse_t(self, f: impl FunctionT<A, A1>) -> A3 {
+        let f = f.to_function();
+        let (a, children) = self.run();
+        Cofree::new.lift_a2()(
+            f.clone()(a),
+            children.traverse_t(|w: Cofree<F, A>| w.traverse_t(f)),
+        )
+    }
+}
+
+impl<F, A1, A2, A3> SequenceA<A2, A3> for Cofree<F, A1>
+where
+    Self: TraverseT<A1, A2, A3, Pointed = A1, Mapped = A1, Traversed = A3>,
+    F: Term,
+    A1: Pointed + WithPointed<Function<Cofree<F, A1>, Cofree<F, PointedT<A1>>>>,
+    A2: Term,
+    A3: Term,
+{
+    type Inner = A1;
+    type Value = PointedT<A1>;
+    type Sequenced = A3;
+
+    fn sequence_a(self) -> A3 {
+        sequence_a_default(self)
+    }
+}
+
+impl<A> From<RoseTree<A>> for Cofree<Vec<()>, A>
+where
+    A: Term,
+{
+    fn from(RoseTree(a, children): RoseTree<A>) -> Self {
+        Cofree::new(a, children.into_iter().map(Cofree::from).collect())
+    }
+}
+
+impl<A> From<Cofree<Vec<()>, A>> for RoseTree<A>
+where
+    A: Term,
+{
+    fn from(w: Cofree<Vec<()>, A>) -> Self {
+        let (a, children) = w.run();
+        RoseTree(a, children.into_iter().map(RoseTree::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{base::data::tree::rose_tree::RoseTree, prelude::*};
+
+    use super::Cofree;
+
+    type Tree<A> = Cofree<Vec<()>, A>;
+
+    fn tree() -> Tree<usize> {
+        RoseTree(
+            1,
+            vec![
+                RoseTree(2, vec![RoseTree(4, vec![]), RoseTree(5, vec![])]),
+                RoseTree(3, vec![]),
+            ],
+        )
+        .into()
+    }
+
+    #[test]
+    fn test_rose_tree_roundtrip() {
+        let rose = RoseTree(1, vec![RoseTree(2, vec![]), RoseTree(3, vec![])]);
+        assert_eq!(RoseTree::from(Tree::from(rose.clone())), rose);
+    }
+
+    #[test]
+    fn test_extend() {
+        let sums = tree().extend(|w: Tree<usize>| w.foldr(|a, acc| a + acc, 0));
+        assert_eq!(
+            RoseTree::from(sums),
+            RoseTree(
+                15,
+                vec![
+                    RoseTree(11, vec![RoseTree(4, vec![]), RoseTree(5, vec![])]),
+                    RoseTree(3, vec![]),
+                ],
+            )
+        );
+
+        // Comonad laws
+        assert_eq!(
+            RoseTree::from(tree().extend(Cofree::extract)),
+            RoseTree::from(tree())
+        );
+
+        let f = |w: Tree<usize>| w.unwrap().len();
+        assert_eq!(tree().extend(f).extract(), f(tree()));
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let nested = tree().duplicate();
+        let children = nested.unwrap();
+        assert_eq!(
+            RoseTree::from(children[0].clone().extract()),
+            RoseTree(2, vec![RoseTree(4, vec![]), RoseTree(5, vec![])])
+        );
+    }
+
+    #[test]
+    fn test_functor_foldable() {
+        let doubled = tree().fmap(|a: usize| a * 2);
+        let push = |mut acc: Vec<usize>, a| {
+            acc.push(a);
+            acc
+        };
+        assert_eq!(
+            doubled.clone().foldr(move |a, acc| push(acc, a), vec![]),
+            vec![6, 10, 8, 4, 2]
+        );
+        assert_eq!(doubled.foldl(push, vec![]), vec![2, 4, 8, 10, 6]);
+    }
+
+    #[test]
+    fn test_traverse() {
+        let out: Option<Tree<usize>> = tree().traverse_t(|a: usize| Some(a + 1));
+        assert_eq!(
+            out.map(RoseTree::from),
+            Some(RoseTree(
+                2,
+                vec![
+                    RoseTree(3, vec![RoseTree(5, vec![]), RoseTree(6, vec![])]),
+                    RoseTree(4, vec![]),
+                ],
+            ))
+        );
+
+        let out: Option<Tree<usize>> =
+            tree().traverse_t(|a: usize| if a == 4 { None } else { Some(a) });
+        assert!(out.is_none());
+
+        let out: Option<Tree<usize>> = tree().fmap(Some).sequence_a();
+        assert_eq!(out.map(RoseTree::from), Some(RoseTree::from(tree())));
+    }
+
+    #[test]
+    fn test_coiter() {
+        // A complete binary tree of depth 2, annotated with the depth of each node
+        let tree = Tree::coiter(
+            |n: usize| if n < 2 { vec![n + 1, n + 1] } else { vec![] },
+            0,
+        );
+        assert_eq!(tree.foldr(|a, acc| a + acc, 0), 10);
+    }
+
+    #[test]
+    fn test_unfold() {
+        let tree = Tree::unfold(|n: usize| (n.to_string(), (1..n).collect::<Vec<_>>()), 3);
+        assert_eq!(
+            RoseTree::from(tree),
+            RoseTree(
+                "3".to_string(),
+                vec![
+                    RoseTree("1".to_string(), vec![]),
+                    RoseTree("2".to_string(), vec![RoseTree("1".to_string(), vec![])]),
+                ],
+            )
+        );
+    }
+}
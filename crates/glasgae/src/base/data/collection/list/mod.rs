@@ -6,9 +6,11 @@ pub use filter::*;
 
 pub mod array;
 pub mod linked_list;
+pub mod seq;
 pub mod string;
 pub mod vec;
 pub mod vec_deque;
+pub mod zip_list;
 
 pub trait ToVec<T> {
     fn to_vec(self) -> Vec<T>;
@@ -0,0 +1,490 @@
+//! 2-3 finger tree annotated with subtree sizes.
+//!
+//! Rust cannot express the nested type `FingerTree<Node<T>>` of the middle tree
+//! without polymorphic recursion, so nodes of every depth share the [`Node`] type,
+//! and the depth of each element is an invariant of the tree rather than of its type:
+//! the elements of the outermost tree are leaves,
+//! and the elements of each middle tree are nodes of the depth below.
+
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+/// An element of a tree: a leaf at the outermost level,
+/// or a 2-3 node of elements one level down.
+pub(super) enum Node<T> {
+    Leaf(T),
+    Node2(usize, Arc<Node<T>>, Arc<Node<T>>),
+    Node3(usize, Arc<Node<T>>, Arc<Node<T>>, Arc<Node<T>>),
+}
+
+/// One to four elements at the end of a deep tree.
+pub(super) type Digit<T> = Vec<Arc<Node<T>>>;
+
+pub(super) enum Tree<T> {
+    Empty,
+    Single(Arc<Node<T>>),
+    Deep(usize, Digit<T>, Arc<Tree<T>>, Digit<T>),
+}
+
+impl<T> Clone for Tree<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Tree::Empty => Tree::Empty,
+            Tree::Single(x) => Tree::Single(x.clone()),
+            Tree::Deep(s, pr, m, sf) => Tree::Deep(*s, pr.clone(), m.clone(), sf.clone()),
+        }
+    }
+}
+
+impl<T> Node<T> {
+    pub fn leaf(t: T) -> Arc<Self> {
+        Arc::new(Node::Leaf(t))
+    }
+
+    fn node2(a: Arc<Self>, b: Arc<Self>) -> Arc<Self> {
+        Arc::new(Node::Node2(a.size() + b.size(), a, b))
+    }
+
+    fn node3(a: Arc<Self>, b: Arc<Self>, c: Arc<Self>) -> Arc<Self> {
+        Arc::new(Node::Node3(a.size() + b.size() + c.size(), a, b, c))
+    }
+
+    pub fn size(&self) -> usize {
+        match self {
+            Node::Leaf(_) => 1,
+            Node::Node2(s, ..) | Node::Node3(s, ..) => *s,
+        }
+    }
+
+    /// The elements of a 2-3 node, as a digit.
+    fn to_digit(&self) -> Digit<T> {
+        match self {
+            Node::Leaf(_) => unreachable!("a leaf has no children"),
+            Node::Node2(_, a, b) => vec![a.clone(), b.clone()],
+            Node::Node3(_, a, b, c) => vec![a.clone(), b.clone(), c.clone()],
+        }
+    }
+
+    pub fn lookup(&self, i: usize) -> &T {
+        match self {
+            Node::Leaf(t) => t,
+            Node::Node2(_, a, b) => lookup_digit(i, [a, b]),
+            Node::Node3(_, a, b, c) => lookup_digit(i, [a, b, c]),
+        }
+    }
+
+    fn adjust(&self, i: usize, f: &Function<T, T>) -> Arc<Self>
+    where
+        T: Term,
+    {
+        match self {
+            Node::Leaf(t) => Node::leaf(f.clone()(t.clone())),
+            node => {
+                let digit = adjust_digit(i, node.to_digit(), f);
+                match &digit[..] {
+                    [a, b] => Node::node2(a.clone(), b.clone()),
+                    [a, b, c] => Node::node3(a.clone(), b.clone(), c.clone()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn size_digit<T>(digit: &[Arc<Node<T>>]) -> usize {
+    digit.iter().map(|x| x.size()).sum()
+}
+
+fn lookup_digit<'a, T>(mut i: usize, digit: impl IntoIterator<Item = &'a Arc<Node<T>>>) -> &'a T
+where
+    T: 'a,
+{
+    for x in digit {
+        if i < x.size() {
+            return x.lookup(i);
+        }
+        i -= x.size();
+    }
+    unreachable!("index out of bounds")
+}
+
+fn adjust_digit<T>(mut i: usize, mut digit: Digit<T>, f: &Function<T, T>) -> Digit<T>
+where
+    T: Term,
+{
+    for x in digit.iter_mut() {
+        if i < x.size() {
+            *x = x.adjust(i, f);
+            return digit;
+        }
+        i -= x.size();
+    }
+    unreachable!("index out of bounds")
+}
+
+/// Split a digit around the element containing index `i`,
+/// returning the offset of `i` within that element.
+fn split_digit<T>(mut i: usize, digit: Digit<T>) -> (Digit<T>, Arc<Node<T>>, usize, Digit<T>) {
+    let mut l = vec![];
+    let mut xs = digit.into_iter();
+    while let Some(x) = xs.next() {
+        if i < x.size() {
+            return (l, x, i, xs.collect());
+        }
+        i -= x.size();
+        l.push(x);
+    }
+    unreachable!("index out of bounds")
+}
+
+/// Group a list of at least two elements into 2-3 nodes.
+fn nodes<T>(xs: Digit<T>) -> Digit<T> {
+    let mut out = vec![];
+    let mut xs = xs.into_iter();
+    loop {
+        let rest = xs.len();
+        match rest {
+            2 => {
+                let (a, b) = (xs.next().unwrap(), xs.next().unwrap());
+                out.push(Node::node2(a, b));
+                return out;
+            }
+            3 => {
+                let (a, b, c) = (xs.next().unwrap(), xs.next().unwrap(), xs.next().unwrap());
+                out.push(Node::node3(a, b, c));
+                return out;
+            }
+            4 => {
+                let (a, b) = (xs.next().unwrap(), xs.next().unwrap());
+                let (c, d) = (xs.next().unwrap(), xs.next().unwrap());
+                out.push(Node::node2(a, b));
+                out.push(Node::node2(c, d));
+                return out;
+            }
+            _ => {
+                let (a, b, c) = (xs.next().unwrap(), xs.next().unwrap(), xs.next().unwrap());
+                out.push(Node::node3(a, b, c));
+            }
+        }
+    }
+}
+
+impl<T> Tree<T> {
+    pub fn size(&self) -> usize {
+        match self {
+            Tree::Empty => 0,
+            Tree::Single(x) => x.size(),
+            Tree::Deep(s, ..) => *s,
+        }
+    }
+
+    fn deep(pr: Digit<T>, m: Arc<Tree<T>>, sf: Digit<T>) -> Self {
+        let size = size_digit(&pr) + m.size() + size_digit(&sf);
+        Tree::Deep(size, pr, m, sf)
+    }
+
+    fn from_digit(digit: Digit<T>) -> Self {
+        digit.into_iter().fold(Tree::Empty, Tree::snoc)
+    }
+
+    /// Build a deep tree whose prefix may be empty.
+    fn deep_l(pr: Digit<T>, m: Arc<Tree<T>>, sf: Digit<T>) -> Self {
+        if !pr.is_empty() {
+            return Tree::deep(pr, m, sf);
+        }
+
+        match (*m).clone().view_l() {
+            None => Tree::from_digit(sf),
+            Some((x, m)) => Tree::deep(x.to_digit(), Arc::new(m), sf),
+        }
+    }
+
+    /// Build a deep tree whose suffix may be empty.
+    fn deep_r(pr: Digit<T>, m: Arc<Tree<T>>, sf: Digit<T>) -> Self {
+        if !sf.is_empty() {
+            return Tree::deep(pr, m, sf);
+        }
+
+        match (*m).clone().view_r() {
+            None => Tree::from_digit(pr),
+            Some((m, x)) => Tree::deep(pr, Arc::new(m), x.to_digit()),
+        }
+    }
+
+    pub fn cons(self, a: Arc<Node<T>>) -> Self {
+        match self {
+            Tree::Empty => Tree::Single(a),
+            Tree::Single(b) => Tree::deep(vec![a], Arc::new(Tree::Empty), vec![b]),
+            Tree::Deep(s, mut pr, m, sf) => {
+                let s = s + a.size();
+                if pr.len() == 4 {
+                    let mut rest = pr.split_off(1);
+                    let e = rest.pop().unwrap();
+                    let d = rest.pop().unwrap();
+                    let c = rest.pop().unwrap();
+                    let b = pr.pop().unwrap();
+                    let m = (*m).clone().cons(Node::node3(c, d, e));
+                    Tree::Deep(s, vec![a, b], Arc::new(m), sf)
+                } else {
+                    pr.insert(0, a);
+                    Tree::Deep(s, pr, m, sf)
+                }
+            }
+        }
+    }
+
+    pub fn snoc(self, a: Arc<Node<T>>) -> Self {
+        match self {
+            Tree::Empty => Tree::Single(a),
+            Tree::Single(b) => Tree::deep(vec![b], Arc::new(Tree::Empty), vec![a]),
+            Tree::Deep(s, pr, m, mut sf) => {
+                let s = s + a.size();
+                if sf.len() == 4 {
+                    let e = sf.pop().unwrap();
+                    let d = sf.pop().unwrap();
+                    let c = sf.pop().unwrap();
+                    let b = sf.pop().unwrap();
+                    let m = (*m).clone().snoc(Node::node3(b, c, d));
+                    Tree::Deep(s, pr, Arc::new(m), vec![e, a])
+                } else {
+                    sf.push(a);
+                    Tree::Deep(s, pr, m, sf)
+                }
+            }
+        }
+    }
+
+    pub fn view_l(self) -> Option<(Arc<Node<T>>, Self)> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(x) => Some((x, Tree::Empty)),
+            Tree::Deep(_, mut pr, m, sf) => {
+                let x = pr.remove(0);
+                Some((x, Tree::deep_l(pr, m, sf)))
+            }
+        }
+    }
+
+    pub fn view_r(self) -> Option<(Self, Arc<Node<T>>)> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(x) => Some((Tree::Empty, x)),
+            Tree::Deep(_, pr, m, mut sf) => {
+                let x = sf.pop().unwrap();
+                Some((Tree::deep_r(pr, m, sf), x))
+            }
+        }
+    }
+
+    pub fn front(&self) -> Option<&Arc<Node<T>>> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(x) => Some(x),
+            Tree::Deep(_, pr, ..) => pr.first(),
+        }
+    }
+
+    pub fn back(&self) -> Option<&Arc<Node<T>>> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(x) => Some(x),
+            Tree::Deep(.., sf) => sf.last(),
+        }
+    }
+
+    /// Concatenate two trees with a list of elements between them.
+    pub fn app3(self, ts: Digit<T>, other: Self) -> Self {
+        match (self, other) {
+            (Tree::Empty, t) => ts.into_iter().rev().fold(t, Tree::cons),
+            (t, Tree::Empty) => ts.into_iter().fold(t, Tree::snoc),
+            (Tree::Single(x), t) => Tree::Empty.app3(ts, t).cons(x),
+            (t, Tree::Single(x)) => t.app3(ts, Tree::Empty).snoc(x),
+            (Tree::Deep(s1, pr1, m1, sf1), Tree::Deep(s2, pr2, m2, sf2)) => {
+                let s = s1 + size_digit(&ts) + s2;
+                let mid = sf1.into_iter().chain(ts).chain(pr2).collect();
+                let m = (*m1).clone().app3(nodes(mid), (*m2).clone());
+                Tree::Deep(s, pr1, Arc::new(m), sf2)
+            }
+        }
+    }
+
+    /// Split a tree around the element containing index `i`,
+    /// returning the offset of `i` within that element.
+    ///
+    /// `i` must be less than the size of the tree.
+    pub fn split(self, i: usize) -> (Self, Arc<Node<T>>, usize, Self) {
+        match self {
+            Tree::Empty => unreachable!("split on an empty tree"),
+            Tree::Single(x) => (Tree::Empty, x, i, Tree::Empty),
+            Tree::Deep(_, pr, m, sf) => {
+                let spr = size_digit(&pr);
+                let sm = m.size();
+                if i < spr {
+                    let (l, x, j, r) = split_digit(i, pr);
+                    (Tree::from_digit(l), x, j, Tree::deep_l(r, m, sf))
+                } else if i < spr + sm {
+                    let (ml, xs, j, mr) = (*m).clone().split(i - spr);
+                    let (l, x, k, r) = split_digit(j, xs.to_digit());
+                    (
+                        Tree::deep_r(pr, Arc::new(ml), l),
+                        x,
+                        k,
+                        Tree::deep_l(r, Arc::new(mr), sf),
+                    )
+                } else {
+                    let (l, x, j, r) = split_digit(i - spr - sm, sf);
+                    (Tree::deep_r(pr, m, l), x, j, Tree::from_digit(r))
+                }
+            }
+        }
+    }
+
+    /// The leaf at index `i`, which must be less than the size of the tree.
+    pub fn lookup(&self, i: usize) -> &T {
+        match self {
+            Tree::Empty => unreachable!("lookup on an empty tree"),
+            Tree::Single(x) => x.lookup(i),
+            Tree::Deep(_, pr, m, sf) => {
+                let spr = size_digit(pr);
+                let sm = m.size();
+                if i < spr {
+                    lookup_digit(i, pr)
+                } else if i < spr + sm {
+                    m.lookup(i - spr)
+                } else {
+                    lookup_digit(i - spr - sm, sf)
+                }
+            }
+        }
+    }
+
+    /// Replace the leaf at index `i` with the result of `f`,
+    /// sharing every unaffected subtree.
+    pub fn adjust(&self, i: usize, f: &Function<T, T>) -> Self
+    where
+        T: Term,
+    {
+        match self {
+            Tree::Empty => unreachable!("adjust on an empty tree"),
+            Tree::Single(x) => Tree::Single(x.adjust(i, f)),
+            Tree::Deep(s, pr, m, sf) => {
+                let spr = size_digit(pr);
+                let sm = m.size();
+                if i < spr {
+                    Tree::Deep(*s, adjust_digit(i, pr.clone(), f), m.clone(), sf.clone())
+                } else if i < spr + sm {
+                    Tree::Deep(*s, pr.clone(), Arc::new(m.adjust(i - spr, f)), sf.clone())
+                } else {
+                    let sf = adjust_digit(i - spr - sm, sf.clone(), f);
+                    Tree::Deep(*s, pr.clone(), m.clone(), sf)
+                }
+            }
+        }
+    }
+}
+
+/// A pending part of a borrowing tree walk.
+enum Frame<'a, T> {
+    Node(&'a Node<T>),
+    Tree(&'a Tree<T>),
+}
+
+/// Pre-order walk over the leaves of a tree, from left to right.
+pub(super) struct Leaves<'a, T> {
+    stack: Vec<Frame<'a, T>>,
+}
+
+impl<'a, T> Leaves<'a, T> {
+    pub fn new(tree: &'a Tree<T>) -> Self {
+        Leaves {
+            stack: vec![Frame::Tree(tree)],
+        }
+    }
+}
+
+impl<'a, T> Iterator for Leaves<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        while let Some(frame) = self.stack.pop() {
+            match frame {
+                Frame::Node(Node::Leaf(t)) => return Some(t),
+                Frame::Node(Node::Node2(_, a, b)) => {
+                    self.stack.extend([Frame::Node(b), Frame::Node(a)]);
+                }
+                Frame::Node(Node::Node3(_, a, b, c)) => {
+                    self.stack
+                        .extend([Frame::Node(c), Frame::Node(b), Frame::Node(a)]);
+                }
+                Frame::Tree(Tree::Empty) => (),
+                Frame::Tree(Tree::Single(x)) => self.stack.push(Frame::Node(x)),
+                Frame::Tree(Tree::Deep(_, pr, m, sf)) => {
+                    self.stack.extend(sf.iter().rev().map(|x| Frame::Node(x)));
+                    self.stack.push(Frame::Tree(m));
+                    self.stack.extend(pr.iter().rev().map(|x| Frame::Node(x)));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A pending part of a consuming tree walk.
+enum OwnedFrame<T> {
+    Node(Arc<Node<T>>),
+    Tree(Arc<Tree<T>>),
+}
+
+/// Consuming pre-order walk over the leaves of a tree, from left to right.
+///
+/// Leaves which are still shared with another tree are cloned.
+pub(super) struct IntoLeaves<T> {
+    stack: Vec<OwnedFrame<T>>,
+}
+
+impl<T> IntoLeaves<T> {
+    pub fn new(tree: Tree<T>) -> Self {
+        IntoLeaves {
+            stack: vec![OwnedFrame::Tree(Arc::new(tree))],
+        }
+    }
+}
+
+impl<T> Iterator for IntoLeaves<T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(frame) = self.stack.pop() {
+            match frame {
+                OwnedFrame::Node(node) => match Arc::try_unwrap(node) {
+                    Ok(Node::Leaf(t)) => return Some(t),
+                    Err(node) => match &*node {
+                        Node::Leaf(t) => return Some(t.clone()),
+                        node => self
+                            .stack
+                            .extend(node.to_digit().into_iter().rev().map(OwnedFrame::Node)),
+                    },
+                    Ok(node) => self
+                        .stack
+                        .extend(node.to_digit().into_iter().rev().map(OwnedFrame::Node)),
+                },
+                OwnedFrame::Tree(tree) => match &*tree {
+                    Tree::Empty => (),
+                    Tree::Single(x) => self.stack.push(OwnedFrame::Node(x.clone())),
+                    Tree::Deep(_, pr, m, sf) => {
+                        self.stack
+                            .extend(sf.iter().rev().cloned().map(OwnedFrame::Node));
+                        self.stack.push(OwnedFrame::Tree(m.clone()));
+                        self.stack
+                            .extend(pr.iter().rev().cloned().map(OwnedFrame::Node));
+                    }
+                },
+            }
+        }
+        None
+    }
+}
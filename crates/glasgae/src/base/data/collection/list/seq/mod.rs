@@ -0,0 +1,552 @@
+//! Persistent sequences, backed by finger trees.
+//!
+//! Based on [Data.Sequence](https://hackage.haskell.org/package/containers/docs/Data-Sequence.html),
+//! and [Finger trees: a simple general-purpose data structure](https://www.staff.city.ac.uk/~ross/papers/FingerTree.html)
+//! by Ralf Hinze and Ross Paterson.
+//!
+//! Unlike [`Vec`], a [`Seq`] shares structure between versions,
+//! so cloning is cheap and operations which return a modified sequence
+//! leave the original intact without copying it.
+//!
+//! | Operation | Complexity |
+//! |-|-|
+//! | [`cons`](Seq::cons), [`snoc`](Seq::snoc), [`uncons`](Seq::uncons), [`unsnoc`](Seq::unsnoc) | amortized O(1) |
+//! | [`lookup`](Seq::lookup), [`adjust`](Seq::adjust), [`split_at`](Seq::split_at) | O(log(min(i, n - i))) |
+//! | [`concat`](Seq::concat) | O(log(min(n, m))) |
+//!
+//! ```
+//! # use glasgae::base::data::collection::list::{seq::Seq, ToVec};
+//! let xs = Seq::from_iter(1..=5);
+//! let ys = xs.clone().snoc(6).cons(0);
+//!
+//! assert_eq!(xs.len(), 5);
+//! assert_eq!(ys.lookup(6), Some(&6));
+//!
+//! let (l, r) = ys.split_at(3);
+//! assert_eq!(l, Seq::from_iter([0, 1, 2]));
+//! assert_eq!(r.concat(xs).to_vec(), vec![3, 4, 5, 6, 1, 2, 3, 4, 5]);
+//! ```
+
+mod finger_tree;
+
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    ops::Index,
+};
+
+use crate::{
+    base::data::collection::list::{Append, Filter, ToVec},
+    derive_applicative_iterable, derive_foldable_iterable, derive_functor_iterable,
+    derive_monad_iterable, derive_monoid_iterable, derive_pointed, derive_traversable_iterable,
+    derive_with_pointed,
+    prelude::*,
+};
+
+use finger_tree::{IntoLeaves, Leaves, Node, Tree};
+
+/// A finite, persistent sequence of values.
+pub struct Seq<T>(Tree<T>);
+
+impl<T> Seq<T> {
+    /// The empty sequence.
+    pub fn new() -> Self {
+        Seq(Tree::Empty)
+    }
+
+    /// A sequence containing a single element.
+    pub fn singleton(t: T) -> Self {
+        Seq(Tree::Single(Node::leaf(t)))
+    }
+
+    /// The number of elements in the sequence.
+    pub fn len(&self) -> usize {
+        self.0.size()
+    }
+
+    /// Returns `true` if the sequence contains no elements.
+    pub fn is_empty(&self) -> bool {
+        matches!(self.0, Tree::Empty)
+    }
+
+    /// Add an element to the front of the sequence.
+    pub fn cons(self, t: T) -> Self {
+        Seq(self.0.cons(Node::leaf(t)))
+    }
+
+    /// Add an element to the back of the sequence.
+    pub fn snoc(self, t: T) -> Self {
+        Seq(self.0.snoc(Node::leaf(t)))
+    }
+
+    /// The first element of the sequence.
+    pub fn front(&self) -> Option<&T> {
+        self.0.front().map(|x| x.lookup(0))
+    }
+
+    /// The last element of the sequence.
+    pub fn back(&self) -> Option<&T> {
+        self.0.back().map(|x| x.lookup(0))
+    }
+
+    /// The element at index `i`, or `None` if it is out of bounds.
+    pub fn lookup(&self, i: usize) -> Option<&T> {
+        if i < self.len() {
+            Some(self.0.lookup(i))
+        } else {
+            None
+        }
+    }
+
+    /// Concatenate two sequences.
+    pub fn concat(self, other: Self) -> Self {
+        Seq(self.0.app3(vec![], other.0))
+    }
+
+    /// Split the sequence into the first `i` elements and the rest.
+    ///
+    /// If `i` is out of bounds, the second half is empty.
+    pub fn split_at(self, i: usize) -> (Self, Self) {
+        if i == 0 {
+            (Seq::new(), self)
+        } else if i >= self.len() {
+            (self, Seq::new())
+        } else {
+            let (l, x, _, r) = self.0.split(i);
+            (Seq(l), Seq(r.cons(x)))
+        }
+    }
+
+    /// The first `n` elements of the sequence.
+    pub fn take(self, n: usize) -> Self {
+        self.split_at(n).0
+    }
+
+    /// All but the first `n` elements of the sequence.
+    pub fn drop(self, n: usize) -> Self {
+        self.split_at(n).1
+    }
+
+    /// Iterate over references to the elements of the sequence, from front to back.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        Leaves::new(&self.0)
+    }
+}
+
+impl<T> Seq<T>
+where
+    T: Term,
+{
+    /// A sequence of `n` copies of `t`.
+    pub fn replicate(n: usize, t: T) -> Self {
+        std::iter::repeat_n(t, n).collect()
+    }
+
+    /// Remove the first element of the sequence.
+    pub fn uncons(self) -> Option<(T, Self)> {
+        let (x, rest) = self.0.view_l()?;
+        Some((x.lookup(0).clone(), Seq(rest)))
+    }
+
+    /// Remove the last element of the sequence.
+    pub fn unsnoc(self) -> Option<(Self, T)> {
+        let (rest, x) = self.0.view_r()?;
+        Some((Seq(rest), x.lookup(0).clone()))
+    }
+
+    /// Apply `f` to the element at index `i`,
+    /// or return the sequence unchanged if it is out of bounds.
+    pub fn adjust(self, i: usize, f: impl FunctionT<T, T>) -> Self {
+        if i < self.len() {
+            Seq(self.0.adjust(i, &f.to_function()))
+        } else {
+            self
+        }
+    }
+
+    /// Replace the element at index `i`,
+    /// or return the sequence unchanged if it is out of bounds.
+    pub fn update(self, i: usize, t: T) -> Self {
+        self.adjust(i, r#const(t))
+    }
+
+    /// Perform an action `n` times, collecting the results into a sequence.
+    ///
+    /// ```
+    /// # use glasgae::{base::data::collection::list::seq::Seq, transformers::state::State};
+    /// let counter = State::new(|s: usize| (s, s + 1));
+    /// assert_eq!(
+    ///     Seq::replicate_m(3, counter).run(1),
+    ///     (Seq::from_iter([1, 2, 3]), 4)
+    /// );
+    /// ```
+    pub fn replicate_m<MA, MF, MB>(n: usize, ma: MA) -> MB
+    where
+        MA: Functor<Function<Seq<T>, Seq<T>>, Pointed = T, Mapped = MF>
+            + WithPointed<Seq<T>, WithPointed = MB>,
+        MF: Applicative<Seq<T>, Seq<T>, WithA = MB, WithB = MB>,
+        MB: PureA<Pointed = Seq<T>>,
+    {
+        (0..n).fold(PureA::pure_a(Seq::new()), |acc, _| {
+            cons.lift_a2()(ma.clone(), acc)
+        })
+    }
+}
+
+/// Add an element to the front of a sequence.
+pub fn cons<T>(t: T, seq: Seq<T>) -> Seq<T> {
+    seq.cons(t)
+}
+
+/// Add an element to the back of a sequence.
+pub fn snoc<T>(t: T, seq: Seq<T>) -> Seq<T> {
+    seq.snoc(t)
+}
+
+impl<T> Clone for Seq<T> {
+    fn clone(&self) -> Self {
+        Seq(self.0.clone())
+    }
+}
+
+impl<T> Default for Seq<T> {
+    fn default() -> Self {
+        Seq::new()
+    }
+}
+
+impl<T> Debug for Seq<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Show for Seq<T>
+where
+    T: Term + Show,
+{
+    fn show(self) -> String {
+        self.to_vec().show()
+    }
+}
+
+impl<T> PartialEq for Seq<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for Seq<T> where T: Eq {}
+
+impl<T> PartialOrd for Seq<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T> Ord for Seq<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> Hash for Seq<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        self.iter().for_each(|t| t.hash(state));
+    }
+}
+
+impl<T> Index<usize> for Seq<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        match self.lookup(i) {
+            Some(t) => t,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {i}",
+                self.len()
+            ),
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Seq<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().fold(Seq::new(), Seq::snoc)
+    }
+}
+
+impl<T> Extend<T> for Seq<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let seq = std::mem::take(self);
+        *self = iter.into_iter().fold(seq, Seq::snoc);
+    }
+}
+
+impl<T> IntoIterator for Seq<T>
+where
+    T: Clone,
+{
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(IntoLeaves::new(self.0))
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Seq<T> {
+    type Item = &'a T;
+    type IntoIter = Box<dyn Iterator<Item = &'a T> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+/// Consuming iterator over the elements of a [`Seq`].
+///
+/// Elements still shared with another sequence are cloned.
+pub struct IntoIter<T>(IntoLeaves<T>);
+
+impl<T> Iterator for IntoIter<T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.next()
+    }
+}
+
+derive_pointed!(Seq<(X)>);
+derive_with_pointed!(Seq<(X)>);
+derive_functor_iterable!(Seq<(X)>);
+derive_applicative_iterable!(Seq<(X)>);
+derive_monad_iterable!(Seq<(X)>);
+derive_monoid_iterable!(Seq<(X)>);
+derive_foldable_iterable!(Seq<(X)>);
+derive_traversable_iterable!(Seq<(X)>, cons);
+
+impl<T> Semigroup for Seq<T>
+where
+    T: Term,
+{
+    fn assoc_s(self, a: Self) -> Self {
+        self.concat(a)
+    }
+}
+
+impl<T> Append for Seq<T>
+where
+    T: Term,
+{
+    fn append(self, t: Self) -> Self {
+        self.concat(t)
+    }
+}
+
+impl<T> Filter<T> for Seq<T>
+where
+    T: Term,
+{
+    fn filter(self, p: impl FunctionT<T, bool>) -> Self {
+        let p = p.to_function();
+        self.into_iter().filter(|t| p.clone()(t.clone())).collect()
+    }
+}
+
+impl<MB, A, B> FoldM<MB, A, B> for Seq<B>
+where
+    MB: Monad<A, Pointed = A, Chained = MB>,
+    A: Term,
+    B: Term,
+{
+    fn foldl_m(self, f: impl BifunT<A, B, MB>, a: A) -> MB {
+        let f = f.to_bifun();
+        match self.uncons() {
+            None => ReturnM::return_m(a),
+            Some((x, xs)) => f.clone()(a, x).chain_m(move |a| xs.foldl_m(f, a)),
+        }
+    }
+
+    fn foldr_m(self, f: impl BifunT<B, A, MB>, a: A) -> MB {
+        let f = f.to_bifun();
+        match self.unsnoc() {
+            None => ReturnM::return_m(a),
+            Some((xs, x)) => f.clone()(x, a).chain_m(move |a| xs.foldr_m(f, a)),
+        }
+    }
+}
+
+impl<A, MA, MF, MB> FilterM<MA, A, MB> for Seq<A>
+where
+    MA: Functor<Function<Seq<A>, Seq<A>>, Pointed = bool, Mapped = MF>
+        + WithPointed<Seq<A>, WithPointed = MB>,
+    MF: Applicative<Seq<A>, Seq<A>, WithA = MB, WithB = MB>,
+    MB: Pointed<Pointed = Seq<A>> + PureA,
+    A: Term,
+{
+    fn filter_m(self, f: impl FunctionT<A, MA>) -> MB {
+        let f = f.to_function();
+        self.foldr(
+            |next, acc| {
+                {
+                    let next = next.clone();
+                    move |keep, acc: Seq<A>| if keep { acc.cons(next) } else { acc }
+                }
+                .lift_a2()(f(next), acc)
+            },
+            PureA::pure_a(Seq::new()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{base::data::collection::list::vec::push, prelude::*, transformers::state::State};
+
+    use super::Seq;
+
+    /// Check a sequence against the equivalent vector,
+    /// through every means of observing it.
+    fn check(seq: &Seq<usize>, expected: &[usize]) {
+        assert_eq!(seq.len(), expected.len());
+        assert_eq!(seq.iter().copied().collect::<Vec<_>>(), expected);
+        assert_eq!(seq.clone().into_iter().collect::<Vec<_>>(), expected);
+        for (i, t) in expected.iter().enumerate() {
+            assert_eq!(seq.lookup(i), Some(t));
+        }
+        assert_eq!(seq.lookup(expected.len()), None);
+        assert_eq!(seq.front(), expected.first());
+        assert_eq!(seq.back(), expected.last());
+    }
+
+    #[test]
+    fn test_cons_snoc() {
+        let mut seq = Seq::new();
+        let mut expected = std::collections::VecDeque::new();
+        for i in 0..200 {
+            if i % 3 == 0 {
+                seq = seq.cons(i);
+                expected.push_front(i);
+            } else {
+                seq = seq.snoc(i);
+                expected.push_back(i);
+            }
+        }
+        check(&seq, &expected.iter().copied().collect::<Vec<_>>());
+
+        // Drain from alternating ends
+        while let Some(front) = expected.pop_front() {
+            let (t, rest) = seq.uncons().unwrap();
+            assert_eq!(t, front);
+            seq = rest;
+
+            if let Some(back) = expected.pop_back() {
+                let (rest, t) = seq.unsnoc().unwrap();
+                assert_eq!(t, back);
+                seq = rest;
+            }
+        }
+        assert!(seq.is_empty());
+        assert!(seq.uncons().is_none());
+    }
+
+    #[test]
+    fn test_split_concat() {
+        let expected = (0..100).collect::<Vec<usize>>();
+        let seq = Seq::from_iter(expected.clone());
+
+        for i in 0..=100 {
+            let (l, r) = seq.clone().split_at(i);
+            check(&l, &expected[..i]);
+            check(&r, &expected[i..]);
+            check(&l.concat(r), &expected);
+        }
+
+        // Concatenate sequences of every small size pairing
+        for n in 0..20 {
+            for m in 0..20 {
+                let l = Seq::from_iter(0..n);
+                let r = Seq::from_iter(n..n + m);
+                check(&l.concat(r), &(0..n + m).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_persistence() {
+        let xs = Seq::from_iter(0..50usize);
+        let ys = xs.clone().adjust(25, |t| t * 100).update(0, 7).snoc(50);
+
+        check(&xs, &(0..50).collect::<Vec<_>>());
+
+        let mut expected = (0..51).collect::<Vec<_>>();
+        expected[25] = 2500;
+        expected[0] = 7;
+        check(&ys, &expected);
+
+        assert_eq!(xs.clone().adjust(50, |t| t + 1), xs);
+    }
+
+    #[test]
+    fn test_instances() {
+        let seq = Seq::from_iter([1usize, 2, 3]);
+
+        assert_eq!(seq.clone().fmap(|t| t * 2), Seq::from_iter([2, 4, 6]));
+        assert_eq!(
+            seq.clone().chain_m(|t| Seq::replicate(t, t)),
+            Seq::from_iter([1, 2, 2, 3, 3, 3])
+        );
+        assert_eq!(seq.clone().foldr(|t, acc| t + acc * 10, 0), 321);
+        assert_eq!(seq.clone().foldl(|acc, t| acc * 10 + t, 0), 123);
+        assert_eq!(
+            seq.clone()
+                .traverse_t(|t| if t > 0 { Some(t) } else { None }),
+            Some(seq.clone())
+        );
+        assert_eq!(
+            seq.clone().assoc_s(Seq::from_iter([4])),
+            Seq::from_iter([1, 2, 3, 4])
+        );
+        assert_eq!(Seq::<usize>::mempty(), Seq::new());
+        assert_eq!(seq.clone().show(), "[1, 2, 3]");
+        assert_eq!(seq[2], 3);
+    }
+
+    #[test]
+    fn test_monad_helpers() {
+        let seq = Seq::from_iter(1..=4usize);
+
+        let sum = seq
+            .clone()
+            .foldl_m(
+                |acc, t| State::new(move |s: Vec<usize>| (acc + t, push(t, s))),
+                0,
+            )
+            .run(vec![]);
+        assert_eq!(sum, (10, vec![4, 3, 2, 1]));
+
+        let evens: State<usize, Seq<usize>> = seq
+            .filter_m(|t: usize| State::new(move |count: usize| (t.is_multiple_of(2), count + 1)));
+        assert_eq!(evens.run(0), (Seq::from_iter([2, 4]), 4));
+    }
+}
@@ -69,3 +69,45 @@ impl Monoid for String {
         list.concat()
     }
 }
+
+/// Split a string into words, separated by whitespace.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::string::words;
+/// assert_eq!(words("  one two\tthree\n".to_string()), vec!["one", "two", "three"]);
+/// ```
+pub fn words(s: String) -> Vec<String> {
+    s.split_whitespace().map(ToString::to_string).collect()
+}
+
+/// Join words with single spaces.
+pub fn unwords(ws: Vec<String>) -> String {
+    ws.join(" ")
+}
+
+/// Split a string into lines, at each newline.
+///
+/// A trailing newline does not start a new line.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::string::lines;
+/// assert_eq!(lines("one\n\ntwo\n".to_string()), vec!["one", "", "two"]);
+/// ```
+pub fn lines(s: String) -> Vec<String> {
+    if s.is_empty() {
+        return vec![];
+    }
+    let s = s.strip_suffix('\n').unwrap_or(&s);
+    s.split('\n').map(ToString::to_string).collect()
+}
+
+/// Join lines, following each with a newline.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::string::{lines, unlines};
+/// let s = "one\n\ntwo\n".to_string();
+/// assert_eq!(unlines(lines(s.clone())), s);
+/// ```
+pub fn unlines(ls: Vec<String>) -> String {
+    ls.into_iter().map(|l| l + "\n").collect()
+}
@@ -0,0 +1,509 @@
+//! Functions from [Data.List](https://hackage.haskell.org/package/base/docs/Data-List.html).
+//!
+//! Each function takes its list by value as the last argument,
+//! so it can be partially applied with [`Curry`](crate::base::data::function::curry::Curry)
+//! or composed as a [`FunctionT`].
+
+use crate::prelude::*;
+
+/// Split a list into its longest prefix of elements which satisfy `p`,
+/// and the remainder.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::span;
+/// assert_eq!(span(|x| x < 3, vec![1, 2, 3, 4, 1]), (vec![1, 2], vec![3, 4, 1]));
+/// ```
+pub fn span<T>(p: impl FunctionT<T, bool>, mut xs: Vec<T>) -> (Vec<T>, Vec<T>)
+where
+    T: Term,
+{
+    let p = p.to_function();
+    let i = xs
+        .iter()
+        .position(|x| !p.clone()(x.clone()))
+        .unwrap_or(xs.len());
+    let rest = xs.split_off(i);
+    (xs, rest)
+}
+
+/// Split a list into its longest prefix of elements which do not satisfy `p`,
+/// and the remainder.
+pub fn break_<T>(p: impl FunctionT<T, bool>, xs: Vec<T>) -> (Vec<T>, Vec<T>)
+where
+    T: Term,
+{
+    let p = p.to_function();
+    span(move |x| !p(x), xs)
+}
+
+/// Group adjacent elements which are equal according to `eq`.
+///
+/// Each element is compared with the first element of its group.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::group_by;
+/// assert_eq!(
+///     group_by(|a, b| a <= b, vec![1, 2, 2, 1, 3, 0]),
+///     vec![vec![1, 2, 2, 1, 3], vec![0]]
+/// );
+/// ```
+pub fn group_by<T>(eq: impl BifunT<T, T, bool>, xs: Vec<T>) -> Vec<Vec<T>>
+where
+    T: Term,
+{
+    let eq = eq.to_bifun();
+    let mut out: Vec<Vec<T>> = vec![];
+    for x in xs {
+        match out.last_mut() {
+            Some(group) if eq.clone()(group[0].clone(), x.clone()) => group.push(x),
+            _ => out.push(vec![x]),
+        }
+    }
+    out
+}
+
+/// Group adjacent equal elements.
+pub fn group<T>(xs: Vec<T>) -> Vec<Vec<T>>
+where
+    T: Term + PartialEq,
+{
+    group_by(|a: T, b: T| a == b, xs)
+}
+
+/// Insert a list between each of a list of lists, and concatenate the result.
+pub fn intercalate<T>(sep: Vec<T>, xss: Vec<Vec<T>>) -> Vec<T>
+where
+    T: Term,
+{
+    intersperse(sep, xss).concat()
+}
+
+/// Insert an element between each element of a list.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::intersperse;
+/// assert_eq!(intersperse(0, vec![1, 2, 3]), vec![1, 0, 2, 0, 3]);
+/// ```
+pub fn intersperse<T>(sep: T, xs: Vec<T>) -> Vec<T>
+where
+    T: Term,
+{
+    let mut out = Vec::with_capacity((2 * xs.len()).saturating_sub(1));
+    for (i, x) in xs.into_iter().enumerate() {
+        if i > 0 {
+            out.push(sep.clone());
+        }
+        out.push(x);
+    }
+    out
+}
+
+/// Transpose the rows and columns of a list of lists.
+///
+/// Rows which are too short to contribute to a column are skipped.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::transpose;
+/// assert_eq!(
+///     transpose(vec![vec![10, 11], vec![20], vec![], vec![30, 31, 32]]),
+///     vec![vec![10, 20, 30], vec![11, 31], vec![32]]
+/// );
+/// ```
+pub fn transpose<T>(xss: Vec<Vec<T>>) -> Vec<Vec<T>> {
+    let mut rows = xss.into_iter().map(Vec::into_iter).collect::<Vec<_>>();
+    let mut out = vec![];
+    loop {
+        let column = rows
+            .iter_mut()
+            .filter_map(Iterator::next)
+            .collect::<Vec<_>>();
+        if column.is_empty() {
+            return out;
+        }
+        out.push(column);
+    }
+}
+
+/// Every subsequence of a list, in the order of Data.List.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::subsequences;
+/// assert_eq!(
+///     subsequences(vec![1, 2, 3]),
+///     vec![vec![], vec![1], vec![2], vec![1, 2], vec![3], vec![1, 3], vec![2, 3], vec![1, 2, 3]]
+/// );
+/// ```
+pub fn subsequences<T>(xs: Vec<T>) -> Vec<Vec<T>>
+where
+    T: Term,
+{
+    xs.into_iter().fold(vec![vec![]], |mut acc, x| {
+        let extended = acc
+            .iter()
+            .cloned()
+            .map(|mut ys| {
+                ys.push(x.clone());
+                ys
+            })
+            .collect::<Vec<_>>();
+        acc.extend(extended);
+        acc
+    })
+}
+
+/// Every permutation of a list, in the order of Data.List.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::permutations;
+/// assert_eq!(
+///     permutations(vec![1, 2, 3]),
+///     vec![
+///         vec![1, 2, 3],
+///         vec![2, 1, 3],
+///         vec![3, 2, 1],
+///         vec![2, 3, 1],
+///         vec![3, 1, 2],
+///         vec![1, 3, 2]
+///     ]
+/// );
+/// ```
+pub fn permutations<T>(xs: Vec<T>) -> Vec<Vec<T>>
+where
+    T: Term,
+{
+    /// Insert `t` into each position of `xs` ahead of `ts`,
+    /// returning `xs ++ ts` and the new permutations prepended to `r`.
+    fn interleave<T: Term>(
+        t: &T,
+        ts: &[T],
+        prefix: Vec<T>,
+        xs: &[T],
+        r: Vec<Vec<T>>,
+    ) -> (Vec<T>, Vec<Vec<T>>) {
+        match xs {
+            [] => (ts.to_vec(), r),
+            [y, ys @ ..] => {
+                let mut prefix_y = prefix.clone();
+                prefix_y.push(y.clone());
+                let (us, mut zs) = interleave(t, ts, prefix_y, ys, r);
+
+                let mut perm = prefix;
+                perm.extend([t.clone(), y.clone()]);
+                perm.extend(us.iter().cloned());
+                zs.insert(0, perm);
+
+                let mut y_us = vec![y.clone()];
+                y_us.extend(us);
+                (y_us, zs)
+            }
+        }
+    }
+
+    fn perms<T: Term>(ts: &[T], is: Vec<T>) -> Vec<Vec<T>> {
+        let [t, ts @ ..] = ts else {
+            return vec![];
+        };
+
+        let mut t_is = vec![t.clone()];
+        t_is.extend(is.iter().cloned());
+
+        permutations(is)
+            .into_iter()
+            .rev()
+            .fold(perms(ts, t_is), |r, xs| interleave(t, ts, vec![], &xs, r).1)
+    }
+
+    let mut out = vec![xs.clone()];
+    out.extend(perms(&xs, vec![]));
+    out
+}
+
+/// Running left fold, from the initial value onward.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::scanl;
+/// assert_eq!(scanl(|acc, x| acc + x, 0, vec![1, 2, 3]), vec![0, 1, 3, 6]);
+/// ```
+pub fn scanl<T, B>(f: impl BifunT<B, T, B>, z: B, xs: Vec<T>) -> Vec<B>
+where
+    T: Term,
+    B: Term,
+{
+    let f = f.to_bifun();
+    let mut out = vec![z.clone()];
+    xs.into_iter().fold(z, |acc, x| {
+        let acc = f.clone()(acc, x);
+        out.push(acc.clone());
+        acc
+    });
+    out
+}
+
+/// Running right fold, ending with the initial value.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::scanr;
+/// assert_eq!(scanr(|x, acc| x + acc, 0, vec![1, 2, 3]), vec![6, 5, 3, 0]);
+/// ```
+pub fn scanr<T, B>(f: impl BifunT<T, B, B>, z: B, xs: Vec<T>) -> Vec<B>
+where
+    T: Term,
+    B: Term,
+{
+    let f = f.to_bifun();
+    let mut out = vec![z.clone()];
+    xs.into_iter().rfold(z, |acc, x| {
+        let acc = f.clone()(x, acc);
+        out.push(acc.clone());
+        acc
+    });
+    out.reverse();
+    out
+}
+
+/// The first `n` repeated applications of `f` to `x`, starting with `x` itself.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::iterate_n;
+/// assert_eq!(iterate_n(4, |x| x * 2, 1), vec![1, 2, 4, 8]);
+/// ```
+pub fn iterate_n<T>(n: usize, f: impl FunctionT<T, T>, x: T) -> Vec<T>
+where
+    T: Term,
+{
+    let f = f.to_function();
+    std::iter::successors(Some(x), |x| Some(f.clone()(x.clone())))
+        .take(n)
+        .collect()
+}
+
+/// Build a list from a seed value.
+///
+/// `f` returns `None` to finish, or the next element and a new seed.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::unfoldr;
+/// assert_eq!(
+///     unfoldr(|n| if n > 0 { Some((n, n - 1)) } else { None }, 3),
+///     vec![3, 2, 1]
+/// );
+/// ```
+pub fn unfoldr<T, B>(f: impl FunctionT<B, Option<(T, B)>>, b: B) -> Vec<T>
+where
+    T: Term,
+    B: Term,
+{
+    let f = f.to_function();
+    let mut out = vec![];
+    let mut b = b;
+    while let Some((t, next)) = f.clone()(b) {
+        out.push(t);
+        b = next;
+    }
+    out
+}
+
+/// Zip three lists into a list of triples, stopping at the shortest.
+pub fn zip3<A, B, C>(a: Vec<A>, b: Vec<B>, c: Vec<C>) -> Vec<(A, B, C)> {
+    a.into_iter()
+        .zip(b)
+        .zip(c)
+        .map(|((a, b), c)| (a, b, c))
+        .collect()
+}
+
+/// Zip three lists with a function, stopping at the shortest.
+pub fn zip_with3<A, B, C, D>(
+    mut f: impl FnMut(A, B, C) -> D,
+    a: Vec<A>,
+    b: Vec<B>,
+    c: Vec<C>,
+) -> Vec<D> {
+    zip3(a, b, c)
+        .into_iter()
+        .map(|(a, b, c)| f(a, b, c))
+        .collect()
+}
+
+/// Split a list of triples into three lists.
+pub fn unzip3<A, B, C>(xs: Vec<(A, B, C)>) -> (Vec<A>, Vec<B>, Vec<C>) {
+    let mut out = (vec![], vec![], vec![]);
+    for (a, b, c) in xs {
+        out.0.push(a);
+        out.1.push(b);
+        out.2.push(c);
+    }
+    out
+}
+
+/// The value of the first pair in an association list whose key is `k`.
+pub fn lookup<K, V>(k: K, xs: Vec<(K, V)>) -> Option<V>
+where
+    K: PartialEq,
+{
+    xs.into_iter().find(|(k_, _)| *k_ == k).map(|(_, v)| v)
+}
+
+/// Remove duplicates according to `eq`, keeping the first occurrence of each element.
+pub fn nub_by<T>(eq: impl BifunT<T, T, bool>, xs: Vec<T>) -> Vec<T>
+where
+    T: Term,
+{
+    let eq = eq.to_bifun();
+    xs.into_iter().fold(vec![], |mut acc, x| {
+        if !acc.iter().any(|y| eq.clone()(y.clone(), x.clone())) {
+            acc.push(x);
+        }
+        acc
+    })
+}
+
+/// Remove duplicates, keeping the first occurrence of each element.
+pub fn nub<T>(xs: Vec<T>) -> Vec<T>
+where
+    T: Term + PartialEq,
+{
+    nub_by(|a: T, b: T| a == b, xs)
+}
+
+/// Split a list into the elements which satisfy `p`, and those which do not.
+pub fn partition<T>(p: impl FunctionT<T, bool>, xs: Vec<T>) -> (Vec<T>, Vec<T>)
+where
+    T: Term,
+{
+    let p = p.to_function();
+    xs.into_iter().partition(|x| p.clone()(x.clone()))
+}
+
+/// Every prefix of a list, shortest first.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::inits;
+/// assert_eq!(inits(vec![1, 2]), vec![vec![], vec![1], vec![1, 2]]);
+/// ```
+pub fn inits<T>(xs: Vec<T>) -> Vec<Vec<T>>
+where
+    T: Term,
+{
+    (0..=xs.len()).map(|i| xs[..i].to_vec()).collect()
+}
+
+/// Every suffix of a list, longest first.
+///
+/// ```
+/// # use glasgae::base::data::collection::list::vec::tails;
+/// assert_eq!(tails(vec![1, 2]), vec![vec![1, 2], vec![2], vec![]]);
+/// ```
+pub fn tails<T>(xs: Vec<T>) -> Vec<Vec<T>>
+where
+    T: Term,
+{
+    (0..=xs.len()).map(|i| xs[i..].to_vec()).collect()
+}
+
+/// Returns `true` if the first list is a prefix of the second.
+pub fn is_prefix_of<T>(prefix: Vec<T>, xs: Vec<T>) -> bool
+where
+    T: PartialEq,
+{
+    xs.starts_with(&prefix)
+}
+
+/// Returns `true` if the first list is a suffix of the second.
+pub fn is_suffix_of<T>(suffix: Vec<T>, xs: Vec<T>) -> bool
+where
+    T: PartialEq,
+{
+    xs.ends_with(&suffix)
+}
+
+/// Returns `true` if the first list is contained, uninterrupted, in the second.
+pub fn is_infix_of<T>(needle: Vec<T>, xs: Vec<T>) -> bool
+where
+    T: PartialEq,
+{
+    needle.is_empty() || xs.windows(needle.len()).any(|w| w == needle)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            break_(|x| x > 3, vec![1, 2, 3, 4, 1]),
+            (vec![1, 2, 3], vec![4, 1])
+        );
+        assert_eq!(span(|_| true, vec![1, 2]), (vec![1, 2], vec![]));
+        assert_eq!(
+            partition(|x| x % 2 == 0, vec![1, 2, 3, 4]),
+            (vec![2, 4], vec![1, 3])
+        );
+        assert_eq!(
+            group(vec![1, 1, 2, 3, 3, 3]),
+            vec![vec![1, 1], vec![2], vec![3, 3, 3]]
+        );
+    }
+
+    #[test]
+    fn test_combine() {
+        assert_eq!(
+            intercalate(vec![0, 0], vec![vec![1], vec![2, 3], vec![]]),
+            vec![1, 0, 0, 2, 3, 0, 0]
+        );
+        assert_eq!(intersperse(0, Vec::<usize>::new()), vec![]);
+        assert_eq!(
+            zip_with3(
+                |a, b, c| a + b * c,
+                vec![1, 2, 3],
+                vec![4, 5],
+                vec![6, 7, 8]
+            ),
+            vec![25, 37]
+        );
+        assert_eq!(
+            unzip3(zip3(vec![1, 2], vec!['a', 'b'], vec!["x", "y"])),
+            (vec![1, 2], vec!['a', 'b'], vec!["x", "y"])
+        );
+        assert_eq!(lookup(2, vec![(1, "one"), (2, "two")]), Some("two"));
+        assert_eq!(nub(vec![3, 1, 3, 2, 1]), vec![3, 1, 2]);
+        assert_eq!(
+            nub_by(|a, b| a % 3 == b % 3, vec![1, 2, 4, 6]),
+            vec![1, 2, 6]
+        );
+    }
+
+    #[test]
+    fn test_permutations() {
+        let perms = permutations(vec![1, 2, 3, 4]);
+        assert_eq!(perms.len(), 24);
+        assert_eq!(nub(perms.clone()).len(), 24);
+        assert_eq!(perms[0], vec![1, 2, 3, 4]);
+        assert_eq!(permutations(Vec::<usize>::new()), vec![vec![]]);
+        assert_eq!(subsequences(vec![1, 2, 3, 4]).len(), 16);
+    }
+
+    #[test]
+    fn test_predicates() {
+        assert!(is_prefix_of(vec![1, 2], vec![1, 2, 3]));
+        assert!(!is_prefix_of(vec![2], vec![1, 2, 3]));
+        assert!(is_suffix_of(vec![2, 3], vec![1, 2, 3]));
+        assert!(is_infix_of(vec![2, 3], vec![1, 2, 3, 4]));
+        assert!(!is_infix_of(vec![3, 2], vec![1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_curry() {
+        let comma_separated = intersperse.curry(",".to_string());
+        assert_eq!(
+            comma_separated(vec!["a".to_string(), "b".to_string()]).concat(),
+            "a,b"
+        );
+
+        let evens = vec![vec![1, 2], vec![3, 4]]
+            .fmap(|xs| partition(|x| x % 2 == 0, xs).0)
+            .concat();
+        assert_eq!(evens, vec![2, 4]);
+    }
+}
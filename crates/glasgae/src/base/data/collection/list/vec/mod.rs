@@ -1,3 +1,7 @@
+mod data_list;
+
+pub use data_list::*;
+
 use std::cmp::Ordering;
 
 use crate::{
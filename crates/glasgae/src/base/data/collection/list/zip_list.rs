@@ -0,0 +1,225 @@
+//! Lists with a zipping [`Applicative`] instance.
+//!
+//! Based on [Control.Applicative.ZipList](https://hackage.haskell.org/package/base/docs/Control-Applicative.html#t:ZipList).
+//!
+//! Where the applicative instance for [`Vec`] combines every function with every argument,
+//! [`ZipList`] applies them pointwise, truncating to the shorter of the two lists.
+//! As a consequence, sequencing a list of `ZipList`s transposes it:
+//!
+//! ```
+//! # use glasgae::{base::data::collection::list::zip_list::ZipList, prelude::*};
+//! let rows = vec![
+//!     ZipList::from(vec![1, 2, 3]),
+//!     ZipList::from(vec![4, 5, 6]),
+//! ];
+//!
+//! assert_eq!(
+//!     rows.sequence_a().get_zip_list(),
+//!     vec![vec![1, 4], vec![2, 5], vec![3, 6]]
+//! );
+//!
+//! let sums = (|a: usize, b: usize| a + b).lift_a2()(
+//!     ZipList::from(vec![1, 2, 3]),
+//!     ZipList::from(vec![10, 20]),
+//! );
+//! assert_eq!(sums, ZipList::from(vec![11, 22]));
+//! ```
+
+use crate::{derive_pointed, derive_traversable_iterable, derive_with_pointed, prelude::*};
+
+use ZipList::*;
+
+/// A list whose applicative instance zips.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ZipList<T> {
+    /// A finite list.
+    List(Vec<T>),
+    /// A single value repeated forever, as produced by [`PureA::pure_a`].
+    ///
+    /// Zipping with a finite list replicates the value to fit.
+    Repeat(T),
+}
+
+impl<T> ZipList<T> {
+    /// The empty list.
+    pub fn new() -> Self {
+        List(vec![])
+    }
+
+    /// The underlying list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the list is infinite.
+    pub fn get_zip_list(self) -> Vec<T> {
+        match self {
+            List(ts) => ts,
+            Repeat(_) => panic!("get_zip_list: infinite ZipList"),
+        }
+    }
+
+    /// The first `n` elements of the list.
+    pub fn take(self, n: usize) -> Vec<T>
+    where
+        T: Clone,
+    {
+        match self {
+            List(ts) => ts.into_iter().take(n).collect(),
+            Repeat(t) => vec![t; n],
+        }
+    }
+}
+
+impl<T> Default for ZipList<T> {
+    fn default() -> Self {
+        ZipList::new()
+    }
+}
+
+impl<T> From<Vec<T>> for ZipList<T> {
+    fn from(ts: Vec<T>) -> Self {
+        List(ts)
+    }
+}
+
+impl<T> FromIterator<T> for ZipList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        List(iter.into_iter().collect())
+    }
+}
+
+/// Add an element to the front of a finite list.
+///
+/// # Panics
+///
+/// Panics if the list is infinite.
+pub fn cons<T>(t: T, ts: ZipList<T>) -> ZipList<T> {
+    let mut ts = ts.get_zip_list();
+    ts.insert(0, t);
+    List(ts)
+}
+
+derive_pointed!(ZipList<(T)>);
+derive_with_pointed!(ZipList<(T)>);
+
+impl<T, U> Functor<U> for ZipList<T>
+where
+    T: Term,
+    U: Term,
+{
+    type Mapped = ZipList<U>;
+
+    fn fmap(self, f: impl FunctionT<T, U>) -> ZipList<U> {
+        let f = f.to_function();
+        match self {
+            List(ts) => List(ts.into_iter().map(|t| f.clone()(t)).collect()),
+            Repeat(t) => Repeat(f(t)),
+        }
+    }
+}
+
+impl<T> PureA for ZipList<T>
+where
+    T: Term,
+{
+    fn pure_a(t: T) -> Self {
+        Repeat(t)
+    }
+}
+
+impl<F, A, B> AppA<A, B> for ZipList<F>
+where
+    F: Term + FunctionT<A, B>,
+    A: Term,
+    B: Term,
+{
+    type WithA = ZipList<A>;
+    type WithB = ZipList<B>;
+
+    fn app_a(self, a: ZipList<A>) -> ZipList<B> {
+        match (self, a) {
+            (Repeat(f), a) => a.fmap(f),
+            (List(fs), Repeat(a)) => List(fs.into_iter().map(|f| f(a.clone())).collect()),
+            (List(fs), List(as_)) => List(fs.into_iter().zip(as_).map(|(f, a)| f(a)).collect()),
+        }
+    }
+}
+
+/// # Panics
+///
+/// Folding an infinite list panics.
+impl<T, U> Foldable<U> for ZipList<T>
+where
+    T: Term,
+    U: Term,
+{
+    fn foldr(self, f: impl BifunT<T, U, U>, z: U) -> U {
+        self.get_zip_list().foldr(f, z)
+    }
+
+    fn foldl(self, f: impl BifunT<U, T, U>, z: U) -> U {
+        self.get_zip_list().foldl(f, z)
+    }
+}
+
+impl<T> Foldable1<T> for ZipList<T>
+where
+    T: Term,
+{
+    fn foldr1(self, f: impl BifunT<T, T, T>) -> T {
+        foldr1_default(self, f)
+    }
+
+    fn foldl1(self, f: impl BifunT<T, T, T>) -> T {
+        foldl1_default(self, f)
+    }
+}
+
+derive_traversable_iterable!(ZipList<(T)>, cons);
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    use super::ZipList;
+
+    #[test]
+    fn test_applicative() {
+        let fs = ZipList::from(vec![
+            (|x: usize| x + 1).boxed() as Function<usize, usize>,
+            (|x: usize| x * 2).boxed(),
+            (|x: usize| x * x).boxed(),
+        ]);
+
+        assert_eq!(
+            fs.clone().app_a(ZipList::from(vec![10, 20, 30])),
+            ZipList::from(vec![11, 40, 900])
+        );
+        assert_eq!(fs.app_a(PureA::pure_a(5)), ZipList::from(vec![6, 10, 25]));
+        assert_eq!(
+            ZipList::pure_a(identity::<usize>.boxed() as Function<usize, usize>)
+                .app_a(ZipList::pure_a(1))
+                .take(3),
+            vec![1, 1, 1]
+        );
+    }
+
+    #[test]
+    fn test_traversable() {
+        assert_eq!(
+            Vec::<ZipList<usize>>::new().sequence_a().take(2),
+            vec![vec![], vec![]]
+        );
+        assert_eq!(
+            vec![ZipList::from(vec![1, 2]), ZipList::from(vec![3])]
+                .sequence_a()
+                .get_zip_list(),
+            vec![vec![1, 3]]
+        );
+        assert_eq!(
+            ZipList::from(vec![Some(1), Some(2)]).sequence_a(),
+            Some(ZipList::from(vec![1, 2]))
+        );
+        assert_eq!(ZipList::from(vec![1, 2, 3]).foldr(|t, acc| t + acc, 0), 6);
+    }
+}
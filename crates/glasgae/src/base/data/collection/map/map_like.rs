@@ -0,0 +1,400 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
+
+use vector_map::VecMap;
+
+use crate::prelude::*;
+
+/// By-value combinators for finite maps,
+/// in the style of [Data.Map](https://hackage.haskell.org/package/containers/docs/Data-Map-Strict.html).
+///
+/// Implementors provide in-place lookup, insertion and removal,
+/// from which the rest of the interface is derived.
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use glasgae::base::data::collection::map::MapLike;
+/// let m = BTreeMap::from_list_with(|new, old| new + old, [("a", 1), ("b", 2), ("a", 3)]);
+/// assert_eq!(m, BTreeMap::from([("a", 4), ("b", 2)]));
+///
+/// let m = m
+///     .adjust(|v| v * 10, &"a")
+///     .alter(|v: Option<i32>| Some(v.unwrap_or_default() + 1), "c")
+///     .update(|v| if v > 5 { None } else { Some(v) }, &"a");
+/// assert_eq!(m, BTreeMap::from([("b", 2), ("c", 1)]));
+/// ```
+pub trait MapLike<K, V>: Term + FromIterator<(K, V)> + IntoIterator<Item = (K, V)>
+where
+    K: Term,
+    V: Term,
+{
+    /// The value stored under `k`, if any.
+    fn lookup(&self, k: &K) -> Option<&V>;
+
+    /// Insert a value under `k` in place, replacing any existing value.
+    fn put(&mut self, k: K, v: V);
+
+    /// Remove the entry for `k` in place, returning its value.
+    fn take(&mut self, k: &K) -> Option<V>;
+
+    /// Build a map from a list of entries,
+    /// combining the values of duplicate keys as `f(new, old)`.
+    fn from_list_with(f: impl BifunT<V, V, V>, kvs: impl IntoIterator<Item = (K, V)>) -> Self {
+        let f = f.to_bifun();
+        kvs.into_iter()
+            .fold(empty(), |m, (k, v)| m.insert_with(f.clone(), k, v))
+    }
+
+    /// Insert a value under `k`,
+    /// combining it with any existing value as `f(new, old)`.
+    fn insert_with(mut self, f: impl BifunT<V, V, V>, k: K, v: V) -> Self {
+        let v = match self.take(&k) {
+            Some(old) => f.to_bifun()(v, old),
+            None => v,
+        };
+        self.put(k, v);
+        self
+    }
+
+    /// Apply `f` to the value stored under `k`,
+    /// or return the map unchanged if there is none.
+    fn adjust(self, f: impl FunctionT<V, V>, k: &K) -> Self {
+        let f = f.to_function();
+        self.update(|v| Some(f(v)), k)
+    }
+
+    /// Apply `f` to the value stored under `k`,
+    /// removing the entry if it returns `None`.
+    fn update(mut self, f: impl FunctionT<V, Option<V>>, k: &K) -> Self {
+        if let Some(v) = self.take(k) {
+            if let Some(v) = f.to_function()(v) {
+                self.put(k.clone(), v);
+            }
+        }
+        self
+    }
+
+    /// Insert, update or remove the entry for `k`,
+    /// depending on the result of `f` applied to its current value.
+    fn alter(mut self, f: impl FunctionT<Option<V>, Option<V>>, k: K) -> Self {
+        let v = self.take(&k);
+        if let Some(v) = f.to_function()(v) {
+            self.put(k, v);
+        }
+        self
+    }
+
+    /// Left-biased union of two maps.
+    ///
+    /// Entries of `self` take precedence over those of `other`.
+    fn union(self, other: Self) -> Self {
+        self.union_with(|l, _| l, other)
+    }
+
+    /// Union of two maps,
+    /// combining the values of keys present in both as `f(left, right)`.
+    fn union_with(self, f: impl BifunT<V, V, V>, other: Self) -> Self {
+        let f = f.to_bifun();
+        other.into_iter().fold(self, |mut m, (k, r)| {
+            let v = match m.take(&k) {
+                Some(l) => f.clone()(l, r),
+                None => r,
+            };
+            m.put(k, v);
+            m
+        })
+    }
+
+    /// Union of a list of maps, combining values from left to right with `f`.
+    fn unions_with(f: impl BifunT<V, V, V>, ms: impl IntoIterator<Item = Self>) -> Self {
+        let f = f.to_bifun();
+        ms.into_iter()
+            .fold(empty(), |acc, m| acc.union_with(f.clone(), m))
+    }
+
+    /// Intersection of two maps,
+    /// combining the values of keys present in both as `f(left, right)`.
+    fn intersection_with<W, X>(
+        self,
+        f: impl BifunT<V, W, X>,
+        mut other: WithPointedT<Self, W>,
+    ) -> WithPointedT<Self, X>
+    where
+        Self: WithPointed<W> + WithPointed<X>,
+        WithPointedT<Self, W>: MapLike<K, W>,
+        WithPointedT<Self, X>: MapLike<K, X>,
+        W: Term,
+        X: Term,
+    {
+        let f = f.to_bifun();
+        self.into_iter()
+            .filter_map(|(k, l)| {
+                let r = other.take(&k)?;
+                Some((k, f.clone()(l, r)))
+            })
+            .collect()
+    }
+
+    /// The entries of `self` whose keys are not present in `other`.
+    fn difference<W>(self, other: WithPointedT<Self, W>) -> Self
+    where
+        Self: WithPointed<W>,
+        WithPointedT<Self, W>: MapLike<K, W>,
+        W: Term,
+    {
+        self.into_iter()
+            .filter(|(k, _)| other.lookup(k).is_none())
+            .collect()
+    }
+
+    /// Split a map into the entries whose values satisfy `p`, and those which do not.
+    fn partition(self, p: impl FunctionT<V, bool>) -> (Self, Self) {
+        let p = p.to_function();
+        self.into_iter()
+            .fold((empty(), empty()), |(mut yes, mut no), (k, v)| {
+                if p.clone()(v.clone()) {
+                    yes.put(k, v);
+                } else {
+                    no.put(k, v);
+                }
+                (yes, no)
+            })
+    }
+
+    /// Apply `f` to every value, keeping only the entries for which it returns `Some`.
+    fn map_maybe<W>(self, f: impl FunctionT<V, Option<W>>) -> WithPointedT<Self, W>
+    where
+        Self: WithPointed<W>,
+        WithPointedT<Self, W>: MapLike<K, W>,
+        W: Term,
+    {
+        let f = f.to_function();
+        self.into_iter()
+            .filter_map(|(k, v)| Some((k, f.clone()(v)?)))
+            .collect()
+    }
+
+    /// Apply `f` to every key and value.
+    fn map_with_key<W>(self, f: impl BifunT<K, V, W>) -> WithPointedT<Self, W>
+    where
+        Self: WithPointed<W>,
+        WithPointedT<Self, W>: MapLike<K, W>,
+        W: Term,
+    {
+        let f = f.to_bifun();
+        self.into_iter()
+            .map(|(k, v)| (k.clone(), f.clone()(k, v)))
+            .collect()
+    }
+
+    /// Right-associative fold over the keys and values of a map.
+    fn foldr_with_key<B>(self, f: impl BifunT<(K, V), B, B>, z: B) -> B
+    where
+        B: Term,
+    {
+        let f = f.to_bifun();
+        self.into_iter()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rfold(z, |acc, kv| f.clone()(kv, acc))
+    }
+
+    /// Apply the applicative action `f` to every key and value,
+    /// and collect the results into a map.
+    fn traverse_with_key<W, A1, MF, A2>(self, f: impl BifunT<K, V, A1>) -> A2
+    where
+        Self: WithPointed<W>,
+        WithPointedT<Self, W>: MapLike<K, W>,
+        W: Term,
+        A1: Functor<
+                Function<WithPointedT<Self, W>, WithPointedT<Self, W>>,
+                Pointed = W,
+                Mapped = MF,
+            > + WithPointed<WithPointedT<Self, W>, WithPointed = A2>,
+        MF: Applicative<WithPointedT<Self, W>, WithPointedT<Self, W>, WithA = A2, WithB = A2>,
+        A2: PureA<Pointed = WithPointedT<Self, W>>,
+    {
+        let f = f.to_bifun();
+        self.foldr_with_key(
+            move |(k, v): (K, V), acc: A2| {
+                {
+                    let k = k.clone();
+                    move |w, mut m: WithPointedT<Self, W>| {
+                        m.put(k, w);
+                        m
+                    }
+                }
+                .lift_a2()(f.clone()(k, v), acc)
+            },
+            PureA::pure_a(empty()),
+        )
+    }
+}
+
+/// The empty map, for implementors without a [`Default`] instance.
+fn empty<M, K, V>() -> M
+where
+    M: FromIterator<(K, V)>,
+{
+    std::iter::empty().collect()
+}
+
+impl<K, V> MapLike<K, V> for HashMap<K, V>
+where
+    K: Term + Eq + Hash,
+    V: Term,
+{
+    fn lookup(&self, k: &K) -> Option<&V> {
+        self.get(k)
+    }
+
+    fn put(&mut self, k: K, v: V) {
+        self.insert(k, v);
+    }
+
+    fn take(&mut self, k: &K) -> Option<V> {
+        self.remove(k)
+    }
+}
+
+impl<K, V> MapLike<K, V> for BTreeMap<K, V>
+where
+    K: Term + Ord,
+    V: Term,
+{
+    fn lookup(&self, k: &K) -> Option<&V> {
+        self.get(k)
+    }
+
+    fn put(&mut self, k: K, v: V) {
+        self.insert(k, v);
+    }
+
+    fn take(&mut self, k: &K) -> Option<V> {
+        self.remove(k)
+    }
+}
+
+impl<K, V> MapLike<K, V> for VecMap<K, V>
+where
+    K: Term + PartialEq,
+    V: Term,
+{
+    fn lookup(&self, k: &K) -> Option<&V> {
+        self.get(k)
+    }
+
+    fn put(&mut self, k: K, v: V) {
+        self.insert(k, v);
+    }
+
+    fn take(&mut self, k: &K) -> Option<V> {
+        self.remove(k)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, HashMap};
+
+    use vector_map::VecMap;
+
+    use crate::{prelude::*, transformers::state::State};
+
+    use super::MapLike;
+
+    fn map() -> BTreeMap<&'static str, usize> {
+        BTreeMap::from([("a", 1), ("b", 2), ("c", 3)])
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let other = BTreeMap::from([("b", "two"), ("d", "four")]);
+
+        assert_eq!(
+            map().intersection_with(|l, r: &str| format!("{l}{r}"), other.clone()),
+            BTreeMap::from([("b", "2two".to_string())])
+        );
+        assert_eq!(
+            map().difference(other),
+            BTreeMap::from([("a", 1), ("c", 3)])
+        );
+        assert_eq!(
+            BTreeMap::unions_with(|l, r| l + r, [map(), map(), BTreeMap::from([("d", 4)])]),
+            BTreeMap::from([("a", 2), ("b", 4), ("c", 6), ("d", 4)])
+        );
+
+        // Semigroup is a left-biased union
+        assert_eq!(
+            map().assoc_s(BTreeMap::from([("a", 10), ("d", 4)])),
+            BTreeMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)])
+        );
+        assert_eq!(
+            HashMap::from([("a", 1)]).assoc_s(HashMap::from([("a", 2)])),
+            HashMap::from([("a", 1)])
+        );
+    }
+
+    #[test]
+    fn test_mapping() {
+        let (odd, even) = map().partition(|v| v % 2 == 1);
+        assert_eq!(odd, BTreeMap::from([("a", 1), ("c", 3)]));
+        assert_eq!(even, BTreeMap::from([("b", 2)]));
+
+        assert_eq!(
+            map().map_maybe(|v| (v > 1).then(|| v.to_string())),
+            BTreeMap::from([("b", "2".to_string()), ("c", "3".to_string())])
+        );
+        assert_eq!(
+            map().map_with_key(|k: &str, v| k.repeat(v)),
+            BTreeMap::from([
+                ("a", "a".to_string()),
+                ("b", "bb".to_string()),
+                ("c", "ccc".to_string())
+            ])
+        );
+        assert_eq!(
+            map().foldr_with_key(|(k, v), acc: String| format!("{k}{v}{acc}"), String::new()),
+            "a1b2c3"
+        );
+    }
+
+    #[test]
+    fn test_traverse_with_key() {
+        assert_eq!(
+            map().traverse_with_key(|_, v| if v > 0 { Some(v * 2) } else { None }),
+            Some(BTreeMap::from([("a", 2), ("b", 4), ("c", 6)]))
+        );
+        assert_eq!(
+            map().traverse_with_key(|k, v| if k != "b" { Some(v) } else { None }),
+            None
+        );
+
+        // Number each entry in key order
+        let numbered: State<usize, BTreeMap<&str, (usize, usize)>> =
+            map().traverse_with_key(|_, v| State::new(move |n: usize| ((n, v), n + 1)));
+        assert_eq!(
+            numbered.run(0),
+            (
+                BTreeMap::from([("a", (0, 1)), ("b", (1, 2)), ("c", (2, 3))]),
+                3
+            )
+        );
+    }
+
+    #[test]
+    fn test_vec_map() {
+        let m = VecMap::from_list_with(|new, old| new + old, [(1, 1), (2, 2), (1, 3)]);
+        let m = m
+            .insert_with(|new, old| new * old, 2, 5)
+            .alter(|_| Some(0), 3)
+            .update(|_| None, &1);
+
+        assert_eq!(m.lookup(&1), None);
+        assert_eq!(m.lookup(&2), Some(&10));
+        assert_eq!(m.lookup(&3), Some(&0));
+        assert_eq!(m.len(), 2);
+    }
+}
@@ -1,5 +1,10 @@
+mod map_like;
+
+pub use map_like::*;
+
 pub mod btree_map;
 pub mod hash_map;
+pub mod p_map;
 pub mod vec_map;
 
 // Implement standard typeclasses for a type with std::collection iterator semantics
@@ -223,8 +228,9 @@ macro_rules! derive_iterable_map {
             $key: $crate::prelude::Term $(+ $trait)*,
             $value: $crate::prelude::Term,
         {
+            // Left-biased union, as in Data.Map
             fn assoc_s(self, a: Self) -> Self {
-                self.into_iter().chain(a).collect()
+                a.into_iter().chain(self).collect()
             }
         }
 
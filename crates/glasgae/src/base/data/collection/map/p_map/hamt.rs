@@ -0,0 +1,301 @@
+//! Hash array mapped trie.
+//!
+//! Each level of a branch consumes [`BITS`] bits of a key's hash,
+//! and stores only the children that are present, indexed by a bitmap.
+//! Children are shared between versions through [`Arc`],
+//! so an update copies only the branches on the path to the changed key.
+
+use std::{
+    borrow::Borrow,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+/// Number of hash bits consumed by each level of the trie.
+const BITS: u32 = 5;
+
+const MASK: u64 = (1 << BITS) - 1;
+
+pub(super) enum Node<K, V> {
+    Leaf(u64, K, V),
+    /// Leaves whose keys have the same full hash.
+    Collision(u64, Vec<Arc<Node<K, V>>>),
+    Branch(u32, Vec<Arc<Node<K, V>>>),
+}
+
+/// Hash a key with a fixed hasher,
+/// so that equal keys share a position in every trie.
+pub(super) fn hash_of<K>(k: &K) -> u64
+where
+    K: Hash + ?Sized,
+{
+    let mut hasher = DefaultHasher::new();
+    k.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn bit(hash: u64, shift: u32) -> u32 {
+    1 << ((hash >> shift) & MASK)
+}
+
+/// Position of the child for `bit` within a branch's children.
+fn position(bitmap: u32, bit: u32) -> usize {
+    (bitmap & (bit - 1)).count_ones() as usize
+}
+
+impl<K, V> Node<K, V> {
+    pub fn leaf(hash: u64, k: K, v: V) -> Arc<Self> {
+        Arc::new(Node::Leaf(hash, k, v))
+    }
+
+    /// Combine two nodes with different hashes into a branch at depth `shift`.
+    fn merge(shift: u32, a: Arc<Self>, ha: u64, b: Arc<Self>, hb: u64) -> Arc<Self> {
+        let (ba, bb) = (bit(ha, shift), bit(hb, shift));
+        let node = if ba == bb {
+            Node::Branch(ba, vec![Node::merge(shift + BITS, a, ha, b, hb)])
+        } else if ba < bb {
+            Node::Branch(ba | bb, vec![a, b])
+        } else {
+            Node::Branch(ba | bb, vec![b, a])
+        };
+        Arc::new(node)
+    }
+
+    /// Returns `true` if a node can stand in for a branch containing only it.
+    fn is_leafy(&self) -> bool {
+        !matches!(self, Node::Branch(..))
+    }
+}
+
+impl<K, V> Node<K, V>
+where
+    K: Eq,
+{
+    pub fn get<'a>(mut node: &'a Arc<Self>, hash: u64, k: &K) -> Option<&'a V> {
+        let mut shift = 0;
+        loop {
+            match &**node {
+                Node::Leaf(h, k_, v) => return (*h == hash && k_ == k).then_some(v),
+                Node::Collision(h, leaves) => {
+                    if *h != hash {
+                        return None;
+                    }
+                    return leaves.iter().find_map(|leaf| match &**leaf {
+                        Node::Leaf(_, k_, v) if k_ == k => Some(v),
+                        _ => None,
+                    });
+                }
+                Node::Branch(bitmap, children) => {
+                    let bit = bit(hash, shift);
+                    if bitmap & bit == 0 {
+                        return None;
+                    }
+                    node = &children[position(*bitmap, bit)];
+                    shift += BITS;
+                }
+            }
+        }
+    }
+
+    /// Update the entry for `k`.
+    ///
+    /// `f` receives `k` back along with its current value, if any,
+    /// and returns the new entry, or `None` to remove it.
+    ///
+    /// Returns the updated node, or `None` if it is now empty,
+    /// along with the change in the number of entries.
+    pub fn alter<Q>(
+        node: &Arc<Self>,
+        shift: u32,
+        hash: u64,
+        k: Q,
+        f: impl FnOnce(Q, Option<&V>) -> Option<(K, V)>,
+    ) -> (Option<Arc<Self>>, isize)
+    where
+        Q: Borrow<K>,
+    {
+        match &**node {
+            Node::Leaf(h, k_, v) if *h == hash && k_ == k.borrow() => match f(k, Some(v)) {
+                Some((k, v)) => (Some(Node::leaf(hash, k, v)), 0),
+                None => (None, -1),
+            },
+            Node::Leaf(h, ..) => match f(k, None) {
+                None => (Some(node.clone()), 0),
+                Some((k, v)) if *h == hash => (
+                    Some(Arc::new(Node::Collision(
+                        hash,
+                        vec![node.clone(), Node::leaf(hash, k, v)],
+                    ))),
+                    1,
+                ),
+                Some((k, v)) => (
+                    Some(Node::merge(
+                        shift,
+                        node.clone(),
+                        *h,
+                        Node::leaf(hash, k, v),
+                        hash,
+                    )),
+                    1,
+                ),
+            },
+            Node::Collision(h, leaves) if *h == hash => {
+                let i = leaves
+                    .iter()
+                    .position(|leaf| matches!(&**leaf, Node::Leaf(_, k_, _) if k_ == k.borrow()));
+
+                let Some(i) = i else {
+                    return match f(k, None) {
+                        None => (Some(node.clone()), 0),
+                        Some((k, v)) => {
+                            let mut leaves = leaves.clone();
+                            leaves.push(Node::leaf(hash, k, v));
+                            (Some(Arc::new(Node::Collision(hash, leaves))), 1)
+                        }
+                    };
+                };
+
+                let Node::Leaf(_, _, v) = &*leaves[i] else {
+                    unreachable!()
+                };
+
+                let mut leaves = leaves.clone();
+                match f(k, Some(v)) {
+                    Some((k, v)) => {
+                        leaves[i] = Node::leaf(hash, k, v);
+                        (Some(Arc::new(Node::Collision(hash, leaves))), 0)
+                    }
+                    None => {
+                        leaves.remove(i);
+                        let node = if leaves.len() == 1 {
+                            leaves.pop().unwrap()
+                        } else {
+                            Arc::new(Node::Collision(hash, leaves))
+                        };
+                        (Some(node), -1)
+                    }
+                }
+            }
+            Node::Collision(h, _) => match f(k, None) {
+                None => (Some(node.clone()), 0),
+                Some((k, v)) => (
+                    Some(Node::merge(
+                        shift,
+                        node.clone(),
+                        *h,
+                        Node::leaf(hash, k, v),
+                        hash,
+                    )),
+                    1,
+                ),
+            },
+            Node::Branch(bitmap, children) => {
+                let bit = bit(hash, shift);
+                let i = position(*bitmap, bit);
+
+                if bitmap & bit == 0 {
+                    return match f(k, None) {
+                        None => (Some(node.clone()), 0),
+                        Some((k, v)) => {
+                            let mut children = children.clone();
+                            children.insert(i, Node::leaf(hash, k, v));
+                            (Some(Arc::new(Node::Branch(bitmap | bit, children))), 1)
+                        }
+                    };
+                }
+
+                let (child, delta) = Node::alter(&children[i], shift + BITS, hash, k, f);
+                match child {
+                    Some(child) if Arc::ptr_eq(&child, &children[i]) => (Some(node.clone()), 0),
+                    Some(child) => {
+                        if children.len() == 1 && child.is_leafy() {
+                            return (Some(child), delta);
+                        }
+                        let mut children = children.clone();
+                        children[i] = child;
+                        (Some(Arc::new(Node::Branch(*bitmap, children))), delta)
+                    }
+                    None => {
+                        let mut children = children.clone();
+                        children.remove(i);
+                        match &children[..] {
+                            [] => (None, delta),
+                            [child] if child.is_leafy() => (Some(child.clone()), delta),
+                            _ => (Some(Arc::new(Node::Branch(bitmap & !bit, children))), delta),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Borrowing iterator over the entries of a trie, in hash order.
+pub(super) struct Entries<'a, K, V> {
+    stack: Vec<&'a Arc<Node<K, V>>>,
+}
+
+impl<'a, K, V> Entries<'a, K, V> {
+    pub fn new(root: Option<&'a Arc<Node<K, V>>>) -> Self {
+        Entries {
+            stack: root.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Entries<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match &**self.stack.pop()? {
+                Node::Leaf(_, k, v) => return Some((k, v)),
+                Node::Collision(_, children) | Node::Branch(_, children) => {
+                    self.stack.extend(children.iter().rev())
+                }
+            }
+        }
+    }
+}
+
+/// Owning iterator over the entries of a trie, in hash order.
+///
+/// Entries still shared with another trie are cloned.
+pub(super) struct IntoEntries<K, V> {
+    stack: Vec<Arc<Node<K, V>>>,
+}
+
+impl<K, V> IntoEntries<K, V> {
+    pub fn new(root: Option<Arc<Node<K, V>>>) -> Self {
+        IntoEntries {
+            stack: root.into_iter().collect(),
+        }
+    }
+}
+
+impl<K, V> Iterator for IntoEntries<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.stack.pop()?;
+            match Arc::try_unwrap(node) {
+                Ok(Node::Leaf(_, k, v)) => return Some((k, v)),
+                Ok(Node::Collision(_, children) | Node::Branch(_, children)) => {
+                    self.stack.extend(children.into_iter().rev())
+                }
+                Err(node) => match &*node {
+                    Node::Leaf(_, k, v) => return Some((k.clone(), v.clone())),
+                    Node::Collision(_, children) | Node::Branch(_, children) => {
+                        self.stack.extend(children.iter().rev().cloned())
+                    }
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,469 @@
+//! Persistent hash maps, backed by hash array mapped tries.
+//!
+//! Based on [Data.HashMap](https://hackage.haskell.org/package/unordered-containers/docs/Data-HashMap-Strict.html),
+//! and [Ideal Hash Trees](https://infoscience.epfl.ch/record/64398) by Phil Bagwell.
+//!
+//! Unlike [`HashMap`](std::collections::HashMap), a [`PMap`] shares structure between versions,
+//! so cloning is O(1), and operations which return a modified map
+//! copy only the O(log n) branches on the path to the changed key.
+//! This makes it a good fit for state which is threaded through
+//! [`State`](crate::transformers::state::State), or captured by cloned closures.
+//!
+//! ```
+//! # use glasgae::base::data::collection::map::p_map::PMap;
+//! let m = PMap::from_iter([("one", 1), ("two", 2)]);
+//! let n = m.clone().insert("three", 3).remove(&"one");
+//!
+//! assert_eq!(m.get(&"one"), Some(&1));
+//! assert_eq!(m.len(), 2);
+//!
+//! assert_eq!(n.get(&"one"), None);
+//! assert_eq!(n.get(&"three"), Some(&3));
+//! assert_eq!(n.len(), 2);
+//! ```
+
+mod hamt;
+
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, ops::Index, sync::Arc};
+
+use crate::{derive_iterable_map, derive_pointed, derive_with_pointed, prelude::*};
+
+use super::MapLike;
+
+use hamt::{hash_of, Entries, IntoEntries, Node};
+
+/// A persistent map from hashable keys to values.
+pub struct PMap<K, V> {
+    root: Option<Arc<Node<K, V>>>,
+    len: usize,
+}
+
+impl<K, V> PMap<K, V> {
+    /// The empty map.
+    pub fn new() -> Self {
+        PMap { root: None, len: 0 }
+    }
+
+    /// The number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the entries of the map, in an unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        Entries::new(self.root.as_ref())
+    }
+
+    /// Iterate over the keys of the map, in an unspecified order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    /// Iterate over the values of the map, in an unspecified order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> PMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// A map containing a single entry.
+    pub fn singleton(k: K, v: V) -> Self {
+        PMap::new().insert(k, v)
+    }
+
+    /// The value stored under `k`, if any.
+    pub fn get(&self, k: &K) -> Option<&V> {
+        Node::get(self.root.as_ref()?, hash_of(k), k)
+    }
+
+    /// Returns `true` if the map contains an entry for `k`.
+    pub fn contains_key(&self, k: &K) -> bool {
+        self.get(k).is_some()
+    }
+
+    /// Update the entry for `k` through `f`,
+    /// which receives `k` back along with its current value,
+    /// and returns the new entry, if any.
+    fn alter_entry<Q>(self, k: Q, f: impl FnOnce(Q, Option<&V>) -> Option<(K, V)>) -> Self
+    where
+        Q: Borrow<K>,
+    {
+        let hash = hash_of(k.borrow());
+        match &self.root {
+            None => match f(k, None) {
+                None => self,
+                Some((k, v)) => PMap {
+                    root: Some(Node::leaf(hash, k, v)),
+                    len: 1,
+                },
+            },
+            Some(root) => {
+                let (root, delta) = Node::alter(root, 0, hash, k, f);
+                PMap {
+                    root,
+                    len: self.len.wrapping_add_signed(delta),
+                }
+            }
+        }
+    }
+
+    /// Insert a value under `k`, replacing any existing value.
+    pub fn insert(self, k: K, v: V) -> Self {
+        self.alter_entry(k, |k, _| Some((k, v)))
+    }
+
+    /// Remove the entry for `k`, if any.
+    pub fn remove(self, k: &K) -> Self {
+        self.alter_entry(k, |_, _| None)
+    }
+}
+
+impl<K, V> PMap<K, V>
+where
+    K: Term + Eq + Hash,
+    V: Term,
+{
+    /// Insert a value under `k`,
+    /// combining it with any existing value as `f(new, old)`.
+    pub fn insert_with(self, f: impl BifunT<V, V, V>, k: K, v: V) -> Self {
+        let f = f.to_bifun();
+        self.alter_entry(k, |k, old| match old {
+            Some(old) => Some((k, f(v, old.clone()))),
+            None => Some((k, v)),
+        })
+    }
+
+    /// Apply `f` to the value stored under `k`,
+    /// or return the map unchanged if there is none.
+    pub fn adjust(self, f: impl FunctionT<V, V>, k: &K) -> Self {
+        let f = f.to_function();
+        self.alter_entry(k, |k, old| old.map(|v| (k.clone(), f(v.clone()))))
+    }
+
+    /// Insert, update or remove the entry for `k`,
+    /// depending on the result of `f` applied to its current value.
+    pub fn alter(self, f: impl FunctionT<Option<V>, Option<V>>, k: K) -> Self {
+        let f = f.to_function();
+        self.alter_entry(k, |k, old| f(old.cloned()).map(|v| (k, v)))
+    }
+
+    /// Left-biased union of two maps.
+    ///
+    /// Entries of `self` take precedence over those of `other`.
+    pub fn union(self, other: Self) -> Self {
+        self.union_with(|l, _| l, other)
+    }
+
+    /// Union of two maps,
+    /// combining the values of keys present in both as `f(left, right)`.
+    pub fn union_with(self, f: impl BifunT<V, V, V>, other: Self) -> Self {
+        let f = f.to_bifun();
+        // Insert the smaller map into the larger
+        if self.len >= other.len {
+            other.into_iter().fold(self, |acc, (k, r)| {
+                acc.alter_entry(k, |k, l| match l {
+                    Some(l) => Some((k, f.clone()(l.clone(), r))),
+                    None => Some((k, r)),
+                })
+            })
+        } else {
+            self.into_iter().fold(other, |acc, (k, l)| {
+                acc.alter_entry(k, |k, r| match r {
+                    Some(r) => Some((k, f.clone()(l, r.clone()))),
+                    None => Some((k, l)),
+                })
+            })
+        }
+    }
+}
+
+impl<K, V> MapLike<K, V> for PMap<K, V>
+where
+    K: Term + Eq + Hash,
+    V: Term,
+{
+    fn lookup(&self, k: &K) -> Option<&V> {
+        self.get(k)
+    }
+
+    fn put(&mut self, k: K, v: V) {
+        *self = std::mem::take(self).insert(k, v);
+    }
+
+    fn take(&mut self, k: &K) -> Option<V> {
+        let v = self.get(k).cloned();
+        *self = std::mem::take(self).remove(k);
+        v
+    }
+}
+
+/// Insert a value into a map, replacing any existing value.
+pub fn insert<K, V>(k: K, v: V, m: PMap<K, V>) -> PMap<K, V>
+where
+    K: Eq + Hash,
+{
+    m.insert(k, v)
+}
+
+impl<K, V> Clone for PMap<K, V> {
+    fn clone(&self) -> Self {
+        PMap {
+            root: self.root.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K, V> Default for PMap<K, V> {
+    fn default() -> Self {
+        PMap::new()
+    }
+}
+
+impl<K, V> Debug for PMap<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> PartialEq for PMap<K, V>
+where
+    K: Eq + Hash,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K, V> Eq for PMap<K, V>
+where
+    K: Eq + Hash,
+    V: Eq,
+{
+}
+
+impl<K, V> Index<&K> for PMap<K, V>
+where
+    K: Eq + Hash,
+{
+    type Output = V;
+
+    fn index(&self, k: &K) -> &V {
+        self.get(k).expect("key not found in PMap")
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for PMap<K, V>
+where
+    K: Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(PMap::new(), |acc, (k, v)| acc.insert(k, v))
+    }
+}
+
+impl<K, V> Extend<(K, V)> for PMap<K, V>
+where
+    K: Eq + Hash,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let m = std::mem::take(self);
+        *self = iter.into_iter().fold(m, |acc, (k, v)| acc.insert(k, v));
+    }
+}
+
+impl<K, V> IntoIterator for PMap<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter(IntoEntries::new(self.root))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a PMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Box<dyn Iterator<Item = (&'a K, &'a V)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+/// Consuming iterator over the entries of a [`PMap`].
+///
+/// Entries still shared with another map are cloned.
+pub struct IntoIter<K, V>(IntoEntries<K, V>);
+
+impl<K, V> Iterator for IntoIter<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.0.next()
+    }
+}
+
+derive_pointed!(PMap<K : Eq : Hash, (V)>);
+derive_with_pointed!(PMap<K : Eq : Hash, (V)>);
+derive_iterable_map!(PMap<K, V>, insert, Eq, Hash);
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        hash::{Hash, Hasher},
+    };
+
+    use crate::{prelude::*, transformers::state::State};
+
+    use super::PMap;
+
+    /// Check a map against the equivalent [`HashMap`].
+    fn check<K>(m: &PMap<K, usize>, expected: &HashMap<K, usize>)
+    where
+        K: Clone + Eq + Hash + std::fmt::Debug,
+    {
+        assert_eq!(m.len(), expected.len());
+        assert_eq!(m.iter().count(), expected.len());
+        assert_eq!(m.clone().into_iter().collect::<HashMap<_, _>>(), *expected);
+        for (k, v) in expected {
+            assert_eq!(m.get(k), Some(v));
+        }
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut m = PMap::new();
+        let mut expected = HashMap::new();
+
+        for i in 0..2000usize {
+            m = m.insert(i * 7 % 1000, i);
+            expected.insert(i * 7 % 1000, i);
+        }
+        check(&m, &expected);
+
+        for i in (0..1000).step_by(3) {
+            m = m.remove(&i);
+            expected.remove(&i);
+        }
+        check(&m, &expected);
+        assert_eq!(m.get(&3), None);
+
+        let m = (0..1000).fold(m, |m, i| m.remove(&i));
+        assert!(m.is_empty());
+        assert_eq!(m, PMap::new());
+    }
+
+    /// A key whose hash only depends on its value modulo 4.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Colliding(usize);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            (self.0 % 4).hash(state)
+        }
+    }
+
+    #[test]
+    fn test_collisions() {
+        let m = (0..20).fold(PMap::new(), |m, i| m.insert(Colliding(i), i));
+        let expected = (0..20)
+            .map(|i| (Colliding(i), i))
+            .collect::<HashMap<_, _>>();
+        check(&m, &expected);
+
+        let m = (0..20)
+            .filter(|i| i % 4 != 1)
+            .fold(m, |m, i| m.remove(&Colliding(i)));
+        assert_eq!(m.len(), 5);
+        assert_eq!(m.get(&Colliding(4)), None);
+
+        let m = [1, 5, 13, 17]
+            .into_iter()
+            .fold(m, |m, i| m.remove(&Colliding(i)));
+        assert_eq!(m.len(), 1);
+        assert_eq!(m.get(&Colliding(9)), Some(&9));
+        assert_eq!(m.get(&Colliding(5)), None);
+    }
+
+    #[test]
+    fn test_persistence() {
+        let m = PMap::from_iter((0..100usize).map(|i| (i, i)));
+        let n = m
+            .clone()
+            .adjust(|v| v * 2, &10)
+            .remove(&20)
+            .insert(100, 100);
+
+        assert_eq!(m.len(), 100);
+        assert_eq!(m[&10], 10);
+        assert_eq!(m.get(&20), Some(&20));
+        assert_eq!(m.get(&100), None);
+
+        assert_eq!(n.len(), 100);
+        assert_eq!(n[&10], 20);
+        assert_eq!(n.get(&20), None);
+        assert_eq!(n.get(&100), Some(&100));
+
+        let n = n
+            .insert_with(|new, old| new + old, 10, 1)
+            .alter(|v: Option<usize>| v.map(|v| v + 1), 11)
+            .alter(|_| Some(0), 20);
+        assert_eq!(n[&10], 21);
+        assert_eq!(n[&11], 12);
+        assert_eq!(n[&20], 0);
+    }
+
+    #[test]
+    fn test_instances() {
+        let m = PMap::from_iter([("a", 1), ("b", 2), ("c", 3)]);
+
+        assert_eq!(
+            m.clone().fmap(|v| v * 10),
+            PMap::from_iter([("a", 10), ("b", 20), ("c", 30)])
+        );
+        assert_eq!(m.clone().foldr(|v, acc| v + acc, 0), 6);
+        assert_eq!(
+            m.clone().union(PMap::from_iter([("a", 100), ("d", 4)])),
+            PMap::from_iter([("a", 1), ("b", 2), ("c", 3), ("d", 4)])
+        );
+        assert_eq!(
+            m.clone()
+                .union_with(|l, r| l + r, PMap::from_iter([("a", 100)])),
+            PMap::from_iter([("a", 101), ("b", 2), ("c", 3)])
+        );
+
+        let counted = m
+            .keys()
+            .copied()
+            .fold(State::<PMap<&str, usize>, ()>::return_m(()), |acc, k| {
+                acc.then_m(State::<_, ()>::modify(move |s: PMap<&str, usize>| {
+                    s.insert_with(|new, old| new + old, k, 1)
+                }))
+            })
+            .exec(PMap::singleton("a", 1));
+        assert_eq!(counted, PMap::from_iter([("a", 2), ("b", 1), ("c", 1)]));
+    }
+}
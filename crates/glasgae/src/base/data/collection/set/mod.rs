@@ -1,3 +1,4 @@
 pub mod btree_set;
 pub mod hash_set;
+pub mod p_set;
 pub mod vec_set;
@@ -0,0 +1,226 @@
+//! Persistent hash sets, backed by [`PMap`].
+//!
+//! ```
+//! # use glasgae::base::data::collection::set::p_set::PSet;
+//! let s = PSet::from_iter([1, 2, 3]);
+//! let t = s.clone().insert(4).remove(&1);
+//!
+//! assert!(s.contains(&1));
+//! assert!(!t.contains(&1));
+//! assert_eq!(s.intersection(t), PSet::from_iter([2, 3]));
+//! ```
+
+use std::{fmt::Debug, hash::Hash};
+
+use crate::{
+    base::data::collection::map::p_map::{self, PMap},
+    derive_applicative_iterable, derive_foldable_iterable, derive_functor_iterable,
+    derive_monad_iterable, derive_monoid_iterable, derive_pointed, derive_semigroup_iterable,
+    derive_traversable_iterable, derive_with_pointed,
+    prelude::*,
+};
+
+/// A persistent set of hashable values.
+pub struct PSet<T>(PMap<T, ()>);
+
+impl<T> PSet<T> {
+    /// The empty set.
+    pub fn new() -> Self {
+        PSet(PMap::new())
+    }
+
+    /// The number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the elements of the set, in an unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.keys()
+    }
+}
+
+impl<T> PSet<T>
+where
+    T: Eq + Hash,
+{
+    /// A set containing a single element.
+    pub fn singleton(t: T) -> Self {
+        PSet(PMap::singleton(t, ()))
+    }
+
+    /// Returns `true` if the set contains `t`.
+    pub fn contains(&self, t: &T) -> bool {
+        self.0.contains_key(t)
+    }
+
+    /// Add an element to the set.
+    pub fn insert(self, t: T) -> Self {
+        PSet(self.0.insert(t, ()))
+    }
+
+    /// Remove an element from the set, if present.
+    pub fn remove(self, t: &T) -> Self {
+        PSet(self.0.remove(t))
+    }
+}
+
+impl<T> PSet<T>
+where
+    T: Term + Eq + Hash,
+{
+    /// The elements of either set.
+    pub fn union(self, other: Self) -> Self {
+        PSet(self.0.union(other.0))
+    }
+
+    /// The elements of both sets.
+    pub fn intersection(self, other: Self) -> Self {
+        let (small, large) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        small.into_iter().filter(|t| large.contains(t)).collect()
+    }
+
+    /// The elements of `self` which are not in `other`.
+    pub fn difference(self, other: Self) -> Self {
+        other.iter().fold(self, |acc, t| acc.remove(t))
+    }
+}
+
+/// Add an element to a set.
+pub fn insert<T>(t: T, s: PSet<T>) -> PSet<T>
+where
+    T: Eq + Hash,
+{
+    s.insert(t)
+}
+
+impl<T> Clone for PSet<T> {
+    fn clone(&self) -> Self {
+        PSet(self.0.clone())
+    }
+}
+
+impl<T> Default for PSet<T> {
+    fn default() -> Self {
+        PSet::new()
+    }
+}
+
+impl<T> Debug for PSet<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for PSet<T>
+where
+    T: Eq + Hash,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Eq for PSet<T> where T: Eq + Hash {}
+
+impl<T> FromIterator<T> for PSet<T>
+where
+    T: Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        PSet(iter.into_iter().map(|t| (t, ())).collect())
+    }
+}
+
+impl<T> Extend<T> for PSet<T>
+where
+    T: Eq + Hash,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(|t| (t, ())))
+    }
+}
+
+impl<T> IntoIterator for PSet<T>
+where
+    T: Clone,
+{
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self.0.into_iter())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PSet<T> {
+    type Item = &'a T;
+    type IntoIter = Box<dyn Iterator<Item = &'a T> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+/// Consuming iterator over the elements of a [`PSet`].
+///
+/// Elements still shared with another set are cloned.
+pub struct IntoIter<T>(p_map::IntoIter<T, ()>);
+
+impl<T> Iterator for IntoIter<T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.next().map(|(t, _)| t)
+    }
+}
+
+derive_pointed!(PSet<(X : Eq : Hash)>);
+derive_with_pointed!(PSet<(X : Eq : Hash)>);
+derive_functor_iterable!(PSet<(X : Eq : Hash)>);
+derive_applicative_iterable!(PSet<(X : Eq : Hash)>);
+derive_monad_iterable!(PSet<(X : Eq : Hash)>);
+derive_semigroup_iterable!(PSet<(X : Eq : Hash)>);
+derive_monoid_iterable!(PSet<(X : Eq : Hash)>);
+derive_foldable_iterable!(PSet<(X : Eq : Hash)>);
+derive_traversable_iterable!(PSet<(X : Eq : Hash)>, insert);
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    use super::PSet;
+
+    #[test]
+    fn test_set() {
+        let s = PSet::from_iter(0..10usize);
+        let t = PSet::from_iter((5..15).step_by(2));
+
+        assert_eq!(s.clone().union(t.clone()).len(), 12);
+        assert_eq!(
+            s.clone().intersection(t.clone()),
+            PSet::from_iter([5, 7, 9])
+        );
+        assert_eq!(
+            s.clone().difference(t),
+            PSet::from_iter([0, 1, 2, 3, 4, 6, 8])
+        );
+        assert_eq!(s.clone().fmap(|t| t / 2), PSet::from_iter(0..5));
+        assert_eq!(s.foldr(|t, acc| t + acc, 0), 45);
+    }
+}
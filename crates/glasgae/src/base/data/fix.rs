@@ -0,0 +1,455 @@
+//! Recursion schemes.
+//!
+//! A recursive type can be described by its *base functor*:
+//! a copy of the type with each recursive position replaced by a type parameter.
+//! [`Recursive`] types can be peeled apart one layer at a time with [`Recursive::project`],
+//! and [`Corecursive`] types can be built up one layer at a time with [`Corecursive::embed`].
+//!
+//! This is sufficient to express the common folds and unfolds once, generically,
+//! rather than hand-writing the traversal for each recursive type:
+//!
+//! * [`Recursive::cata`] folds a structure from the leaves up.
+//! * [`Recursive::para`] folds a structure, with access to each original subterm.
+//! * [`Recursive::histo`] folds a structure, with access to the results for every subterm.
+//! * [`Corecursive::ana`] unfolds a structure from a seed.
+//! * [`Corecursive::apo`] unfolds a structure, with the option to stop early.
+//! * [`Corecursive::futu`] unfolds a structure, with the option to produce several layers at once.
+//! * [`hylo`] unfolds and then folds a structure, without building it in full.
+//!
+//! [`Fix<F>`] is the least fixed point of the base functor `F`,
+//! and can be used to build recursive types directly from their base functor.
+//!
+//! # Base functors
+//!
+//! Since Rust lacks higher-kinded types, the [`Base`] of a type is
+//! a concrete instantiation of its base functor (conventionally `TF<()>`),
+//! and its layers are recovered as [`LayerT<T, A>`].
+//!
+//! The base functor, along with the [`Base`], [`Recursive`] and [`Corecursive`] instances,
+//! can be generated for an existing type with `#[derive(Recursive)]`.
+//!
+//! # Example
+//!
+//! ```
+//! # use glasgae::{base::data::fix::{Corecursive, Recursive}, prelude::*};
+//! #[derive(Debug, Clone, PartialEq, Recursive)]
+//! enum Expr {
+//!     Lit(i64),
+//!     Add(Box<Expr>, Box<Expr>),
+//!     Mul(Box<Expr>, Box<Expr>),
+//! }
+//!
+//! let expr = Expr::Add(
+//!     Box::new(Expr::Lit(2)),
+//!     Box::new(Expr::Mul(Box::new(Expr::Lit(3)), Box::new(Expr::Lit(4)))),
+//! );
+//!
+//! let value = expr.cata(|e: ExprF<i64>| match e {
+//!     ExprF::Lit(n) => n,
+//!     ExprF::Add(a, b) => a + b,
+//!     ExprF::Mul(a, b) => a * b,
+//! });
+//!
+//! assert_eq!(value, 14);
+//!
+//! // Build a sum of the numbers 1 to 3
+//! let sum = Expr::ana((1, false), |(n, leaf): (i64, bool)| {
+//!     if leaf || n == 3 {
+//!         ExprF::Lit(n)
+//!     } else {
+//!         ExprF::Add((n, true), (n + 1, false))
+//!     }
+//! });
+//!
+//! assert_eq!(
+//!     sum.cata(|e: ExprF<String>| match e {
+//!         ExprF::Lit(n) => n.to_string(),
+//!         ExprF::Add(a, b) => format!("{a} + {b}"),
+//!         ExprF::Mul(a, b) => format!("{a} * {b}"),
+//!     }),
+//!     "1 + 2 + 3"
+//! );
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{
+    base::{
+        control::monad::free::{Free, FreeLayerT},
+        data::cofree::{Cofree, CofreeLayerT},
+    },
+    prelude::*,
+};
+
+/// A type with an associated base functor.
+pub trait Base: Term {
+    /// The base functor of this type, instantiated at `()`.
+    type Base: Term + WithPointed<Self>;
+}
+
+/// Convenience alias to [`Base::Base`].
+pub type BaseT<T> = <T as Base>::Base;
+
+/// A single layer of `T`, with its recursive positions holding values of type `A`.
+pub type LayerT<T, A> = WithPointedT<BaseT<T>, A>;
+
+/// A type which can be taken apart one layer at a time.
+pub trait Recursive: Base {
+    /// Peel off the outermost layer of the structure.
+    fn project(self) -> LayerT<Self, Self>;
+
+    /// Catamorphism: fold the structure from the leaves up.
+    ///
+    /// ```text
+    /// cata(alg)(t) == alg(project(t).fmap(cata(alg)))
+    /// ```
+    fn cata<A>(self, alg: impl FunctionT<LayerT<Self, A>, A>) -> A
+    where
+        BaseT<Self>: WithPointed<A>,
+        LayerT<Self, Self>: Functor<A, Mapped = LayerT<Self, A>>,
+        A: Term,
+    {
+        let alg = alg.to_function();
+        alg.clone()(self.project().fmap(|t: Self| t.cata(alg)))
+    }
+
+    /// Paramorphism: fold the structure from the leaves up,
+    /// with access to the original subterm alongside each folded result.
+    ///
+    /// ```text
+    /// para(alg)(t) == alg(project(t).fmap(|t| (t, para(alg)(t))))
+    /// ```
+    fn para<A>(self, alg: impl FunctionT<LayerT<Self, (Self, A)>, A>) -> A
+    where
+        BaseT<Self>: WithPointed<(Self, A)>,
+        LayerT<Self, Self>: Functor<(Self, A), Mapped = LayerT<Self, (Self, A)>>,
+        A: Term,
+    {
+        let alg = alg.to_function();
+        alg.clone()(self.project().fmap(|t: Self| (t.clone(), t.para(alg))))
+    }
+
+    /// Histomorphism: fold the structure from the leaves up,
+    /// with access to the folded results of every descendant,
+    /// annotated onto the structure as a [`Cofree`].
+    fn histo<A>(self, alg: impl FunctionT<CofreeLayerT<BaseT<Self>, A>, A>) -> A
+    where
+        BaseT<Self>: WithPointed<Cofree<BaseT<Self>, A>>,
+        LayerT<Self, Self>: Functor<Cofree<BaseT<Self>, A>, Mapped = CofreeLayerT<BaseT<Self>, A>>,
+        CofreeLayerT<BaseT<Self>, A>: Clone,
+        A: Term,
+    {
+        self.histo_cofree(alg.to_function()).extract()
+    }
+
+    /// Annotate every node of the structure with the result of [`Recursive::histo`] at that node.
+    fn histo_cofree<A>(
+        self,
+        alg: impl FunctionT<CofreeLayerT<BaseT<Self>, A>, A>,
+    ) -> Cofree<BaseT<Self>, A>
+    where
+        BaseT<Self>: WithPointed<Cofree<BaseT<Self>, A>>,
+        LayerT<Self, Self>: Functor<Cofree<BaseT<Self>, A>, Mapped = CofreeLayerT<BaseT<Self>, A>>,
+        CofreeLayerT<BaseT<Self>, A>: Clone,
+        A: Term,
+    {
+        let alg = alg.to_function();
+        let layer = self.project().fmap({
+            let alg = alg.clone();
+            |t: Self| t.histo_cofree(alg)
+        });
+        Cofree::new(alg(layer.clone()), layer)
+    }
+}
+
+/// A type which can be built up one layer at a time.
+pub trait Corecursive: Base {
+    /// Wrap a layer into the structure.
+    fn embed(layer: LayerT<Self, Self>) -> Self;
+
+    /// Anamorphism: unfold the structure from a seed.
+    ///
+    /// ```text
+    /// ana(coalg)(a) == embed(coalg(a).fmap(ana(coalg)))
+    /// ```
+    fn ana<A>(a: A, coalg: impl FunctionT<A, LayerT<Self, A>>) -> Self
+    where
+        BaseT<Self>: WithPointed<A>,
+        LayerT<Self, A>: Functor<Self, Mapped = LayerT<Self, Self>>,
+        A: Term,
+    {
+        let coalg = coalg.to_function();
+        Self::embed(coalg.clone()(a).fmap(|a: A| Self::ana(a, coalg)))
+    }
+
+    /// Apomorphism: unfold the structure from a seed,
+    /// with the option of finishing a branch early by producing
+    /// a [`Left`] subterm in place of a [`Right`] seed.
+    fn apo<A>(a: A, coalg: impl FunctionT<A, LayerT<Self, Either<Self, A>>>) -> Self
+    where
+        BaseT<Self>: WithPointed<Either<Self, A>>,
+        LayerT<Self, Either<Self, A>>: Functor<Self, Mapped = LayerT<Self, Self>>,
+        A: Term,
+    {
+        let coalg = coalg.to_function();
+        Self::embed(coalg.clone()(a).fmap(|e: Either<Self, A>| match e {
+            Left(t) => t,
+            Right(a) => Self::apo(a, coalg),
+        }))
+    }
+
+    /// Futumorphism: unfold the structure from a seed,
+    /// with the option of producing several layers at once
+    /// by way of a [`Free`] program over the base functor.
+    fn futu<A>(a: A, coalg: impl FunctionT<A, FreeLayerT<BaseT<Self>, A>>) -> Self
+    where
+        BaseT<Self>: WithPointed<Free<BaseT<Self>, A>>,
+        FreeLayerT<BaseT<Self>, A>: Functor<Self, Mapped = LayerT<Self, Self>>,
+        A: Term,
+    {
+        let coalg = coalg.to_function();
+        Self::embed(coalg.clone()(a).fmap(|next: Free<BaseT<Self>, A>| futu_worker(next, coalg)))
+    }
+}
+
+fn futu_worker<T, A>(free: Free<BaseT<T>, A>, coalg: Function<A, FreeLayerT<BaseT<T>, A>>) -> T
+where
+    T: Corecursive,
+    BaseT<T>: WithPointed<Free<BaseT<T>, A>>,
+    FreeLayerT<BaseT<T>, A>: Functor<T, Mapped = LayerT<T, T>>,
+    A: Term,
+{
+    match free.resume() {
+        Right(a) => T::futu(a, coalg),
+        Left(layer) => T::embed(layer.fmap(|next: Free<BaseT<T>, A>| futu_worker(next, coalg))),
+    }
+}
+
+/// Hylomorphism: unfold a structure from a seed with `coalg`, then fold it with `alg`.
+///
+/// The intermediate structure is never built in full;
+/// each layer is folded as soon as it has been unfolded.
+///
+/// ```text
+/// hylo(a, alg, coalg) == alg(coalg(a).fmap(|a| hylo(a, alg, coalg)))
+/// ```
+pub fn hylo<A, B, FA, FB>(a: A, alg: impl FunctionT<FB, B>, coalg: impl FunctionT<A, FA>) -> B
+where
+    FA: Functor<B, Pointed = A, Mapped = FB>,
+    FB: Term,
+    A: Term,
+    B: Term,
+{
+    let alg = alg.to_function();
+    let coalg = coalg.to_function();
+    alg.clone()(coalg.clone()(a).fmap(|a: A| hylo(a, alg, coalg)))
+}
+
+/// The least fixed point of the functor `F`.
+///
+/// Wraps a layer of `F` whose recursive positions contain further [`Fix<F>`] values.
+pub struct Fix<F>(Erased, PhantomData<fn() -> F>);
+
+impl<F> Clone for Fix<F> {
+    fn clone(&self) -> Self {
+        Fix(self.0.clone(), PhantomData)
+    }
+}
+
+impl<F> Fix<F>
+where
+    F: Term + WithPointed<Fix<F>>,
+{
+    pub fn new(layer: WithPointedT<F, Fix<F>>) -> Self {
+        Fix(layer.boxed(), PhantomData)
+    }
+
+    pub fn unfix(self) -> WithPointedT<F, Fix<F>> {
+        self.0.downcast()
+    }
+}
+
+impl<F> Base for Fix<F>
+where
+    F: Term + WithPointed<Fix<F>>,
+{
+    type Base = F;
+}
+
+impl<F> Recursive for Fix<F>
+where
+    F: Term + WithPointed<Fix<F>>,
+{
+    fn project(self) -> WithPointedT<F, Fix<F>> {
+        self.unfix()
+    }
+}
+
+impl<F> Corecursive for Fix<F>
+where
+    F: Term + WithPointed<Fix<F>>,
+{
+    fn embed(layer: WithPointedT<F, Fix<F>>) -> Self {
+        Fix::new(layer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        base::{
+            control::monad::free::Free,
+            data::{
+                term_rewrite::{ZipperTerm, ZipperTermF},
+                tree::rose_tree::{RoseTree, RoseTreeF},
+            },
+        },
+        prelude::*,
+    };
+
+    use super::{hylo, Corecursive, Fix, Recursive};
+
+    #[derive(Debug, Clone, PartialEq, Recursive)]
+    enum Nat {
+        Zero,
+        Succ(Box<Nat>),
+    }
+
+    fn to_int(n: Nat) -> u64 {
+        n.cata(|n: NatF<u64>| match n {
+            NatF::Zero => 0,
+            NatF::Succ(n) => n + 1,
+        })
+    }
+
+    fn from_int(n: u64) -> Nat {
+        Nat::ana(n, |n: u64| {
+            if n == 0 {
+                NatF::Zero
+            } else {
+                NatF::Succ(n - 1)
+            }
+        })
+    }
+
+    fn tree() -> RoseTree<usize> {
+        RoseTree(
+            1,
+            vec![RoseTree(2, vec![RoseTree(4, vec![])]), RoseTree(3, vec![])],
+        )
+    }
+
+    #[test]
+    fn test_cata() {
+        let sum = tree()
+            .cata(|RoseTreeF(t, cs): RoseTreeF<usize, usize>| t + cs.into_iter().sum::<usize>());
+        assert_eq!(sum, 10);
+
+        let depth = tree().cata(|RoseTreeF(_, cs): RoseTreeF<usize, usize>| {
+            1 + cs.into_iter().max().unwrap_or_default()
+        });
+        assert_eq!(depth, 3);
+
+        assert_eq!(to_int(from_int(7)), 7);
+    }
+
+    #[test]
+    fn test_ana() {
+        let t = RoseTree::ana(3, |n: usize| RoseTreeF(n, (0..n).collect()));
+        assert_eq!(t.clone().fmap(|_| 1).foldr(|a, b| a + b, 0), 8);
+        assert_eq!(t.0, 3);
+        assert_eq!(t.1.len(), 3);
+    }
+
+    #[test]
+    fn test_para() {
+        let factorial = |n: Nat| {
+            n.para(|n: NatF<(Nat, u64)>| match n {
+                NatF::Zero => 1,
+                NatF::Succ((n, acc)) => (to_int(n) + 1) * acc,
+            })
+        };
+        assert_eq!(factorial(from_int(5)), 120);
+    }
+
+    #[test]
+    fn test_histo() {
+        let fib = |n: Nat| {
+            n.histo(|n: NatF<_>| match n {
+                NatF::Zero => 0,
+                NatF::Succ(prev) => match prev.clone().unwrap() {
+                    NatF::Zero => 1,
+                    NatF::Succ(prev_) => prev.extract() + prev_.extract(),
+                },
+            })
+        };
+        assert_eq!(fib(from_int(10)), 55);
+    }
+
+    #[test]
+    fn test_apo() {
+        let three = from_int(3);
+        let n = Nat::apo(10, move |n: u64| {
+            if n == 3 {
+                NatF::Succ(Left(three))
+            } else {
+                NatF::Succ(Right(n - 1))
+            }
+        });
+        assert_eq!(to_int(n), 11);
+    }
+
+    #[test]
+    fn test_futu() {
+        let n = Nat::futu(9, |n: u64| match n {
+            0 => NatF::Zero,
+            1 => NatF::Succ(Free::Pure(0)),
+            n => NatF::Succ(Free::wrap(NatF::Succ(Free::Pure(n - 2)))),
+        });
+        assert_eq!(n, from_int(9));
+    }
+
+    #[test]
+    fn test_hylo() {
+        let triangle = hylo(
+            100,
+            |RoseTreeF(t, cs): RoseTreeF<usize, usize>| t + cs.into_iter().sum::<usize>(),
+            |n: usize| RoseTreeF(n, if n > 0 { vec![n - 1] } else { vec![] }),
+        );
+        assert_eq!(triangle, 5050);
+    }
+
+    #[test]
+    fn test_fix() {
+        type FixNat = Fix<NatF<()>>;
+
+        let two = FixNat::embed(NatF::Succ(FixNat::embed(NatF::Succ(FixNat::embed(
+            NatF::Zero,
+        )))));
+
+        let n = two.cata(|n: NatF<u64>| match n {
+            NatF::Zero => 0,
+            NatF::Succ(n) => n + 1,
+        });
+        assert_eq!(n, 2);
+    }
+
+    #[test]
+    fn test_zipper_term() {
+        let term = ZipperTerm::a(
+            ZipperTerm::l("x", ZipperTerm::var("x")),
+            ZipperTerm::l(
+                "y",
+                ZipperTerm::a(ZipperTerm::var("y"), ZipperTerm::var("z")),
+            ),
+        );
+
+        let vars = term.cata(|t: ZipperTermF<Vec<String>>| match t {
+            ZipperTermF::Var(v) => vec![v],
+            ZipperTermF::L(_, vs) => vs,
+            ZipperTermF::A(l, r) => l.into_iter().chain(r).collect(),
+            ZipperTermF::Free => vec![],
+        });
+        assert_eq!(vars, vec!["x", "y", "z"]);
+    }
+}
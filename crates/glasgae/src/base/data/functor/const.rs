@@ -1,52 +1,84 @@
-use crate::{derive_pointed_via, prelude::*};
+use std::marker::PhantomData;
+
+use crate::{derive_pointed, derive_with_pointed, prelude::*};
 
 use super::Functor;
 
 /// The Const functor.
+///
+/// Holds an `MA`, while pointing at a phantom `B`.
+/// Mapping over it only changes `B`,
+/// and applicative combination combines the held values with their [`Semigroup`] instance.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Const<MA>(pub MA);
+pub struct Const<MA, B>(pub MA, PhantomData<fn() -> B>);
+
+impl<MA, B> Const<MA, B> {
+    pub fn new(ma: MA) -> Self {
+        Const(ma, PhantomData)
+    }
 
-impl<MA> Const<MA> {
     pub fn get(self) -> MA {
         self.0
     }
 }
 
-derive_pointed_via!(Const<(MA)>);
+derive_pointed!(Const<MA, (B)>);
+derive_with_pointed!(Const<MA, (B)>);
 
-impl<MA, T> WithPointed<T> for Const<MA>
+impl<MA, A, B> Functor<B> for Const<MA, A>
 where
-    MA: Pointed<Pointed = T>,
+    MA: Term,
+    A: Term,
+    B: Term,
 {
-    type WithPointed = Self;
+    type Mapped = Const<MA, B>;
+
+    fn fmap(self, _: impl FunctionT<A, B>) -> Const<MA, B> {
+        Const::new(self.0)
+    }
 }
 
-impl<MA, A> Functor<A> for Const<MA>
+impl<MA, A> PureA for Const<MA, A>
 where
-    MA: Pointed<Pointed = A> + WithPointed<A>,
+    MA: Monoid,
+    A: Term,
+{
+    fn pure_a(_: A) -> Self {
+        Const::new(MA::mempty())
+    }
+}
+
+impl<MA, F, A, B> AppA<A, B> for Const<MA, F>
+where
+    MA: Semigroup,
+    F: Term,
     A: Term,
+    B: Term,
 {
-    type Mapped = Const<MA>;
+    type WithA = Const<MA, A>;
+    type WithB = Const<MA, B>;
 
-    fn fmap(self, _: impl FunctionT<Self::Pointed, A>) -> Self::WithPointed {
-        self
+    fn app_a(self, a: Const<MA, A>) -> Const<MA, B> {
+        Const::new(self.0.assoc_s(a.0))
     }
 }
 
-impl<MA> Semigroup for Const<MA>
+impl<MA, B> Semigroup for Const<MA, B>
 where
     MA: Semigroup,
+    B: Term,
 {
     fn assoc_s(self, a: Self) -> Self {
-        Const(self.0.assoc_s(a.0))
+        Const::new(self.0.assoc_s(a.0))
     }
 }
 
-impl<MA> Monoid for Const<MA>
+impl<MA, B> Monoid for Const<MA, B>
 where
-    MA: 'static + Monoid,
+    MA: Monoid,
+    B: Term,
 {
     fn mempty() -> Self {
-        Const(MA::mempty())
+        Const::new(MA::mempty())
     }
 }
@@ -0,0 +1,184 @@
+//! Mutable references in the IO monad.
+//!
+//! Based on [Data.IORef](https://hackage.haskell.org/package/base/docs/Data-IORef.html).
+//!
+//! ```
+//! # use glasgae::{base::data::io_ref::*, prelude::*};
+//! let io = new_io_ref(0).chain_m(|counter| {
+//!     (1..=10)
+//!         .map(|i| modify_io_ref(counter.clone(), move |n| n + i))
+//!         .fold(IO::return_m(()), |acc, next| acc.then_m(next))
+//!         .then_m(read_io_ref(counter))
+//! });
+//!
+//! assert_eq!(unsafe { io.run() }, 55);
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::prelude::*;
+
+/// A mutable variable in the IO monad.
+///
+/// Clones refer to the same variable.
+/// Every operation holds a lock for its duration,
+/// so `IORef`s may be shared between threads.
+pub struct IORef<T>(Arc<Mutex<T>>);
+
+impl<T> IORef<T> {
+    /// Lock the variable, recovering it if an earlier modification panicked.
+    ///
+    /// Modifications never write a partially computed value,
+    /// so the contents remain valid after a panic.
+    fn lock(&self) -> MutexGuard<'_, T> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for IORef<T> {
+    fn clone(&self) -> Self {
+        IORef(self.0.clone())
+    }
+}
+
+/// Two `IORef`s are equal if they refer to the same variable.
+impl<T> PartialEq for IORef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T> Eq for IORef<T> {}
+
+impl<T> std::fmt::Debug for IORef<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("IORef").field(&*self.lock()).finish()
+    }
+}
+
+/// Build a new [`IORef`].
+pub fn new_io_ref<T>(t: T) -> IO<IORef<T>>
+where
+    T: Term,
+{
+    IO::new(move || IORef(Arc::new(Mutex::new(t))))
+}
+
+/// Read the value of an [`IORef`].
+pub fn read_io_ref<T>(r: IORef<T>) -> IO<T>
+where
+    T: Term,
+{
+    IO::new(move || r.lock().clone())
+}
+
+/// Write a new value into an [`IORef`].
+pub fn write_io_ref<T>(r: IORef<T>, t: T) -> IO<()>
+where
+    T: Term,
+{
+    IO::new(move || *r.lock() = t)
+}
+
+/// Mutate the contents of an [`IORef`].
+///
+/// The variable is left unchanged if `f` panics.
+pub fn modify_io_ref<T>(r: IORef<T>, f: impl FunctionT<T, T>) -> IO<()>
+where
+    T: Term,
+{
+    let f = f.to_function();
+    atomic_modify_io_ref(r, |t| (f(t), ()))
+}
+
+/// Strict version of [`modify_io_ref`].
+///
+/// Values are always evaluated eagerly, so this behaves identically to [`modify_io_ref`];
+/// it is provided to ease porting code that uses `modifyIORef'`.
+pub fn modify_io_ref_strict<T>(r: IORef<T>, f: impl FunctionT<T, T>) -> IO<()>
+where
+    T: Term,
+{
+    modify_io_ref(r, f)
+}
+
+/// Atomically modify the contents of an [`IORef`], returning an auxiliary result.
+///
+/// No other operation on the same `IORef` can observe or change its value while `f` runs.
+/// The variable is left unchanged if `f` panics.
+pub fn atomic_modify_io_ref<T, B>(r: IORef<T>, f: impl FunctionT<T, (T, B)>) -> IO<B>
+where
+    T: Term,
+    B: Term,
+{
+    let f = f.to_function();
+    IO::new(move || {
+        let mut guard = r.lock();
+        let (t, b) = f(guard.clone());
+        *guard = t;
+        b
+    })
+}
+
+/// Atomically write a new value into an [`IORef`].
+pub fn atomic_write_io_ref<T>(r: IORef<T>, t: T) -> IO<()>
+where
+    T: Term,
+{
+    atomic_modify_io_ref(r, |_| (t, ()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{base::control::exception::catch, prelude::*};
+
+    use super::{atomic_modify_io_ref, modify_io_ref, new_io_ref, read_io_ref, write_io_ref};
+
+    #[test]
+    fn test_io_ref() {
+        let io = new_io_ref(vec![1]).chain_m(|r| {
+            write_io_ref(r.clone(), vec![2])
+                .then_m(atomic_modify_io_ref(r.clone(), |mut v: Vec<usize>| {
+                    let len = v.len();
+                    v.push(3);
+                    (v, len)
+                }))
+                .chain_m(move |len| read_io_ref(r).fmap(move |v| (len, v)))
+        });
+        assert_eq!(unsafe { io.run() }, (1, vec![2, 3]));
+    }
+
+    #[test]
+    fn test_panic_leaves_value() {
+        let io = new_io_ref(1).chain_m(|r| {
+            catch(
+                modify_io_ref(r.clone(), |_: usize| panic!("modify")),
+                |_: String| IO::return_m(()),
+            )
+            .then_m(modify_io_ref(r.clone(), |n| n + 1))
+            .then_m(read_io_ref(r))
+        });
+        assert_eq!(unsafe { io.run() }, 2);
+    }
+
+    #[test]
+    fn test_threads() {
+        let r = unsafe { new_io_ref(0usize).run() };
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                let r = r.clone();
+                scope.spawn(move || {
+                    for _ in 0..1000 {
+                        unsafe { atomic_modify_io_ref(r.clone(), |n| (n + 1, ())).run() }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(unsafe { read_io_ref(r).run() }, 8000);
+    }
+}
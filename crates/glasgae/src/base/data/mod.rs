@@ -6,17 +6,23 @@ pub mod bifunctor;
 pub mod bipointed;
 pub mod bitraversable;
 pub mod boxed;
+pub mod cofree;
 pub mod collection;
 pub mod either;
+pub mod fix;
 pub mod foldable;
 pub mod foldable1;
 pub mod function;
 pub mod functor;
+pub mod io_ref;
 pub mod maybe;
 pub mod monoid;
+pub mod optics;
+pub mod plated;
 pub mod pointed;
 pub mod semigroup;
 pub mod term;
+pub mod term_rewrite;
 pub mod traversable;
 pub mod tree;
 pub mod tuple;
@@ -0,0 +1,60 @@
+use crate::prelude::*;
+
+use super::{Preview, ToListOf};
+
+/// A fold reads zero or more `A`s out of an `S`, but cannot modify them.
+#[derive(Clone)]
+pub struct Fold<S, A>
+where
+    S: Term,
+    A: Term,
+{
+    fold: Function<S, Vec<A>>,
+}
+
+impl<S, A> Fold<S, A>
+where
+    S: Term,
+    A: Term,
+{
+    /// Build a fold from a function listing its foci.
+    pub fn new(fold: impl FunctionT<S, Vec<A>>) -> Self {
+        Fold { fold: fold.boxed() }
+    }
+
+    /// Fold over every element of a [`Foldable`] structure.
+    pub fn folded() -> Self
+    where
+        S: Foldable<Vec<A>, Pointed = A>,
+    {
+        Fold::new(|s: S| {
+            s.foldr(
+                |a, mut acc: Vec<A>| {
+                    acc.insert(0, a);
+                    acc
+                },
+                vec![],
+            )
+        })
+    }
+}
+
+impl<S, A> Preview<S, A> for Fold<S, A>
+where
+    S: Term,
+    A: Term,
+{
+    fn preview(self, s: S) -> Option<A> {
+        self.to_list_of(s).into_iter().next()
+    }
+}
+
+impl<S, A> ToListOf<S, A> for Fold<S, A>
+where
+    S: Term,
+    A: Term,
+{
+    fn to_list_of(self, s: S) -> Vec<A> {
+        (self.fold)(s)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{base::data::functor::r#const::Const, prelude::*};
+
+use super::{Over, Preview, ToListOf, View};
+
+/// An isomorphism between `S` and `A`,
+/// which converts a `B` back into a `T`.
+#[derive(Clone)]
+pub struct Iso<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    from: Function<S, A>,
+    to: Function<B, T>,
+}
+
+/// An [`Iso`] which does not change the type of its target.
+pub type SimpleIso<S, A> = Iso<S, S, A, A>;
+
+impl<S, T, A, B> Iso<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    /// Build an isomorphism from a pair of inverse functions.
+    pub fn new(from: impl FunctionT<S, A>, to: impl FunctionT<B, T>) -> Self {
+        Iso {
+            from: from.boxed(),
+            to: to.boxed(),
+        }
+    }
+
+    /// Reverse the direction of the isomorphism.
+    pub fn re(self) -> Iso<B, A, T, S> {
+        Iso {
+            from: self.to,
+            to: self.from,
+        }
+    }
+
+    /// Convert a `B` back into a `T`.
+    pub fn review(self, b: B) -> T {
+        (self.to)(b)
+    }
+
+    /// Run the isomorphism in van Laarhoven form,
+    /// lifting an effectful modification of the focus into one of the whole.
+    ///
+    /// ```text
+    /// run(f)(s) == f(from(s)).fmap(to)
+    /// ```
+    pub fn run<FB, FT>(self, f: impl FunctionT<A, FB>) -> impl FunctionT<S, FT>
+    where
+        FB: Functor<T, Pointed = B, Mapped = FT>,
+        FT: Term,
+    {
+        let f = f.to_function();
+        move |s: S| {
+            let Iso { from, to } = self;
+            f(from(s)).fmap(to)
+        }
+    }
+}
+
+impl<S, T, A, B> View<S, A> for Iso<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn view(self, s: S) -> A {
+        self.run(Const::new)(s).get()
+    }
+}
+
+impl<S, T, A, B> Over<S, T, A, B> for Iso<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn over(self, f: impl FunctionT<A, B>, s: S) -> T {
+        let f = f.to_function();
+        self.run(move |a| Identity(f(a)))(s).run()
+    }
+}
+
+impl<S, T, A, B> Preview<S, A> for Iso<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn preview(self, s: S) -> Option<A> {
+        Some(self.view(s))
+    }
+}
+
+impl<S, T, A, B> ToListOf<S, A> for Iso<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn to_list_of(self, s: S) -> Vec<A> {
+        self.run(|a| Const::new(vec![a]))(s).get()
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{base::data::functor::r#const::Const, prelude::*};
+
+use super::{Over, Preview, ToListOf, View};
+
+/// A lens focuses on exactly one `A` within an `S`,
+/// which can be replaced by a `B` to produce a `T`.
+#[derive(Clone)]
+pub struct Lens<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    get: Function<S, A>,
+    set: Bifun<S, B, T>,
+}
+
+/// A [`Lens`] which does not change the type of its target.
+pub type SimpleLens<S, A> = Lens<S, S, A, A>;
+
+impl<S, T, A, B> Lens<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    /// Build a lens from a getter and a setter.
+    pub fn new(get: impl FunctionT<S, A>, set: impl BifunT<S, B, T>) -> Self {
+        Lens {
+            get: get.boxed(),
+            set: set.boxed(),
+        }
+    }
+
+    /// Run the lens in van Laarhoven form,
+    /// lifting an effectful modification of the focus into one of the whole.
+    ///
+    /// ```text
+    /// run(f)(s) == f(get(s)).fmap(|b| set(s, b))
+    /// ```
+    pub fn run<FB, FT>(self, f: impl FunctionT<A, FB>) -> impl FunctionT<S, FT>
+    where
+        FB: Functor<T, Pointed = B, Mapped = FT>,
+        FT: Term,
+    {
+        let f = f.to_function();
+        move |s: S| {
+            let Lens { get, set } = self;
+            f(get(s.clone())).fmap(move |b| set(s, b))
+        }
+    }
+}
+
+impl<A, B, C> Lens<(A, C), (B, C), A, B>
+where
+    A: Term,
+    B: Term,
+    C: Term,
+{
+    /// Focus on the first element of a pair.
+    pub fn fst() -> Self {
+        Lens::new(|(a, _)| a, |(_, c), b| (b, c))
+    }
+}
+
+impl<A, B, C> Lens<(C, A), (C, B), A, B>
+where
+    A: Term,
+    B: Term,
+    C: Term,
+{
+    /// Focus on the second element of a pair.
+    pub fn snd() -> Self {
+        Lens::new(|(_, a)| a, |(c, _), b| (c, b))
+    }
+}
+
+impl<S, T, A, B> View<S, A> for Lens<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn view(self, s: S) -> A {
+        self.run(Const::new)(s).get()
+    }
+}
+
+impl<S, T, A, B> Over<S, T, A, B> for Lens<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn over(self, f: impl FunctionT<A, B>, s: S) -> T {
+        let f = f.to_function();
+        self.run(move |a| Identity(f(a)))(s).run()
+    }
+}
+
+impl<S, T, A, B> Preview<S, A> for Lens<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn preview(self, s: S) -> Option<A> {
+        Some(self.view(s))
+    }
+}
+
+impl<S, T, A, B> ToListOf<S, A> for Lens<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn to_list_of(self, s: S) -> Vec<A> {
+        self.run(|a| Const::new(vec![a]))(s).get()
+    }
+}
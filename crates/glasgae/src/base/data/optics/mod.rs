@@ -0,0 +1,607 @@
+//! Optics: composable, first-class references into data structures.
+//!
+//! An optic focuses on some part of a larger structure,
+//! and can be used to read, replace or modify that part
+//! without the caller having to rebuild the surrounding structure by hand.
+//!
+//! * A [`Lens`] focuses on exactly one target, such as a struct field.
+//! * A [`Prism`] focuses on at most one target, such as an enum variant.
+//! * An [`Iso`] converts losslessly between two representations of the same data.
+//! * A [`Traversal`] focuses on zero or more targets, such as the elements of a [`Vec`].
+//! * A [`Fold`] reads zero or more targets, but cannot modify them.
+//!
+//! Optics are used through the [`View`], [`Over`], [`Preview`] and [`ToListOf`] traits,
+//! and composed from the outside in with [`Then`].
+//! Composing two different kinds of optic produces the most specific kind that supports both;
+//! for example, a [`Lens`] followed by a [`Prism`] is a [`Traversal`].
+//!
+//! Each optic can also be run in van Laarhoven form with its `run` method,
+//! which lifts an action on its focus into an action on the whole structure.
+//! [`Lens::run`] and [`Iso::run`] accept any [`Functor`],
+//! while [`Prism::run`] and [`Traversal::run`] require an [`Applicative`].
+//! Modification is implemented in these terms by running the optic with [`Identity`],
+//! and reading by running it with [`Const`](crate::base::data::functor::r#const::Const).
+//!
+//! Simple lenses for each field of a struct can be generated with `#[derive(Lenses)]`,
+//! and the state of a [`MonadState`](crate::mtl::state::MonadState) can be read and updated through an optic
+//! with [`use_`](crate::mtl::state::use_), [`assign`](crate::mtl::state::assign)
+//! and [`modifying`](crate::mtl::state::modifying).
+//!
+//! # Example
+//!
+//! ```
+//! # use glasgae::{
+//! #     base::data::optics::{Over, Then, View},
+//! #     mtl::state::{assign, modifying, use_},
+//! #     prelude::*,
+//! #     transformers::state::State,
+//! # };
+//! #[derive(Debug, Clone, PartialEq, Lenses)]
+//! struct Position {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! #[derive(Debug, Clone, PartialEq, Lenses)]
+//! struct Player {
+//!     name: String,
+//!     position: Position,
+//! }
+//!
+//! let player = Player {
+//!     name: "Alice".to_string(),
+//!     position: Position { x: 0, y: 0 },
+//! };
+//!
+//! let x = Player::position_lens().then(Position::x_lens());
+//! assert_eq!(x.clone().view(player.clone()), 0);
+//! assert_eq!(x.clone().set(3, player.clone()).position, Position { x: 3, y: 0 });
+//!
+//! // Within a state monad
+//! let step: State<Player, ()> = modifying(x.clone(), |x: i32| x + 1);
+//! let step: State<Player, i32> = step
+//!     .then_m(assign(Player::position_lens().then(Position::y_lens()), 5))
+//!     .then_m(use_(x));
+//!
+//! let (x, player) = step.run(player);
+//! assert_eq!(x, 1);
+//! assert_eq!(player.position, Position { x: 1, y: 5 });
+//! ```
+
+mod fold;
+mod iso;
+mod lens;
+mod prism;
+mod traversal;
+
+pub use fold::*;
+pub use iso::*;
+pub use lens::*;
+pub use prism::*;
+pub use traversal::*;
+
+use crate::prelude::*;
+
+/// Optics which focus on exactly one target.
+pub trait View<S, A>: Term {
+    /// Read the target of the optic.
+    fn view(self, s: S) -> A;
+}
+
+/// Optics which can modify their targets.
+pub trait Over<S, T, A, B>: Term
+where
+    A: Term,
+    B: Term,
+{
+    /// Modify every target of the optic with the given function.
+    fn over(self, f: impl FunctionT<A, B>, s: S) -> T;
+
+    /// Replace every target of the optic with the given value.
+    ///
+    /// ```text
+    /// set(b, s) == over(r#const(b), s)
+    /// ```
+    fn set(self, b: B, s: S) -> T {
+        self.over(r#const(b), s)
+    }
+}
+
+/// Optics which can read their first target, if any.
+pub trait Preview<S, A>: Term {
+    /// Read the first target of the optic, if one exists.
+    fn preview(self, s: S) -> Option<A>;
+}
+
+/// Optics which can read all of their targets.
+pub trait ToListOf<S, A>: Term {
+    /// Read every target of the optic, from left to right.
+    fn to_list_of(self, s: S) -> Vec<A>;
+}
+
+/// Optic composition.
+pub trait Then<O>: Term {
+    type Composed: Term;
+
+    /// Compose this optic with another, which focuses within this optic's targets.
+    fn then(self, o: O) -> Self::Composed;
+}
+
+// Conversions
+impl<S, T, A, B> From<Iso<S, T, A, B>> for Lens<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn from(iso: Iso<S, T, A, B>) -> Self {
+        let from = iso.clone();
+        Lens::new(move |s| from.view(s), move |_, b| iso.review(b))
+    }
+}
+
+impl<S, T, A, B> From<Iso<S, T, A, B>> for Prism<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn from(iso: Iso<S, T, A, B>) -> Self {
+        let from = iso.clone();
+        Prism::new(move |s| Right(from.view(s)), move |b| iso.review(b))
+    }
+}
+
+impl<S, T, A, B> From<Lens<S, T, A, B>> for Traversal<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn from(lens: Lens<S, T, A, B>) -> Self {
+        Traversal::new(move |s: S| -> Parts<A, B, T> {
+            let a = lens.clone().view(s.clone());
+            (
+                vec![a],
+                (move |bs: Vec<B>| {
+                    lens.set(
+                        bs.into_iter()
+                            .next()
+                            .expect("traversal: too few replacements"),
+                        s,
+                    )
+                })
+                .boxed(),
+            )
+        })
+    }
+}
+
+impl<S, T, A, B> From<Prism<S, T, A, B>> for Traversal<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn from(prism: Prism<S, T, A, B>) -> Self {
+        Traversal::new(move |s: S| -> Parts<A, B, T> {
+            match prism.clone().matching(s) {
+                Left(t) => (vec![], r#const(t).boxed()),
+                Right(a) => (
+                    vec![a],
+                    (move |bs: Vec<B>| {
+                        prism.review(
+                            bs.into_iter()
+                                .next()
+                                .expect("traversal: too few replacements"),
+                        )
+                    })
+                    .boxed(),
+                ),
+            }
+        })
+    }
+}
+
+impl<S, T, A, B> From<Iso<S, T, A, B>> for Traversal<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn from(iso: Iso<S, T, A, B>) -> Self {
+        Lens::from(iso).into()
+    }
+}
+
+macro_rules! impl_into_fold {
+    ($($optic:ident),*) => {
+        $(
+            impl<S, T, A, B> From<$optic<S, T, A, B>> for Fold<S, A>
+            where
+                S: Term,
+                T: Term,
+                A: Term,
+                B: Term,
+            {
+                fn from(optic: $optic<S, T, A, B>) -> Self {
+                    Fold::new(move |s| optic.to_list_of(s))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_fold!(Lens, Prism, Iso, Traversal);
+
+// Composition of like optics
+impl<S, T, A, B, C, D> Then<Lens<A, B, C, D>> for Lens<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+    C: Term,
+    D: Term,
+{
+    type Composed = Lens<S, T, C, D>;
+
+    fn then(self, o: Lens<A, B, C, D>) -> Lens<S, T, C, D> {
+        let outer = self.clone();
+        let inner = o.clone();
+        Lens::new(
+            move |s| inner.view(outer.view(s)),
+            move |s, d| self.over(move |a| o.set(d, a), s),
+        )
+    }
+}
+
+impl<S, T, A, B, C, D> Then<Prism<A, B, C, D>> for Prism<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+    C: Term,
+    D: Term,
+{
+    type Composed = Prism<S, T, C, D>;
+
+    fn then(self, o: Prism<A, B, C, D>) -> Prism<S, T, C, D> {
+        let outer = self.clone();
+        let inner = o.clone();
+        Prism::new(
+            move |s| match outer.clone().matching(s) {
+                Left(t) => Left(t),
+                Right(a) => match inner.matching(a) {
+                    Left(b) => Left(outer.review(b)),
+                    Right(c) => Right(c),
+                },
+            },
+            move |d| self.review(o.review(d)),
+        )
+    }
+}
+
+impl<S, T, A, B, C, D> Then<Iso<A, B, C, D>> for Iso<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+    C: Term,
+    D: Term,
+{
+    type Composed = Iso<S, T, C, D>;
+
+    fn then(self, o: Iso<A, B, C, D>) -> Iso<S, T, C, D> {
+        let outer = self.clone();
+        let inner = o.clone();
+        Iso::new(
+            move |s| inner.view(outer.view(s)),
+            move |d| self.review(o.review(d)),
+        )
+    }
+}
+
+impl<S, T, A, B, C, D> Then<Traversal<A, B, C, D>> for Traversal<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+    C: Term,
+    D: Term,
+{
+    type Composed = Traversal<S, T, C, D>;
+
+    fn then(self, o: Traversal<A, B, C, D>) -> Traversal<S, T, C, D> {
+        Traversal::new(move |s: S| -> Parts<C, D, T> {
+            let (foci, rebuild) = self.parts(s);
+
+            let mut cs = vec![];
+            let mut inner = vec![];
+            for a in foci {
+                let (c, rebuild_inner) = o.clone().parts(a);
+                inner.push((c.len(), rebuild_inner));
+                cs.extend(c);
+            }
+
+            (
+                cs,
+                (move |ds: Vec<D>| {
+                    let mut ds = ds.into_iter();
+                    rebuild(
+                        inner
+                            .into_iter()
+                            .map(|(len, rebuild_inner)| {
+                                rebuild_inner(ds.by_ref().take(len).collect())
+                            })
+                            .collect(),
+                    )
+                })
+                .boxed(),
+            )
+        })
+    }
+}
+
+impl<S, A, C> Then<Fold<A, C>> for Fold<S, A>
+where
+    S: Term,
+    A: Term,
+    C: Term,
+{
+    type Composed = Fold<S, C>;
+
+    fn then(self, o: Fold<A, C>) -> Fold<S, C> {
+        Fold::new(move |s| {
+            self.to_list_of(s)
+                .into_iter()
+                .flat_map(|a| o.clone().to_list_of(a))
+                .collect()
+        })
+    }
+}
+
+// Composition of unlike optics, via the most specific optic that supports both
+macro_rules! impl_then {
+    ($($lhs:ident, $rhs:ident => $out:ident;)*) => {
+        $(
+            impl<S, T, A, B, C, D> Then<$rhs<A, B, C, D>> for $lhs<S, T, A, B>
+            where
+                S: Term,
+                T: Term,
+                A: Term,
+                B: Term,
+                C: Term,
+                D: Term,
+            {
+                type Composed = $out<S, T, C, D>;
+
+                fn then(self, o: $rhs<A, B, C, D>) -> $out<S, T, C, D> {
+                    $out::from(self).then($out::from(o))
+                }
+            }
+        )*
+    };
+}
+
+impl_then! {
+    Lens, Prism => Traversal;
+    Lens, Iso => Lens;
+    Lens, Traversal => Traversal;
+    Prism, Lens => Traversal;
+    Prism, Iso => Prism;
+    Prism, Traversal => Traversal;
+    Iso, Lens => Lens;
+    Iso, Prism => Prism;
+    Iso, Traversal => Traversal;
+    Traversal, Lens => Traversal;
+    Traversal, Prism => Traversal;
+    Traversal, Iso => Traversal;
+}
+
+macro_rules! impl_then_fold {
+    ($($optic:ident),*) => {
+        $(
+            impl<S, T, A, B, C> Then<Fold<A, C>> for $optic<S, T, A, B>
+            where
+                S: Term,
+                T: Term,
+                A: Term,
+                B: Term,
+                C: Term,
+            {
+                type Composed = Fold<S, C>;
+
+                fn then(self, o: Fold<A, C>) -> Fold<S, C> {
+                    Fold::from(self).then(o)
+                }
+            }
+
+            impl<S, A, B, C, D> Then<$optic<A, B, C, D>> for Fold<S, A>
+            where
+                S: Term,
+                A: Term,
+                B: Term,
+                C: Term,
+                D: Term,
+            {
+                type Composed = Fold<S, C>;
+
+                fn then(self, o: $optic<A, B, C, D>) -> Fold<S, C> {
+                    self.then(Fold::from(o))
+                }
+            }
+        )*
+    };
+}
+
+impl_then_fold!(Lens, Prism, Iso, Traversal);
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    use super::{Fold, Iso, Lens, Over, Preview, Prism, Then, ToListOf, Traversal, View};
+
+    #[derive(Debug, Clone, PartialEq, Lenses)]
+    struct Tagged<T>(&'static str, T);
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Shape {
+        Circle(f64),
+        Rect(f64, f64),
+    }
+
+    fn circle() -> Prism<Shape, Shape, f64, f64> {
+        Prism::new(
+            |s| match s {
+                Shape::Circle(r) => Right(r),
+                s => Left(s),
+            },
+            Shape::Circle,
+        )
+    }
+
+    #[test]
+    fn test_lens() {
+        let fst = Lens::<(usize, &str), (usize, &str), usize, usize>::fst();
+        assert_eq!(fst.clone().view((1, "a")), 1);
+        assert_eq!(fst.clone().set(2, (1, "a")), (2, "a"));
+        assert_eq!(fst.over(|a: usize| a + 1, (1, "a")), (2, "a"));
+
+        // Type-changing
+        let snd = Lens::<(usize, usize), (usize, String), usize, String>::snd();
+        assert_eq!(
+            snd.over(|a: usize| a.to_string(), (1, 2)),
+            (1, "2".to_string())
+        );
+
+        // Van Laarhoven form
+        let fst = Lens::<(usize, char), (usize, char), usize, usize>::fst();
+        assert_eq!(
+            fst.clone().run(|a: usize| vec![a, a + 1])((1, 'a')),
+            vec![(1, 'a'), (2, 'a')]
+        );
+        assert_eq!(fst.run(|_| None::<usize>)((1, 'a')), None);
+    }
+
+    #[test]
+    fn test_prism() {
+        assert_eq!(circle().preview(Shape::Circle(1.0)), Some(1.0));
+        assert_eq!(circle().preview(Shape::Rect(1.0, 2.0)), None);
+        assert_eq!(
+            circle().over(|r: f64| r * 2.0, Shape::Circle(1.0)),
+            Shape::Circle(2.0)
+        );
+        assert_eq!(
+            circle().over(|r: f64| r * 2.0, Shape::Rect(1.0, 2.0)),
+            Shape::Rect(1.0, 2.0)
+        );
+        assert_eq!(circle().review(3.0), Shape::Circle(3.0));
+        assert_eq!(
+            Prism::<Option<usize>, Option<usize>, usize, usize>::some().to_list_of(Some(3)),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn test_iso() {
+        let chars = Iso::<String, String, Vec<char>, Vec<char>>::new(
+            |s: String| s.chars().collect(),
+            |cs: Vec<char>| cs.into_iter().collect(),
+        );
+        assert_eq!(chars.clone().view("ab".to_string()), vec!['a', 'b']);
+        assert_eq!(
+            chars.clone().over(
+                |mut cs: Vec<char>| {
+                    cs.reverse();
+                    cs
+                },
+                "ab".to_string()
+            ),
+            "ba"
+        );
+        assert_eq!(chars.re().view(vec!['c']), "c");
+    }
+
+    #[test]
+    fn test_traversal() {
+        let each = Traversal::<Vec<usize>, Vec<usize>, usize, usize>::traversed();
+        assert_eq!(each.clone().to_list_of(vec![1, 2, 3]), vec![1, 2, 3]);
+        assert_eq!(
+            each.clone().over(|a: usize| a * 2, vec![1, 2, 3]),
+            vec![2, 4, 6]
+        );
+        assert_eq!(each.clone().preview(vec![]), None);
+        assert_eq!(
+            each.clone()
+                .run(|a: usize| if a > 0 { Some(a - 1) } else { None })(vec![1, 2, 3]),
+            Some(vec![0, 1, 2])
+        );
+        assert_eq!(
+            each.run(|a: usize| if a > 1 { Some(a - 1) } else { None })(vec![1, 2, 3]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_compose() {
+        type Shapes = Vec<(String, Shape)>;
+        let radii = Traversal::<Shapes, Shapes, (String, Shape), (String, Shape)>::traversed()
+            .then(Lens::snd())
+            .then(circle());
+
+        let shapes = vec![
+            ("a".to_string(), Shape::Circle(1.0)),
+            ("b".to_string(), Shape::Rect(1.0, 2.0)),
+            ("c".to_string(), Shape::Circle(3.0)),
+        ];
+
+        assert_eq!(radii.clone().to_list_of(shapes.clone()), vec![1.0, 3.0]);
+        assert_eq!(
+            radii.over(|r: f64| r + 1.0, shapes),
+            vec![
+                ("a".to_string(), Shape::Circle(2.0)),
+                ("b".to_string(), Shape::Rect(1.0, 2.0)),
+                ("c".to_string(), Shape::Circle(4.0)),
+            ]
+        );
+
+        let nested = Lens::<
+            ((usize, char), char),
+            ((usize, char), char),
+            (usize, char),
+            (usize, char),
+        >::fst()
+        .then(Lens::fst());
+        assert_eq!(nested.clone().view(((1, 'a'), 'b')), 1);
+        assert_eq!(nested.set(2, ((1, 'a'), 'b')), ((2, 'a'), 'b'));
+
+        let lengths =
+            Fold::<Vec<String>, String>::folded().then(Fold::new(|s: String| vec![s.len()]));
+        assert_eq!(
+            lengths.to_list_of(vec!["a".to_string(), "bcd".to_string()]),
+            vec![1, 3]
+        );
+    }
+
+    #[test]
+    fn test_derive_lenses() {
+        let tagged = Tagged("a", vec![1, 2]);
+        assert_eq!(Tagged::_0_lens().view(tagged.clone()), "a");
+        assert_eq!(
+            Tagged::_1_lens()
+                .then(Traversal::traversed())
+                .over(|a: usize| a + 1, tagged),
+            Tagged("a", vec![2, 3])
+        );
+    }
+}
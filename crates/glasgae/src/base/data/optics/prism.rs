@@ -0,0 +1,128 @@
+use crate::{base::data::functor::r#const::Const, prelude::*};
+
+use super::{Over, Preview, ToListOf};
+
+/// A prism focuses on at most one `A` within an `S`,
+/// such as a single variant of an enum,
+/// and can build a `T` from a `B` alone.
+#[derive(Clone)]
+pub struct Prism<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    matching: Function<S, Either<T, A>>,
+    review: Function<B, T>,
+}
+
+/// A [`Prism`] which does not change the type of its target.
+pub type SimplePrism<S, A> = Prism<S, S, A, A>;
+
+impl<S, T, A, B> Prism<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    /// Build a prism from a matcher and a constructor.
+    ///
+    /// The matcher returns [`Right`] with the focus if it is present,
+    /// or [`Left`] with the (retyped) original structure otherwise.
+    pub fn new(matching: impl FunctionT<S, Either<T, A>>, review: impl FunctionT<B, T>) -> Self {
+        Prism {
+            matching: matching.boxed(),
+            review: review.boxed(),
+        }
+    }
+
+    /// Match against the prism, returning either the focus or the retyped structure.
+    pub fn matching(self, s: S) -> Either<T, A> {
+        (self.matching)(s)
+    }
+
+    /// Build a structure from a focus.
+    pub fn review(self, b: B) -> T {
+        (self.review)(b)
+    }
+
+    /// Run the prism in van Laarhoven form,
+    /// lifting an effectful modification of the focus into one of the whole.
+    ///
+    /// ```text
+    /// run(f)(s) == match matching(s) {
+    ///     Left(t) => pure_a(t),
+    ///     Right(a) => f(a).fmap(review),
+    /// }
+    /// ```
+    pub fn run<FB, FT>(self, f: impl FunctionT<A, FB>) -> impl FunctionT<S, FT>
+    where
+        FB: Functor<T, Pointed = B, Mapped = FT>,
+        FT: PureA<Pointed = T>,
+    {
+        let f = f.to_function();
+        move |s: S| {
+            let Prism { matching, review } = self;
+            match matching(s) {
+                Left(t) => PureA::pure_a(t),
+                Right(a) => f(a).fmap(review),
+            }
+        }
+    }
+}
+
+impl<A, B> Prism<Option<A>, Option<B>, A, B>
+where
+    A: Term,
+    B: Term,
+{
+    /// Focus on the contents of a [`Some`].
+    pub fn some() -> Self {
+        Prism::new(
+            |s: Option<A>| match s {
+                Some(a) => Right(a),
+                None => Left(None),
+            },
+            Some,
+        )
+    }
+}
+
+impl<S, T, A, B> Over<S, T, A, B> for Prism<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn over(self, f: impl FunctionT<A, B>, s: S) -> T {
+        let f = f.to_function();
+        self.run(move |a| Identity(f(a)))(s).run()
+    }
+}
+
+impl<S, T, A, B> Preview<S, A> for Prism<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn preview(self, s: S) -> Option<A> {
+        self.to_list_of(s).into_iter().next()
+    }
+}
+
+impl<S, T, A, B> ToListOf<S, A> for Prism<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn to_list_of(self, s: S) -> Vec<A> {
+        self.run(|a| Const::new(vec![a]))(s).get()
+    }
+}
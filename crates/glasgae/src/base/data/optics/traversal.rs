@@ -0,0 +1,136 @@
+use crate::{base::data::functor::r#const::Const, prelude::*};
+
+use super::{Over, Preview, ToListOf};
+
+/// A structure split into its foci, and a function which rebuilds it from their replacements.
+///
+/// # Panics
+///
+/// The rebuilding function expects exactly one replacement for each focus, in the same order,
+/// and may panic if given fewer.
+pub type Parts<A, B, T> = (Vec<A>, Function<Vec<B>, T>);
+
+/// A traversal focuses on zero or more `A`s within an `S`,
+/// each of which can be replaced by a `B` to produce a `T`.
+///
+/// Internally, a traversal splits its structure into the list of its foci,
+/// and a function which rebuilds the structure from a list of replacements
+/// of the same length.
+#[derive(Clone)]
+pub struct Traversal<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    parts: Function<S, Parts<A, B, T>>,
+}
+
+/// A [`Traversal`] which does not change the type of its targets.
+pub type SimpleTraversal<S, A> = Traversal<S, S, A, A>;
+
+impl<S, T, A, B> Traversal<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    /// Build a traversal from a function splitting a structure
+    /// into its foci and a function to rebuild it.
+    ///
+    /// The rebuilding function will always be given
+    /// exactly as many replacements as there were foci.
+    pub fn new(parts: impl FunctionT<S, Parts<A, B, T>>) -> Self {
+        Traversal {
+            parts: parts.boxed(),
+        }
+    }
+
+    /// Split a structure into its foci and a function to rebuild it.
+    ///
+    /// See [`Parts`] for the replacements the rebuilding function expects.
+    pub fn parts(self, s: S) -> Parts<A, B, T> {
+        (self.parts)(s)
+    }
+
+    /// Run the traversal in van Laarhoven form,
+    /// evaluating an action for each focus from left to right
+    /// and rebuilding the structure from the results.
+    pub fn run<FB, FV, FF, FT>(self, f: impl FunctionT<A, FB>) -> impl FunctionT<S, FT>
+    where
+        FB: Pointed<Pointed = B> + WithPointed<Vec<B>, WithPointed = FV>,
+        FV: PureA<Pointed = Vec<B>>
+            + Functor<Function<B, Vec<B>>, Mapped = FF>
+            + Functor<T, Mapped = FT>,
+        FF: Applicative<B, Vec<B>, WithA = FB, WithB = FV>,
+        FT: Pointed<Pointed = T>,
+    {
+        let f = f.to_function();
+        move |s: S| {
+            let (foci, rebuild) = self.parts(s);
+            foci.into_iter()
+                .fold(PureA::pure_a(vec![]), |acc: FV, a| {
+                    acc.fmap(|bs: Vec<B>| -> Function<B, Vec<B>> {
+                        (move |b| {
+                            let mut bs = bs;
+                            bs.push(b);
+                            bs
+                        })
+                        .boxed()
+                    })
+                    .app_a(f.clone()(a))
+                })
+                .fmap(rebuild)
+        }
+    }
+}
+
+impl<A, B> Traversal<Vec<A>, Vec<B>, A, B>
+where
+    A: Term,
+    B: Term,
+{
+    /// Focus on every element of a [`Vec`].
+    pub fn traversed() -> Self {
+        Traversal::new(|s: Vec<A>| (s, identity.boxed()))
+    }
+}
+
+impl<S, T, A, B> Over<S, T, A, B> for Traversal<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn over(self, f: impl FunctionT<A, B>, s: S) -> T {
+        let f = f.to_function();
+        self.run(move |a| Identity(f(a)))(s).run()
+    }
+}
+
+impl<S, T, A, B> Preview<S, A> for Traversal<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn preview(self, s: S) -> Option<A> {
+        self.to_list_of(s).into_iter().next()
+    }
+}
+
+impl<S, T, A, B> ToListOf<S, A> for Traversal<S, T, A, B>
+where
+    S: Term,
+    T: Term,
+    A: Term,
+    B: Term,
+{
+    fn to_list_of(self, s: S) -> Vec<A> {
+        self.run(|a| Const::new(vec![a]))(s).get()
+    }
+}
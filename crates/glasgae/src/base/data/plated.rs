@@ -0,0 +1,399 @@
+//! Generic traversal of self-similar structures.
+//!
+//! A [`Plated`] type knows how to find its immediate children of the same type,
+//! and how to rebuild itself from replacements for them.
+//! From this, whole-structure queries and bottom-up rewrites
+//! can be written once for every recursive type,
+//! in the style of [Uniplate](https://hackage.haskell.org/package/uniplate)
+//! and [Control.Lens.Plated](https://hackage.haskell.org/package/lens/docs/Control-Lens-Plated.html).
+//!
+//! `Plated` can be derived for types whose recursive positions are
+//! `Self`, or `Box`, `Vec` or `Option` thereof.
+//!
+//! ```
+//! # use glasgae::{base::data::plated::Plated, prelude::*};
+//! #[derive(Debug, Clone, PartialEq, Plated)]
+//! enum Expr {
+//!     Lit(i64),
+//!     Neg(Box<Expr>),
+//!     Add(Box<Expr>, Box<Expr>),
+//! }
+//!
+//! use Expr::*;
+//!
+//! // -(1 + -(-2))
+//! let expr = Neg(Box::new(Add(
+//!     Box::new(Lit(1)),
+//!     Box::new(Neg(Box::new(Neg(Box::new(Lit(2)))))),
+//! )));
+//!
+//! // Constant folding, to a fixed point
+//! let folded = expr.clone().rewrite(|e| match e {
+//!     Neg(e) => match *e {
+//!         Lit(n) => Some(Lit(-n)),
+//!         Neg(e) => Some(*e),
+//!         _ => None,
+//!     },
+//!     Add(l, r) => match (*l, *r) {
+//!         (Lit(a), Lit(b)) => Some(Lit(a + b)),
+//!         _ => None,
+//!     },
+//!     _ => None,
+//! });
+//! assert_eq!(folded, Lit(-3));
+//!
+//! let literals = expr
+//!     .universe()
+//!     .into_iter()
+//!     .filter_map(|e| match e {
+//!         Lit(n) => Some(n),
+//!         _ => None,
+//!     })
+//!     .collect::<Vec<_>>();
+//! assert_eq!(literals, vec![1, 2]);
+//! ```
+
+use crate::prelude::*;
+
+use super::optics::{Parts, SimpleTraversal, Traversal};
+
+/// A type whose values contain immediate children of the same type.
+pub trait Plated: Term {
+    /// Split a value into its immediate children,
+    /// and a function which rebuilds it from replacements for each of them.
+    fn plate(self) -> Parts<Self, Self, Self>;
+
+    /// The immediate children of a value.
+    fn children(self) -> Vec<Self> {
+        self.plate().0
+    }
+
+    /// A value, followed by all of its descendants, in pre-order.
+    fn universe(self) -> Vec<Self> {
+        let mut out = vec![];
+        let mut stack = vec![self];
+        while let Some(x) = stack.pop() {
+            out.push(x.clone());
+            stack.extend(x.children().into_iter().rev());
+        }
+        out
+    }
+
+    /// Apply `f` to every descendant of a value, from the leaves up.
+    fn transform(self, f: impl FunctionT<Self, Self>) -> Self {
+        let f = f.to_function();
+        let (children, rebuild) = self.plate();
+        let children = children
+            .into_iter()
+            .map(|child| child.transform(f.clone()))
+            .collect();
+        f(rebuild(children))
+    }
+
+    /// Apply the monadic action `f` to every descendant of a value, from the leaves up.
+    fn transform_m<M, MF, MV>(self, f: impl FunctionT<Self, M>) -> M
+    where
+        M: Functor<Function<Vec<Self>, Vec<Self>>, Pointed = Self, Mapped = MF>
+            + WithPointed<Vec<Self>, WithPointed = MV>,
+        MF: Applicative<Vec<Self>, Vec<Self>, WithA = MV, WithB = MV>,
+        MV: PureA<Pointed = Vec<Self>> + ChainM<Self, Chained = M>,
+    {
+        let f = f.to_function();
+        let (children, rebuild) = self.plate();
+        children
+            .map_m({
+                let f = f.clone();
+                move |child: Self| child.transform_m(f.clone())
+            })
+            .chain_m(move |children| f(rebuild(children)))
+    }
+
+    /// Rewrite a value from the leaves up,
+    /// applying `f` wherever it matches until it no longer does.
+    ///
+    /// The result contains no subterm for which `f` returns `Some`.
+    fn rewrite(self, f: impl FunctionT<Self, Option<Self>>) -> Self {
+        let f = f.to_function();
+        self.transform(move |x: Self| match f.clone()(x.clone()) {
+            Some(y) => y.rewrite(f.clone()),
+            None => x,
+        })
+    }
+
+    /// Monadic variant of [`Plated::rewrite`].
+    fn rewrite_m<M, MF, MV, MO>(self, f: impl FunctionT<Self, MO>) -> M
+    where
+        M: ReturnM
+            + Functor<Function<Vec<Self>, Vec<Self>>, Pointed = Self, Mapped = MF>
+            + WithPointed<Vec<Self>, WithPointed = MV>,
+        MF: Applicative<Vec<Self>, Vec<Self>, WithA = MV, WithB = MV>,
+        MV: PureA<Pointed = Vec<Self>> + ChainM<Self, Chained = M>,
+        MO: ChainM<Self, Pointed = Option<Self>, Chained = M>,
+    {
+        let f = f.to_function();
+        self.transform_m(move |x: Self| {
+            let f = f.clone();
+            f.clone()(x.clone()).chain_m(move |y| match y {
+                Some(y) => y.rewrite_m(f),
+                None => ReturnM::return_m(x),
+            })
+        })
+    }
+
+    /// Fold a value from the leaves up,
+    /// combining each subterm with the results for its children.
+    ///
+    /// Named to avoid clashing with [`Recursive::para`](crate::base::data::fix::Recursive::para)
+    /// on types which implement both traits.
+    fn para_plate<R>(self, f: impl BifunT<Self, Vec<R>, R>) -> R
+    where
+        R: Term,
+    {
+        let f = f.to_bifun();
+        let rs = self
+            .clone()
+            .children()
+            .into_iter()
+            .map(|child| child.para_plate(f.clone()))
+            .collect();
+        f(self, rs)
+    }
+
+    /// Each immediate child of a value,
+    /// paired with a function which replaces it within the value.
+    fn holes(self) -> Vec<(Self, Function<Self, Self>)> {
+        let (children, rebuild) = self.plate();
+        children
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, child)| {
+                let children = children.clone();
+                let rebuild = rebuild.clone();
+                let replace = move |x| {
+                    let mut children = children.clone();
+                    children[i] = x;
+                    rebuild(children)
+                };
+                (child, replace.boxed() as Function<Self, Self>)
+            })
+            .collect()
+    }
+
+    /// Every subterm of a value, in pre-order,
+    /// paired with a function which replaces it within the value.
+    fn contexts(self) -> Vec<(Self, Function<Self, Self>)> {
+        let mut out = vec![(self.clone(), identity.boxed() as Function<Self, Self>)];
+        for (child, outer) in self.holes() {
+            for (x, inner) in child.contexts() {
+                let outer = outer.clone();
+                out.push((
+                    x,
+                    (move |x| outer(inner(x))).boxed() as Function<Self, Self>,
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// [`Plated::plate`] as a [`Traversal`] over the immediate children of a value.
+pub fn plate<T>() -> SimpleTraversal<T, T>
+where
+    T: Plated,
+{
+    Traversal::new(Plated::plate)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        base::data::{
+            optics::{Over, ToListOf},
+            term_rewrite::ZipperTerm,
+            tree::rose_tree::RoseTree,
+        },
+        transformers::state::State,
+    };
+
+    use super::{plate, Plated};
+
+    #[derive(Debug, Clone, PartialEq, crate::macros::Plated)]
+    enum Shapes {
+        Leaf,
+        Node {
+            label: usize,
+            boxed: Box<Shapes>,
+            nested: Vec<Vec<Shapes>>,
+            optional: Option<Box<Shapes>>,
+        },
+    }
+
+    fn tree() -> RoseTree<usize> {
+        RoseTree(
+            1,
+            vec![RoseTree(2, vec![RoseTree(3, vec![])]), RoseTree(4, vec![])],
+        )
+    }
+
+    #[test]
+    fn test_rose_tree() {
+        let labels = tree()
+            .universe()
+            .into_iter()
+            .map(|RoseTree(t, _)| t)
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec![1, 2, 3, 4]);
+
+        // Sum each subtree into its root
+        let summed = tree().transform(|RoseTree(t, ts)| {
+            let sum = t + ts.iter().map(|RoseTree(t, _)| t).sum::<usize>();
+            RoseTree(sum, ts)
+        });
+        assert_eq!(*summed.root_label(), 10);
+
+        let depth =
+            tree().para_plate(|_, depths: Vec<usize>| 1 + depths.into_iter().max().unwrap_or(0));
+        assert_eq!(depth, 3);
+
+        assert_eq!(
+            plate().to_list_of(tree()),
+            vec![RoseTree(2, vec![RoseTree(3, vec![])]), RoseTree(4, vec![])]
+        );
+        assert_eq!(
+            plate().over(|RoseTree(t, ts)| RoseTree(t * 10, ts), tree()),
+            RoseTree(
+                1,
+                vec![
+                    RoseTree(20, vec![RoseTree(3, vec![])]),
+                    RoseTree(40, vec![]),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_derive_shapes() {
+        let node = |label, nested, optional| Shapes::Node {
+            label,
+            boxed: Box::new(Shapes::Leaf),
+            nested,
+            optional,
+        };
+
+        let shapes = node(
+            1,
+            vec![
+                vec![node(2, vec![], None)],
+                vec![],
+                vec![Shapes::Leaf, Shapes::Leaf],
+            ],
+            Some(Box::new(node(3, vec![], None))),
+        );
+
+        let (children, rebuild) = shapes.clone().plate();
+        assert_eq!(children.len(), 5);
+        assert_eq!(rebuild.clone()(children.clone()), shapes);
+
+        // The rebuild can be reused, and keeps the non-recursive fields
+        let relabelled = children
+            .into_iter()
+            .map(|child| match child {
+                Shapes::Node {
+                    label,
+                    boxed,
+                    nested,
+                    optional,
+                } => Shapes::Node {
+                    label: label * 10,
+                    boxed,
+                    nested,
+                    optional,
+                },
+                leaf => leaf,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rebuild(relabelled),
+            node(
+                1,
+                vec![
+                    vec![node(20, vec![], None)],
+                    vec![],
+                    vec![Shapes::Leaf, Shapes::Leaf]
+                ],
+                Some(Box::new(node(30, vec![], None))),
+            )
+        );
+
+        assert_eq!(Shapes::Leaf.plate().1(vec![]), Shapes::Leaf);
+    }
+
+    #[test]
+    fn test_transform_m() {
+        // Number every node in post-order
+        let (numbered, count) = tree()
+            .transform_m(|RoseTree(_, ts): RoseTree<usize>| {
+                State::new(move |n: usize| (RoseTree(n, ts.clone()), n + 1))
+            })
+            .run(0);
+
+        assert_eq!(count, 4);
+        assert_eq!(
+            numbered,
+            RoseTree(
+                3,
+                vec![RoseTree(1, vec![RoseTree(0, vec![])]), RoseTree(2, vec![])],
+            )
+        );
+    }
+
+    #[test]
+    fn test_rewrite_m() {
+        // Collapse chains of identity applications, counting each one
+        let id = ZipperTerm::l("x", ZipperTerm::var("x"));
+        let term = ZipperTerm::a(id.clone(), ZipperTerm::a(id.clone(), ZipperTerm::var("y")));
+
+        let (rewritten, count) = term
+            .rewrite_m(move |t: ZipperTerm| {
+                let id = id.clone();
+                State::new(move |n: usize| match t.clone() {
+                    ZipperTerm::A(l, r) if *l == id => (Some(*r), n + 1),
+                    _ => (None, n),
+                })
+            })
+            .run(0);
+
+        assert_eq!(rewritten, ZipperTerm::var("y"));
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_contexts() {
+        let term = ZipperTerm::a(ZipperTerm::var("f"), ZipperTerm::var("x"));
+
+        let contexts = term.clone().contexts();
+        assert_eq!(contexts.len(), 3);
+
+        let replaced = contexts
+            .into_iter()
+            .map(|(t, replace)| replace(ZipperTerm::a(t.clone(), t)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(replaced[0], ZipperTerm::a(term.clone(), term));
+        assert_eq!(
+            replaced[1],
+            ZipperTerm::a(
+                ZipperTerm::a(ZipperTerm::var("f"), ZipperTerm::var("f")),
+                ZipperTerm::var("x")
+            )
+        );
+        assert_eq!(
+            replaced[2],
+            ZipperTerm::a(
+                ZipperTerm::var("f"),
+                ZipperTerm::a(ZipperTerm::var("x"), ZipperTerm::var("x"))
+            )
+        );
+    }
+}
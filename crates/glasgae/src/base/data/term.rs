@@ -1,4 +1,9 @@
-use std::panic::{UnwindSafe, RefUnwindSafe};
+use std::{
+    any::Any,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::prelude::Boxed;
 
 /// [`Term`] without its [`Sized`] and [`Clone`] constraints, for object-safety.
 pub trait TermBase: 'static + Send + Sync + UnwindSafe + RefUnwindSafe {}
@@ -7,3 +12,59 @@ impl<T> TermBase for T where T: 'static + Send + Sync + UnwindSafe + RefUnwindSa
 /// A type suitable for use within a functional expression.
 pub trait Term: TermBase + Sized + Clone {}
 impl<T> Term for T where T: TermBase + Clone {}
+
+/// Object-safe [`Term`] whose concrete type has been forgotten.
+pub trait ErasedT: TermBase {
+    fn clone_erased(&self) -> Erased;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T> ErasedT for T
+where
+    T: Term,
+{
+    fn clone_erased(&self) -> Erased {
+        self.clone().boxed()
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// A [`Term`] with its concrete type erased.
+///
+/// Recursive types whose shape is given by a projection
+/// (such as a [`Free`](crate::base::control::monad::free::Free) monad's
+/// `WithPointedT<F, Free<F, A>>` layer) cannot name that projection in their definition
+/// without making their own [`Clone`] implementation depend on itself.
+///
+/// Storing the layer as an [`Erased`] breaks the cycle;
+/// the owning type is responsible for recovering the original type with [`Erased::downcast`].
+pub type Erased = Box<dyn ErasedT>;
+
+impl Clone for Erased {
+    fn clone(&self) -> Self {
+        (**self).clone_erased()
+    }
+}
+
+/// Recover the concrete type of an [`Erased`].
+pub trait Downcast {
+    /// Panics if `T` is not the type originally erased.
+    fn downcast<T>(self) -> T
+    where
+        T: Term;
+}
+
+impl Downcast for Erased {
+    fn downcast<T>(self) -> T
+    where
+        T: Term,
+    {
+        *self
+            .into_any()
+            .downcast::<T>()
+            .unwrap_or_else(|_| panic!("Erased: Unexpected type"))
+    }
+}
@@ -0,0 +1,499 @@
+//! Untyped lambda calculus terms, rewritten via the continuation-based zipper.
+//!
+//! [`ZipperTerm`] supports capture-avoiding substitution, alpha-equivalence
+//! and free-variable analysis, along with normal-order and call-by-value
+//! reduction driven by [`Direction`] moves over a [`Zipper`](crate::base::control::zipper::Zipper).
+//!
+//! ```
+//! # use glasgae::base::data::term_rewrite::{Strategy, ZipperTerm};
+//! // (Lx. x) y
+//! let term = ZipperTerm::a(
+//!     ZipperTerm::l("x", ZipperTerm::var("x")),
+//!     ZipperTerm::var("y"),
+//! );
+//!
+//! assert_eq!(term.reduce(Strategy::NormalOrder), ZipperTerm::var("y"));
+//! ```
+
+mod reduce;
+mod subst;
+
+pub use reduce::*;
+
+use crate::{base::control::zipper::Travel, prelude::*};
+
+/// An untyped lambda calculus term.
+///
+/// Terms can be built bottom-up with [`var`](ZipperTerm::var), [`l`](ZipperTerm::l)
+/// and [`a`](ZipperTerm::a), or top-down by starting from a term with [`Free`](ZipperTerm::Free) holes
+/// and filling them in with [`left`](ZipperTerm::left), [`right`](ZipperTerm::right)
+/// and [`next`](ZipperTerm::next).
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Recursive, Plated)]
+pub enum ZipperTerm {
+    /// A variable.
+    Var(String),
+    /// A lambda abstraction, binding a variable in its body.
+    L(String, Box<Self>),
+    /// An application of one term to another.
+    A(Box<Self>, Box<Self>),
+    /// A hole, standing in for a subterm that has yet to be filled in.
+    Free,
+}
+
+impl Pointed for ZipperTerm {
+    type Pointed = String;
+}
+
+impl WithPointed<String> for ZipperTerm {
+    type WithPointed = ZipperTerm;
+}
+
+impl Functor<String> for ZipperTerm {
+    type Mapped = ZipperTerm;
+
+    fn fmap(self, f: impl FunctionT<String, String>) -> ZipperTerm {
+        match self {
+            ZipperTerm::Var(t) => ZipperTerm::var(f(t)),
+            ZipperTerm::L(s, n) => ZipperTerm::l(f.to_function()(s), n.fmap(f)),
+            ZipperTerm::A(l, r) => ZipperTerm::a(l.fmap(f.to_function()), r.fmap(f)),
+            ZipperTerm::Free => ZipperTerm::Free,
+        }
+    }
+}
+
+impl std::fmt::Debug for ZipperTerm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ZipperTerm::Var(s) => f.write_str(s),
+            ZipperTerm::A(l, r) => f.write_fmt(format_args!("({l:?} {r:?})")),
+            ZipperTerm::L(t, n) => f.write_fmt(format_args!("L{t}. {n:?}")),
+            ZipperTerm::Free => f.write_str("()"),
+        }
+    }
+}
+
+impl ZipperTerm {
+    /// Build a variable.
+    pub fn var(t: impl ToString) -> Self {
+        ZipperTerm::Var(t.to_string())
+    }
+
+    /// Build an application of `lhs` to `rhs`.
+    pub fn a(lhs: Self, rhs: Self) -> Self {
+        ZipperTerm::A(lhs.boxed(), rhs.boxed())
+    }
+
+    /// Build a lambda abstraction binding `t` in `n`.
+    pub fn l(t: impl ToString, n: Self) -> Self {
+        ZipperTerm::L(t.to_string(), n.boxed())
+    }
+
+    /// Build an application whose function and argument are both holes.
+    pub fn a_free() -> Self {
+        Self::a(ZipperTerm::Free, ZipperTerm::Free)
+    }
+
+    /// Build a lambda abstraction binding `t` in a hole.
+    pub fn l_free(t: impl ToString) -> Self {
+        Self::l(t, ZipperTerm::Free)
+    }
+
+    /// Replace the function of an application with `t`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is not an application.
+    pub fn left(self, t: Self) -> Self {
+        let ZipperTerm::A(_, r) = self else {
+            panic!("Term is not an A")
+        };
+        ZipperTerm::a(t, *r)
+    }
+
+    /// Replace the argument of an application with `t`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is not an application.
+    pub fn right(self, t: Self) -> Self {
+        let ZipperTerm::A(l, _) = self else {
+            panic!("Term is not an A")
+        };
+        ZipperTerm::a(*l, t)
+    }
+
+    /// Replace the body of a lambda abstraction with `t`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is not a lambda abstraction.
+    pub fn next(self, t: Self) -> Self {
+        let ZipperTerm::L(s, _) = self else {
+            panic!("Term is not an L");
+        };
+        ZipperTerm::l(s, t)
+    }
+}
+
+impl<U> Foldable<U> for ZipperTerm
+where
+    U: Term,
+{
+    fn foldr(self, f: impl BifunT<String, U, U>, init: U) -> U {
+        let f = f.to_bifun();
+        match self {
+            ZipperTerm::Var(t) => f(t, init),
+            ZipperTerm::L(l, r) => f.clone()(l, r.foldr(f, init)),
+            ZipperTerm::A(l, r) => l.foldr(f.clone(), r.foldr(f, init)),
+            ZipperTerm::Free => init,
+        }
+    }
+
+    fn foldl(self, f: impl BifunT<U, String, U>, init: U) -> U {
+        let f = f.to_bifun();
+        match self {
+            ZipperTerm::Var(t) => f(init, t),
+            ZipperTerm::L(l, r) => {
+                let init = f.clone()(init, l);
+                r.foldl(f, init)
+            }
+            ZipperTerm::A(l, r) => {
+                let init = l.foldl(f.clone(), init);
+                r.foldl(f, init)
+            }
+            ZipperTerm::Free => init,
+        }
+    }
+}
+
+impl Foldable1<String> for ZipperTerm {
+    fn foldr1(self, f: impl BifunT<String, String, String>) -> String {
+        foldr1_default(self, f)
+    }
+
+    fn foldl1(self, f: impl BifunT<String, String, String>) -> String {
+        foldl1_default(self, f)
+    }
+}
+
+/// Where to go next when [travelling](Travel) over a [`ZipperTerm`] in depth-first order.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    /// Skip the children of the current term.
+    #[default]
+    Up,
+    /// Visit every child of the current term.
+    Next,
+    /// Visit only the function of an application.
+    DownLeft,
+    /// Visit only the argument of an application.
+    DownRight,
+}
+
+impl<M, N> Travel<Direction, M, N> for ZipperTerm
+where
+    M: Monad<ZipperTerm, Pointed = (Option<ZipperTerm>, Direction), Chained = N>,
+    N: Monad<ZipperTerm, Pointed = ZipperTerm, Chained = N> + ReturnM<Pointed = ZipperTerm>,
+{
+    fn travel(self, tf: impl FunctionT<Self, M>) -> N {
+        let tf = tf.to_function();
+
+        tf.clone()(self.clone()).chain_m(|(term_, dir)| {
+            let t = term_.unwrap_or(self);
+
+            match (dir, t) {
+                (Direction::Up, t) | (_, t @ (ZipperTerm::Var(_) | ZipperTerm::Free)) => {
+                    ReturnM::return_m(t)
+                }
+                (_, ZipperTerm::L(v, t1)) => Travel::<Direction, M, N>::travel(*t1, tf)
+                    .chain_m(|t1| ReturnM::return_m(ZipperTerm::l(v, t1))),
+                (Direction::Next, ZipperTerm::A(l, r)) => {
+                    Travel::<Direction, M, N>::travel(*l, tf.clone()).chain_m(|l| {
+                        Travel::<Direction, M, N>::travel(*r, tf)
+                            .chain_m(|r| ReturnM::return_m(ZipperTerm::a(l, r)))
+                    })
+                }
+                (Direction::DownLeft, ZipperTerm::A(l, r)) => {
+                    Travel::<Direction, M, N>::travel(*l, tf)
+                        .chain_m(|l| ReturnM::return_m(ZipperTerm::a(l, *r)))
+                }
+                (Direction::DownRight, ZipperTerm::A(l, r)) => {
+                    Travel::<Direction, M, N>::travel(*r, tf)
+                        .chain_m(|r| ReturnM::return_m(ZipperTerm::a(*l, r)))
+                }
+            }
+        })
+    }
+}
+
+/// Where to go next when [travelling](Travel) over a [`ZipperTerm`],
+/// revisiting each term once a child has been left.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction1 {
+    /// Visit the function of an application, or the body of a lambda abstraction.
+    FirstKid,
+    /// Visit the argument of an application, or the body of a lambda abstraction.
+    RightKid,
+    /// Return to the parent of the current term.
+    Parent,
+}
+
+impl<M, N> Travel<Direction1, M, N> for ZipperTerm
+where
+    M: Monad<ZipperTerm, Pointed = (Option<ZipperTerm>, Direction1), Chained = N>,
+    N: Monad<ZipperTerm, Pointed = ZipperTerm, Chained = N>,
+{
+    fn travel(self, tf: impl FunctionT<Self, M>) -> N {
+        let tf = tf.to_function();
+
+        tf.clone()(self.clone()).chain_m(|(term_, dir)| {
+            let t = term_.unwrap_or(self);
+
+            match (dir, t) {
+                (Direction1::Parent, t) | (_, t @ (ZipperTerm::Var(_) | ZipperTerm::Free)) => {
+                    ReturnM::return_m(t)
+                }
+                (Direction1::FirstKid | Direction1::RightKid, ZipperTerm::L(v, t1)) => t1
+                    .travel(tf.clone())
+                    .chain_m(|t1| ZipperTerm::l(v, t1).travel(tf)),
+                (Direction1::RightKid, ZipperTerm::A(l, r)) => r
+                    .travel(tf.clone())
+                    .chain_m(|r| ZipperTerm::a(*l, r).travel(tf)),
+                (Direction1::FirstKid, ZipperTerm::A(l, r)) => l
+                    .travel(tf.clone())
+                    .chain_m(|l| ZipperTerm::a(l, *r).travel(tf)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        base::{
+            control::zipper::{ZipAllTheWay, ZipMove, ZipTravel, Zipper},
+            data::functor::identity::Identity,
+        },
+        transformers::{cont::Cont, state::State},
+    };
+
+    use super::*;
+
+    fn term() -> ZipperTerm {
+        let x = ZipperTerm::var("x");
+        let f = ZipperTerm::var("f");
+
+        ZipperTerm::l_free("f").next(
+            ZipperTerm::l_free("x").next(
+                ZipperTerm::a_free()
+                    .left(
+                        ZipperTerm::a_free().left(f.clone()).right(
+                            ZipperTerm::l_free("f").next(
+                                ZipperTerm::a_free().left(f.clone()).right(
+                                    ZipperTerm::l_free("f")
+                                        .next(ZipperTerm::l_free("x").next(x.clone())),
+                                ),
+                            ),
+                        ),
+                    )
+                    .right(
+                        ZipperTerm::a_free()
+                            .left(
+                                ZipperTerm::a_free().left(f.clone()).right(
+                                    ZipperTerm::l_free("f")
+                                        .next(ZipperTerm::l_free("x").next(x.clone())),
+                                ),
+                            )
+                            .right(x.clone()),
+                    ),
+            ),
+        )
+    }
+
+    /// `term` with every `Lx. x` replaced by `Ly. y`.
+    fn term_renamed() -> ZipperTerm {
+        let x = ZipperTerm::var("x");
+        let f = ZipperTerm::var("f");
+        let id = ZipperTerm::l("y", ZipperTerm::var("y"));
+
+        ZipperTerm::l(
+            "f",
+            ZipperTerm::l(
+                "x",
+                ZipperTerm::a(
+                    ZipperTerm::a(
+                        f.clone(),
+                        ZipperTerm::l(
+                            "f",
+                            ZipperTerm::a(f.clone(), ZipperTerm::l("f", id.clone())),
+                        ),
+                    ),
+                    ZipperTerm::a(ZipperTerm::a(f, ZipperTerm::l("f", id)), x),
+                ),
+            ),
+        )
+    }
+
+    #[test]
+    fn test_travel() {
+        let term = term()
+            .travel(|_| Identity::return_m((None, Direction::Next)))
+            .run();
+
+        assert_eq!(term, self::term());
+    }
+
+    #[test]
+    fn test_travel_state() {
+        // Count every subterm visited on the way through.
+        let travel: State<usize, ZipperTerm> = term().travel(|_| {
+            State::<_, ()>::modify(|n: usize| n + 1)
+                .then_m(State::return_m((None, Direction::Next)))
+        });
+
+        assert_eq!(travel.run(0), (term(), 18));
+    }
+
+    #[test]
+    fn test_travel_cut() {
+        // Stop descending at the first application of `f`.
+        let travel: State<Vec<ZipperTerm>, ZipperTerm> = term().travel(|term| {
+            let cut = matches!(
+                &term,
+                ZipperTerm::A(l, _) if matches!(&**l, ZipperTerm::Var(s) if s == "f")
+            );
+            State::<_, ()>::modify(move |mut visited: Vec<ZipperTerm>| {
+                visited.push(term.clone());
+                visited
+            })
+            .then_m(State::return_m((
+                None,
+                if cut {
+                    Direction::Up
+                } else {
+                    Direction::DownLeft
+                },
+            )))
+        });
+
+        let (done, visited) = travel.run(vec![]);
+        assert_eq!(done, term());
+        assert_eq!(
+            visited.iter().map(|t| format!("{t:?}")).collect::<Vec<_>>(),
+            vec![
+                "Lf. Lx. ((f Lf. (f Lf. Lx. x)) ((f Lf. Lx. x) x))",
+                "Lx. ((f Lf. (f Lf. Lx. x)) ((f Lf. Lx. x) x))",
+                "((f Lf. (f Lf. Lx. x)) ((f Lf. Lx. x) x))",
+                "(f Lf. (f Lf. Lx. x))",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_travel_replace() {
+        let term = term()
+            .travel(|t| {
+                Identity::return_m(match t {
+                    ZipperTerm::L(t, n)
+                        if t == "x" && matches!(&*n, ZipperTerm::Var(s) if s == "x") =>
+                    {
+                        (
+                            Some(ZipperTerm::l("y", ZipperTerm::var("y"))),
+                            Direction::Next,
+                        )
+                    }
+                    _ => (None, Direction::Next),
+                })
+            })
+            .run();
+
+        assert_eq!(term, term_renamed());
+    }
+
+    #[test]
+    fn test_zip_all_the_way() {
+        let term: Cont<Zipper<ZipperTerm, Direction>> = term()
+            .zip_travel()
+            .zip_all_the_way(Direction::Next, |_| None);
+
+        assert_eq!(term.eval().unwrap(), self::term());
+
+        let renamed: Cont<Zipper<ZipperTerm, Direction>> = self::term()
+            .zip_travel()
+            .zip_all_the_way(Direction::Next, |t| match t {
+                ZipperTerm::L(t, n) if t == "x" && *n == ZipperTerm::var("x") => {
+                    Some(ZipperTerm::l("y", ZipperTerm::var("y")))
+                }
+                _ => None,
+            });
+
+        assert_eq!(renamed.eval().unwrap(), term_renamed());
+    }
+
+    #[test]
+    fn test_zip_move() {
+        let term: Cont<Zipper<ZipperTerm, Direction>> = term()
+            .zip_travel()
+            .zip_move(Direction::Next)
+            .zip_move(Direction::Next)
+            .zip_move(Direction::Next)
+            .zip_move(Direction::DownRight);
+
+        let term = term.fmap(
+            Functor::replace
+                .flip_clone()
+                .curry_clone(ZipperTerm::a(ZipperTerm::var("x"), ZipperTerm::var("x"))),
+        );
+
+        let term: Cont<Zipper<ZipperTerm, Direction>> =
+            term.zip_all_the_way(Direction::Up, |_| None);
+
+        assert_eq!(
+            format!("{:?}", term.eval_t().run().unwrap()),
+            "Lf. Lx. ((f (x x)) ((f Lf. Lx. x) x))"
+        );
+    }
+
+    #[test]
+    fn test_zip_move_parent() {
+        let moves = |n: usize| {
+            let moves = [
+                Direction1::FirstKid,
+                Direction1::FirstKid,
+                Direction1::Parent,
+                Direction1::RightKid,
+                Direction1::Parent,
+                Direction1::Parent,
+                Direction1::Parent,
+            ];
+            let term: Cont<Zipper<ZipperTerm, Direction1>> = moves[..n].iter().fold(
+                ZipperTerm::l(
+                    "x",
+                    ZipperTerm::a(ZipperTerm::var("a"), ZipperTerm::var("b")),
+                )
+                .zip_travel(),
+                |term, dir| term.zip_move(*dir),
+            );
+            term.eval_t().run()
+        };
+
+        // Returning to a parent revisits it.
+        let foci = (0..7)
+            .map(|n| format!("{:?}", moves(n).focus().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            foci,
+            vec![
+                "Lx. (a b)",
+                "(a b)",
+                "a",
+                "(a b)",
+                "b",
+                "(a b)",
+                "Lx. (a b)"
+            ]
+        );
+
+        assert_eq!(format!("{:?}", moves(7).unwrap()), "Lx. (a b)");
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    base::control::zipper::{ZipTravel, Zipper},
+    logger::MonadLogger,
+    prelude::*,
+};
+
+use super::{Direction, ZipperTerm};
+
+/// The order in which redexes are chosen for contraction.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Strategy {
+    /// Contract the leftmost-outermost redex, reducing under binders.
+    ///
+    /// Finds a normal form whenever one exists.
+    #[default]
+    NormalOrder,
+    /// Contract the leftmost-innermost redex whose argument is a value,
+    /// without reducing under binders.
+    CallByValue,
+}
+
+impl Strategy {
+    /// Returns the contractum of `t` if it is a redex under this strategy.
+    fn contract(self, t: &ZipperTerm) -> Option<ZipperTerm> {
+        let ZipperTerm::A(l, r) = t else {
+            return None;
+        };
+
+        let ZipperTerm::L(x, n) = &**l else {
+            return None;
+        };
+
+        match self {
+            Strategy::CallByValue if !r.is_value() => None,
+            _ => Some((**n).clone().subst(x, r)),
+        }
+    }
+
+    /// The direction in which to leave a term that is not a redex.
+    fn skip(self, t: &ZipperTerm) -> Direction {
+        match (self, t) {
+            (Strategy::CallByValue, ZipperTerm::L(..)) => Direction::Up,
+            _ => Direction::Next,
+        }
+    }
+}
+
+impl ZipperTerm {
+    /// Returns `true` if the term is a variable or an abstraction.
+    pub fn is_value(&self) -> bool {
+        matches!(self, ZipperTerm::Var(_) | ZipperTerm::L(..))
+    }
+
+    /// Contract a single redex chosen by `strategy`,
+    /// returning the redex, its contractum, and the rewritten term.
+    fn step_redex(self, strategy: Strategy) -> Option<(ZipperTerm, ZipperTerm, ZipperTerm)> {
+        let mut zipper: Zipper<ZipperTerm, Direction> = self.zip_travel().eval();
+
+        loop {
+            let Zipper::Zipper(t, k) = zipper else {
+                return None;
+            };
+
+            zipper = match strategy.contract(&t) {
+                Some(contractum) => {
+                    let term = k((Some(contractum.clone()), Direction::Up)).rebuild();
+                    return Some((t, contractum, term));
+                }
+                None => {
+                    let dir = strategy.skip(&t);
+                    k((None, dir))
+                }
+            };
+        }
+    }
+
+    /// Contract a single redex chosen by `strategy`,
+    /// or return `None` if the term is already normal.
+    pub fn step(self, strategy: Strategy) -> Option<ZipperTerm> {
+        self.step_redex(strategy).map(|(_, _, term)| term)
+    }
+
+    /// Contract redexes chosen by `strategy` until none remain.
+    ///
+    /// Does not terminate if the term has no normal form under `strategy`.
+    pub fn reduce(self, strategy: Strategy) -> ZipperTerm {
+        let mut term = self;
+        while let Some(next) = term.clone().step(strategy) {
+            term = next;
+        }
+        term
+    }
+
+    /// As [`ZipperTerm::reduce`], logging each contracted redex at `level`.
+    pub fn reduce_traced<LVL, ML, M>(self, strategy: Strategy, level: LVL) -> M
+    where
+        LVL: Term,
+        ML: MonadLogger<LVL, String> + Monad<ZipperTerm, Pointed = (), Chained = M>,
+        M: ReturnM<Pointed = ZipperTerm>,
+    {
+        match self.clone().step_redex(strategy) {
+            Some((redex, contractum, term)) => {
+                ML::log(level.clone(), format!("{redex:?} => {contractum:?}"))
+                    .chain_m(move |_| term.reduce_traced::<LVL, ML, M>(strategy, level))
+            }
+            None => ReturnM::return_m(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use log::Level;
+
+    use crate::{logger::LoggingT, prelude::*};
+
+    use super::{Strategy, ZipperTerm};
+
+    fn var(x: &str) -> ZipperTerm {
+        ZipperTerm::var(x)
+    }
+
+    fn l(x: &str, n: ZipperTerm) -> ZipperTerm {
+        ZipperTerm::l(x, n)
+    }
+
+    fn a(lhs: ZipperTerm, rhs: ZipperTerm) -> ZipperTerm {
+        ZipperTerm::a(lhs, rhs)
+    }
+
+    fn omega() -> ZipperTerm {
+        let w = l("x", a(var("x"), var("x")));
+        a(w.clone(), w)
+    }
+
+    #[test]
+    fn test_normal_order() {
+        // K y Ω only has a normal form under normal order
+        let k = l("x", l("y", var("x")));
+        let term = a(a(k, var("z")), omega());
+        assert_eq!(term.reduce(Strategy::NormalOrder), var("z"));
+
+        // Reduces under binders
+        let term = l("x", a(l("y", var("y")), var("x")));
+        assert_eq!(term.reduce(Strategy::NormalOrder), l("x", var("x")));
+    }
+
+    #[test]
+    fn test_call_by_value() {
+        // Arguments are reduced before the application
+        let id = l("x", var("x"));
+        let term = a(l("y", a(var("y"), var("y"))), a(id.clone(), var("z")));
+        assert_eq!(
+            term.clone().step(Strategy::CallByValue),
+            Some(a(l("y", a(var("y"), var("y"))), var("z")))
+        );
+        assert_eq!(
+            term.clone().step(Strategy::NormalOrder),
+            Some(a(a(id.clone(), var("z")), a(id, var("z"))))
+        );
+        assert_eq!(term.reduce(Strategy::CallByValue), a(var("z"), var("z")));
+
+        // Abstractions are values, so their bodies are left alone
+        let term = l("x", a(l("y", var("y")), var("x")));
+        assert_eq!(term.clone().step(Strategy::CallByValue), None);
+        assert_eq!(term.clone().reduce(Strategy::CallByValue), term);
+    }
+
+    #[test]
+    fn test_capture_avoiding() {
+        // (Lx. Ly. x) y => Ly'. y
+        let term = a(l("x", l("y", var("x"))), var("y"));
+        let reduced = term.reduce(Strategy::NormalOrder);
+        assert_eq!(reduced, l("y'", var("y")));
+        assert!(reduced.alpha_eq(&l("z", var("y"))));
+    }
+
+    #[test]
+    fn test_reduce_traced() {
+        let trace = Arc::new(Mutex::new(vec![]));
+
+        let id = l("x", var("x"));
+        let term = a(a(id.clone(), id.clone()), var("z"));
+
+        let logger = {
+            let trace = trace.clone();
+            move |_: Level, message: String| {
+                let trace = trace.clone();
+                IO::new(move || trace.lock().unwrap().push(message))
+            }
+        };
+
+        let reduced = unsafe {
+            term.reduce_traced::<_, LoggingT<Level, String, IO<()>>, _>(
+                Strategy::NormalOrder,
+                Level::Debug,
+            )
+            .run_t(logger)
+            .run()
+        };
+
+        assert_eq!(reduced, var("z"));
+        assert_eq!(
+            *trace.lock().unwrap(),
+            vec![
+                "(Lx. x Lx. x) => Lx. x".to_string(),
+                "(Lx. x z) => z".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeSet;
+
+use super::ZipperTerm;
+
+impl ZipperTerm {
+    /// Returns the set of variables which occur unbound in this term.
+    pub fn free_vars(&self) -> BTreeSet<String> {
+        match self {
+            ZipperTerm::Var(x) => BTreeSet::from([x.clone()]),
+            ZipperTerm::L(x, n) => {
+                let mut vars = n.free_vars();
+                vars.remove(x);
+                vars
+            }
+            ZipperTerm::A(l, r) => {
+                let mut vars = l.free_vars();
+                vars.extend(r.free_vars());
+                vars
+            }
+            ZipperTerm::Free => BTreeSet::new(),
+        }
+    }
+
+    /// Substitute `s` for every free occurrence of `x`.
+    ///
+    /// Binders which would capture a free variable of `s`
+    /// are renamed to a fresh variable beforehand.
+    pub fn subst(self, x: &str, s: &ZipperTerm) -> ZipperTerm {
+        match self {
+            ZipperTerm::Var(y) if y == x => s.clone(),
+            ZipperTerm::L(y, n) if y == x => ZipperTerm::L(y, n),
+            ZipperTerm::L(y, n) => {
+                let s_vars = s.free_vars();
+                if s_vars.contains(&y) && n.free_vars().contains(x) {
+                    let mut avoid = s_vars;
+                    avoid.extend(n.free_vars());
+                    avoid.insert(x.to_string());
+
+                    let z = fresh(&y, &avoid);
+                    let n = n.subst(&y, &ZipperTerm::var(&z));
+                    ZipperTerm::l(z, n.subst(x, s))
+                } else {
+                    ZipperTerm::l(y, n.subst(x, s))
+                }
+            }
+            ZipperTerm::A(l, r) => ZipperTerm::a(l.subst(x, s), r.subst(x, s)),
+            t => t,
+        }
+    }
+
+    /// Returns `true` if both terms are equal up to the renaming of bound variables.
+    pub fn alpha_eq(&self, other: &ZipperTerm) -> bool {
+        alpha_eq_in(self, other, &mut vec![])
+    }
+}
+
+/// Prime `x` until it no longer clashes with any variable in `avoid`.
+fn fresh(x: &str, avoid: &BTreeSet<String>) -> String {
+    let mut x = x.to_string();
+    while avoid.contains(&x) {
+        x.push('\'');
+    }
+    x
+}
+
+fn alpha_eq_in<'a>(
+    lhs: &'a ZipperTerm,
+    rhs: &'a ZipperTerm,
+    env: &mut Vec<(&'a str, &'a str)>,
+) -> bool {
+    match (lhs, rhs) {
+        (ZipperTerm::Var(x), ZipperTerm::Var(y)) => {
+            // The innermost binder of either variable decides whether they correspond
+            match env.iter().rev().find(|(l, r)| l == x || r == y) {
+                Some((l, r)) => l == x && r == y,
+                None => x == y,
+            }
+        }
+        (ZipperTerm::L(x, m), ZipperTerm::L(y, n)) => {
+            env.push((x, y));
+            let eq = alpha_eq_in(m, n, env);
+            env.pop();
+            eq
+        }
+        (ZipperTerm::A(l1, r1), ZipperTerm::A(l2, r2)) => {
+            alpha_eq_in(l1, l2, env) && alpha_eq_in(r1, r2, env)
+        }
+        (ZipperTerm::Free, ZipperTerm::Free) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::ZipperTerm;
+
+    fn var(x: &str) -> ZipperTerm {
+        ZipperTerm::var(x)
+    }
+
+    fn l(x: &str, n: ZipperTerm) -> ZipperTerm {
+        ZipperTerm::l(x, n)
+    }
+
+    fn a(lhs: ZipperTerm, rhs: ZipperTerm) -> ZipperTerm {
+        ZipperTerm::a(lhs, rhs)
+    }
+
+    #[test]
+    fn test_free_vars() {
+        let term = l("x", a(a(var("x"), var("y")), l("y", a(var("y"), var("z")))));
+        assert_eq!(
+            term.free_vars(),
+            BTreeSet::from(["y".to_string(), "z".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_subst() {
+        // Shadowed occurrences are left alone
+        let term = a(var("x"), l("x", var("x")));
+        assert_eq!(term.subst("x", &var("z")), a(var("z"), l("x", var("x"))));
+
+        // Binders which would capture the substituted term are renamed
+        let term = l("y", a(var("x"), var("y")));
+        let subst = term.subst("x", &var("y"));
+        assert_eq!(subst, l("y'", a(var("y"), var("y'"))));
+        assert!(!subst.alpha_eq(&l("y", a(var("y"), var("y")))));
+    }
+
+    #[test]
+    fn test_alpha_eq() {
+        assert!(l("x", var("x")).alpha_eq(&l("y", var("y"))));
+        assert!(!l("x", var("y")).alpha_eq(&l("y", var("y"))));
+        assert!(
+            l("x", l("y", a(var("x"), var("y")))).alpha_eq(&l("y", l("x", a(var("y"), var("x")))))
+        );
+        assert!(!l("x", l("y", var("x"))).alpha_eq(&l("x", l("y", var("y")))));
+        assert!(var("z").alpha_eq(&var("z")));
+    }
+}
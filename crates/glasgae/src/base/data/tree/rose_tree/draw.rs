@@ -0,0 +1,92 @@
+use crate::prelude::*;
+
+use super::RoseTree;
+
+impl<T> RoseTree<T>
+where
+    T: Term + Show,
+{
+    /// Render the tree as a 2-dimensional ASCII drawing.
+    ///
+    /// ```
+    /// # use glasgae::base::data::tree::rose_tree::RoseTree;
+    /// let tree = RoseTree(1, vec![RoseTree(2, vec![RoseTree(3, vec![])]), RoseTree(4, vec![])]);
+    /// assert_eq!(
+    ///     tree.draw_tree(),
+    ///     "1\n|\n+- 2\n|  |\n|  `- 3\n|\n`- 4\n"
+    /// );
+    /// ```
+    pub fn draw_tree(self) -> String {
+        self.draw().into_iter().map(|line| line + "\n").collect()
+    }
+
+    fn draw(self) -> Vec<String> {
+        let RoseTree(t, ts) = self;
+        let mut lines: Vec<String> = t.show().lines().map(ToString::to_string).collect();
+
+        let count = ts.len();
+        for (i, t) in ts.into_iter().enumerate() {
+            let (first, other) = if i + 1 == count {
+                ("`- ", "   ")
+            } else {
+                ("+- ", "|  ")
+            };
+
+            lines.push("|".to_string());
+            lines.extend(t.draw().into_iter().enumerate().map(|(j, line)| {
+                let prefix = if j == 0 { first } else { other };
+                prefix.to_string() + &line
+            }));
+        }
+
+        lines
+    }
+}
+
+/// Render a forest as a sequence of 2-dimensional ASCII drawings,
+/// separated by blank lines.
+pub fn draw_forest<T>(ts: Vec<RoseTree<T>>) -> String
+where
+    T: Term + Show,
+{
+    ts.into_iter().map(|t| t.draw_tree() + "\n").collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{draw_forest, RoseTree};
+
+    #[test]
+    fn test_draw_tree() {
+        let tree = RoseTree(
+            "root",
+            vec![
+                RoseTree("a", vec![RoseTree("a1", vec![]), RoseTree("a2", vec![])]),
+                RoseTree("b", vec![RoseTree("b1", vec![])]),
+            ],
+        );
+
+        assert_eq!(
+            tree.draw_tree(),
+            "\
+root
+|
++- a
+|  |
+|  +- a1
+|  |
+|  `- a2
+|
+`- b
+   |
+   `- b1
+"
+        );
+    }
+
+    #[test]
+    fn test_draw_forest() {
+        let forest = vec![RoseTree(1, vec![RoseTree(2, vec![])]), RoseTree(3, vec![])];
+        assert_eq!(draw_forest(forest), "1\n|\n`- 2\n\n3\n\n");
+    }
+}
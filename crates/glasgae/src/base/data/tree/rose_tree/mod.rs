@@ -1,10 +1,93 @@
+//! Multi-way trees, also known as rose trees.
+//!
+//! Based on [Data.Tree](https://hackage.haskell.org/package/containers/docs/Data-Tree.html).
+
+mod draw;
+mod unfold;
+mod zipper;
+
+pub use draw::*;
+pub use zipper::*;
+
 use std::fmt::Debug;
 
 use crate::{derive_pointed, derive_with_pointed, prelude::*};
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A non-empty tree, made of a label and a forest of subtrees.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Recursive, Plated)]
 pub struct RoseTree<T>(pub T, pub Vec<Self>);
 
+impl<T> RoseTree<T>
+where
+    T: Term,
+{
+    /// Returns the label of the root node.
+    pub fn root_label(&self) -> &T {
+        &self.0
+    }
+
+    /// Returns the subtrees of the root node.
+    pub fn sub_forest(&self) -> &[RoseTree<T>] {
+        &self.1
+    }
+
+    /// Returns the elements of the tree in pre-order.
+    ///
+    /// ```
+    /// # use glasgae::base::data::tree::rose_tree::RoseTree;
+    /// let tree = RoseTree(1, vec![RoseTree(2, vec![RoseTree(3, vec![])]), RoseTree(4, vec![])]);
+    /// assert_eq!(tree.flatten(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn flatten(self) -> Vec<T> {
+        let mut out = vec![];
+        let mut stack = vec![self];
+        while let Some(RoseTree(t, ts)) = stack.pop() {
+            out.push(t);
+            stack.extend(ts.into_iter().rev());
+        }
+        out
+    }
+
+    /// Returns the list of elements at each level of the tree.
+    ///
+    /// ```
+    /// # use glasgae::base::data::tree::rose_tree::RoseTree;
+    /// let tree = RoseTree(1, vec![RoseTree(2, vec![RoseTree(3, vec![])]), RoseTree(4, vec![])]);
+    /// assert_eq!(tree.levels(), vec![vec![1], vec![2, 4], vec![3]]);
+    /// ```
+    pub fn levels(self) -> Vec<Vec<T>> {
+        let mut out = vec![];
+        let mut level = vec![self];
+        while !level.is_empty() {
+            let (ts, next): (Vec<_>, Vec<_>) =
+                level.into_iter().map(|RoseTree(t, ts)| (t, ts)).unzip();
+            out.push(ts);
+            level = next.into_iter().flatten().collect();
+        }
+        out
+    }
+
+    /// Catamorphism on trees.
+    ///
+    /// `f` combines the label of each node with the results of folding its subtrees.
+    ///
+    /// ```
+    /// # use glasgae::base::data::tree::rose_tree::RoseTree;
+    /// let tree = RoseTree(1, vec![RoseTree(2, vec![RoseTree(3, vec![])]), RoseTree(4, vec![])]);
+    /// let depth = tree.fold_tree(|_, depths: Vec<usize>| 1 + depths.into_iter().max().unwrap_or(0));
+    /// assert_eq!(depth, 3);
+    /// ```
+    pub fn fold_tree<B>(self, f: impl BifunT<T, Vec<B>, B>) -> B
+    where
+        B: Term,
+    {
+        let f = f.to_bifun();
+        let RoseTree(t, ts) = self;
+        let bs = ts.into_iter().map(|t| t.fold_tree(f.clone())).collect();
+        f(t, bs)
+    }
+}
+
 impl<T> Show for RoseTree<T>
 where
     T: Debug,
@@ -0,0 +1,182 @@
+use crate::prelude::*;
+
+use super::RoseTree;
+
+impl<T> RoseTree<T>
+where
+    T: Term,
+{
+    /// Build a tree from a seed value.
+    ///
+    /// `f` produces a label and a list of child seeds from each seed.
+    ///
+    /// ```
+    /// # use glasgae::base::data::tree::rose_tree::RoseTree;
+    /// // Binary representations of the naturals below 8
+    /// let tree = RoseTree::unfold_tree(
+    ///     |n: usize| (n, [2 * n, 2 * n + 1].into_iter().filter(|c| *c < 8 && *c != n).collect()),
+    ///     1,
+    /// );
+    /// assert_eq!(tree.flatten(), vec![1, 2, 4, 5, 3, 6, 7]);
+    /// ```
+    pub fn unfold_tree<B>(f: impl FunctionT<B, (T, Vec<B>)>, b: B) -> Self
+    where
+        B: Term,
+    {
+        let f = f.to_function();
+        let (t, bs) = f.clone()(b);
+        RoseTree(t, Self::unfold_forest(f, bs))
+    }
+
+    /// Build a forest from a list of seed values.
+    pub fn unfold_forest<B>(f: impl FunctionT<B, (T, Vec<B>)>, bs: Vec<B>) -> Vec<Self>
+    where
+        B: Term,
+    {
+        let f = f.to_function();
+        bs.into_iter()
+            .map(|b| Self::unfold_tree(f.clone(), b))
+            .collect()
+    }
+
+    /// Monadic tree builder, in depth-first order.
+    pub fn unfold_tree_m<B, M1, M2, MF, MV>(f: impl FunctionT<B, M1>, b: B) -> M2
+    where
+        B: Term,
+        M1: Monad<RoseTree<T>, Pointed = (T, Vec<B>), Chained = M2>,
+        M2: Functor<
+                Function<Vec<RoseTree<T>>, Vec<RoseTree<T>>>,
+                Pointed = RoseTree<T>,
+                Mapped = MF,
+            > + WithPointed<Vec<RoseTree<T>>, WithPointed = MV>,
+        MF: Applicative<Vec<RoseTree<T>>, Vec<RoseTree<T>>, WithA = MV, WithB = MV>,
+        MV: PureA<Pointed = Vec<RoseTree<T>>> + Functor<RoseTree<T>, Mapped = M2>,
+    {
+        let f = f.to_function();
+        f.clone()(b).chain_m(|(t, bs)| Self::unfold_forest_m(f, bs).fmap(|ts| RoseTree(t, ts)))
+    }
+
+    /// Monadic forest builder, in depth-first order.
+    pub fn unfold_forest_m<B, M1, M2, MF, MV>(f: impl FunctionT<B, M1>, bs: Vec<B>) -> MV
+    where
+        B: Term,
+        M1: Monad<RoseTree<T>, Pointed = (T, Vec<B>), Chained = M2>,
+        M2: Functor<
+                Function<Vec<RoseTree<T>>, Vec<RoseTree<T>>>,
+                Pointed = RoseTree<T>,
+                Mapped = MF,
+            > + WithPointed<Vec<RoseTree<T>>, WithPointed = MV>,
+        MF: Applicative<Vec<RoseTree<T>>, Vec<RoseTree<T>>, WithA = MV, WithB = MV>,
+        MV: PureA<Pointed = Vec<RoseTree<T>>> + Functor<RoseTree<T>, Mapped = M2>,
+    {
+        let f = f.to_function();
+        bs.map_m(|b| Self::unfold_tree_m(f, b))
+    }
+
+    /// Monadic tree builder, in breadth-first order.
+    ///
+    /// Each level of seeds is expanded in full before any of their children,
+    /// using the algorithm from
+    /// [Breadth-First Numbering: Lessons from a Small Exercise in Algorithm Design](https://www.cs.tufts.edu/~nr/cs257/archive/chris-okasaki/breadth-first.pdf)
+    /// by Chris Okasaki.
+    pub fn unfold_tree_m_bf<B, M1, MP, MV, M2>(f: impl FunctionT<B, M1>, b: B) -> M2
+    where
+        B: Term,
+        M1: Functor<Function<Vec<(T, Vec<B>)>, Vec<(T, Vec<B>)>>, Pointed = (T, Vec<B>)>
+            + WithPointed<Vec<(T, Vec<B>)>, WithPointed = MP>,
+        M1::Mapped: Applicative<Vec<(T, Vec<B>)>, Vec<(T, Vec<B>)>, WithA = MP, WithB = MP>,
+        MP: PureA<Pointed = Vec<(T, Vec<B>)>> + Monad<Vec<RoseTree<T>>, Chained = MV>,
+        MV: PureA<Pointed = Vec<RoseTree<T>>>
+            + Monad<Vec<(T, Vec<B>)>, Chained = MP>
+            + Functor<Vec<RoseTree<T>>, Mapped = MV>
+            + Functor<RoseTree<T>, Mapped = M2>,
+        M2: Term,
+    {
+        Functor::<RoseTree<T>>::fmap(Self::unfold_forest_m_bf(f, vec![b]), |mut ts| ts.remove(0))
+    }
+
+    /// Monadic forest builder, in breadth-first order.
+    pub fn unfold_forest_m_bf<B, M1, MP, MV>(f: impl FunctionT<B, M1>, bs: Vec<B>) -> MV
+    where
+        B: Term,
+        M1: Functor<Function<Vec<(T, Vec<B>)>, Vec<(T, Vec<B>)>>, Pointed = (T, Vec<B>)>
+            + WithPointed<Vec<(T, Vec<B>)>, WithPointed = MP>,
+        M1::Mapped: Applicative<Vec<(T, Vec<B>)>, Vec<(T, Vec<B>)>, WithA = MP, WithB = MP>,
+        MP: PureA<Pointed = Vec<(T, Vec<B>)>> + Monad<Vec<RoseTree<T>>, Chained = MV>,
+        MV: PureA<Pointed = Vec<RoseTree<T>>>
+            + Monad<Vec<(T, Vec<B>)>, Chained = MP>
+            + Functor<Vec<RoseTree<T>>, Mapped = MV>,
+    {
+        if bs.is_empty() {
+            return PureA::pure_a(vec![]);
+        }
+
+        let f = f.to_function();
+        bs.map_m(f.clone()).chain_m(|level: Vec<(T, Vec<B>)>| {
+            // Expand the next level as a single forest,
+            // then hand each node back its own children
+            let seeds = level.iter().flat_map(|(_, bs)| bs.clone()).collect();
+            Functor::<Vec<RoseTree<T>>>::fmap(Self::unfold_forest_m_bf(f, seeds), |ts| {
+                let mut ts = ts.into_iter();
+                level
+                    .into_iter()
+                    .map(|(t, bs)| RoseTree(t, ts.by_ref().take(bs.len()).collect()))
+                    .collect()
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{base::data::tree::rose_tree::RoseTree, transformers::state::State};
+
+    fn children(n: usize) -> Vec<usize> {
+        [2 * n, 2 * n + 1]
+            .into_iter()
+            .filter(|c| *c < 8 && *c != n)
+            .collect()
+    }
+
+    /// Label each seed with the order in which it was visited.
+    fn visit(n: usize) -> State<usize, ((usize, usize), Vec<usize>)> {
+        State::new(move |i| (((i, n), children(n)), i + 1))
+    }
+
+    #[test]
+    fn test_unfold_tree() {
+        let tree = RoseTree::unfold_tree(|n| (n, children(n)), 1);
+        assert_eq!(
+            tree,
+            RoseTree(
+                1,
+                vec![
+                    RoseTree(2, vec![RoseTree(4, vec![]), RoseTree(5, vec![])]),
+                    RoseTree(3, vec![RoseTree(6, vec![]), RoseTree(7, vec![])]),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_unfold_tree_m() {
+        let (tree, count): (RoseTree<(usize, usize)>, usize) =
+            RoseTree::unfold_tree_m(visit, 1).run(0);
+        assert_eq!(count, 7);
+        assert_eq!(
+            tree.flatten(),
+            vec![(0, 1), (1, 2), (2, 4), (3, 5), (4, 3), (5, 6), (6, 7)]
+        );
+    }
+
+    #[test]
+    fn test_unfold_tree_m_bf() {
+        let (tree, count): (RoseTree<(usize, usize)>, usize) =
+            RoseTree::unfold_tree_m_bf(visit, 1).run(0);
+        assert_eq!(count, 7);
+        assert_eq!(
+            tree.flatten(),
+            vec![(0, 1), (1, 2), (3, 4), (4, 5), (2, 3), (5, 6), (6, 7)]
+        );
+    }
+}
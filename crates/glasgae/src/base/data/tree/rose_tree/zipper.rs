@@ -0,0 +1,245 @@
+use crate::prelude::*;
+
+use super::RoseTree;
+
+/// The context of a subtree, as seen from its parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Crumb<T> {
+    label: T,
+    /// Siblings to the left of the focus, nearest last.
+    lefts: Vec<RoseTree<T>>,
+    /// Siblings to the right of the focus, nearest last.
+    rights: Vec<RoseTree<T>>,
+}
+
+/// A position within a [`RoseTree`],
+/// supporting navigation between nodes and modification of the focused subtree.
+///
+/// Moves which would leave the tree return `None`.
+///
+/// ```
+/// # use glasgae::base::data::tree::rose_tree::{RoseTree, RoseTreeZipper};
+/// let tree = RoseTree(1, vec![RoseTree(2, vec![]), RoseTree(3, vec![])]);
+///
+/// let zipper = RoseTreeZipper::new(tree)
+///     .first_child()
+///     .and_then(RoseTreeZipper::next_sibling)
+///     .unwrap();
+/// assert_eq!(zipper.label(), &3);
+///
+/// let tree = zipper.modify(|RoseTree(t, ts)| RoseTree(t * 10, ts)).to_tree();
+/// assert_eq!(tree, RoseTree(1, vec![RoseTree(2, vec![]), RoseTree(30, vec![])]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoseTreeZipper<T> {
+    focus: RoseTree<T>,
+    path: Vec<Crumb<T>>,
+}
+
+impl<T> From<RoseTree<T>> for RoseTreeZipper<T> {
+    fn from(tree: RoseTree<T>) -> Self {
+        RoseTreeZipper::new(tree)
+    }
+}
+
+impl<T> RoseTreeZipper<T> {
+    /// Create a zipper focused on the root of `tree`.
+    pub fn new(tree: RoseTree<T>) -> Self {
+        RoseTreeZipper {
+            focus: tree,
+            path: vec![],
+        }
+    }
+
+    /// Returns the focused subtree.
+    pub fn focus(&self) -> &RoseTree<T> {
+        &self.focus
+    }
+
+    /// Returns the label of the focused node.
+    pub fn label(&self) -> &T {
+        &self.focus.0
+    }
+
+    /// Returns `true` if the focus is the root of the tree.
+    pub fn is_root(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Returns the distance from the focus to the root of the tree.
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    /// Move to the parent of the focused node.
+    pub fn parent(mut self) -> Option<Self> {
+        let Crumb {
+            label,
+            mut lefts,
+            rights,
+        } = self.path.pop()?;
+
+        lefts.push(self.focus);
+        lefts.extend(rights.into_iter().rev());
+
+        Some(RoseTreeZipper {
+            focus: RoseTree(label, lefts),
+            path: self.path,
+        })
+    }
+
+    /// Move to the leftmost child of the focused node.
+    pub fn first_child(mut self) -> Option<Self> {
+        let RoseTree(label, children) = self.focus;
+        let mut rights = children;
+        rights.reverse();
+
+        let focus = rights.pop()?;
+        self.path.push(Crumb {
+            label,
+            lefts: vec![],
+            rights,
+        });
+
+        Some(RoseTreeZipper {
+            focus,
+            path: self.path,
+        })
+    }
+
+    /// Move to the rightmost child of the focused node.
+    pub fn last_child(mut self) -> Option<Self> {
+        let RoseTree(label, mut lefts) = self.focus;
+
+        let focus = lefts.pop()?;
+        self.path.push(Crumb {
+            label,
+            lefts,
+            rights: vec![],
+        });
+
+        Some(RoseTreeZipper {
+            focus,
+            path: self.path,
+        })
+    }
+
+    /// Move to the next sibling of the focused node.
+    pub fn next_sibling(mut self) -> Option<Self> {
+        let crumb = self.path.last_mut()?;
+        let focus = crumb.rights.pop()?;
+        crumb.lefts.push(std::mem::replace(&mut self.focus, focus));
+        Some(self)
+    }
+
+    /// Move to the previous sibling of the focused node.
+    pub fn prev_sibling(mut self) -> Option<Self> {
+        let crumb = self.path.last_mut()?;
+        let focus = crumb.lefts.pop()?;
+        crumb.rights.push(std::mem::replace(&mut self.focus, focus));
+        Some(self)
+    }
+
+    /// Move to the root of the tree.
+    pub fn to_root(self) -> Self {
+        let mut zipper = self;
+        while !zipper.is_root() {
+            zipper = zipper.parent().unwrap();
+        }
+        zipper
+    }
+
+    /// Rebuild the whole tree, discarding the current position.
+    pub fn to_tree(self) -> RoseTree<T> {
+        self.to_root().focus
+    }
+}
+
+impl<T> RoseTreeZipper<T>
+where
+    T: Term,
+{
+    /// Apply `f` to the focused subtree.
+    pub fn modify(self, f: impl FunctionT<RoseTree<T>, RoseTree<T>>) -> Self {
+        RoseTreeZipper {
+            focus: f(self.focus),
+            path: self.path,
+        }
+    }
+
+    /// Replace the focused subtree.
+    pub fn replace(self, tree: RoseTree<T>) -> Self {
+        self.modify(r#const(tree))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RoseTree, RoseTreeZipper};
+
+    fn tree() -> RoseTree<&'static str> {
+        RoseTree(
+            "root",
+            vec![
+                RoseTree("a", vec![RoseTree("a1", vec![]), RoseTree("a2", vec![])]),
+                RoseTree("b", vec![]),
+                RoseTree("c", vec![RoseTree("c1", vec![])]),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_navigation() {
+        let zipper = RoseTreeZipper::new(tree());
+        assert!(zipper.is_root());
+        assert_eq!(zipper.clone().parent(), None);
+        assert_eq!(zipper.clone().next_sibling(), None);
+
+        let a = zipper.clone().first_child().unwrap();
+        assert_eq!(a.label(), &"a");
+        assert_eq!(a.clone().prev_sibling(), None);
+
+        let a2 = a.clone().last_child().unwrap();
+        assert_eq!(a2.label(), &"a2");
+        assert_eq!(a2.depth(), 2);
+        assert_eq!(a2.clone().prev_sibling().unwrap().label(), &"a1");
+        assert_eq!(a2.clone().first_child(), None);
+        assert_eq!(a2.clone().parent(), Some(a.clone()));
+
+        let c = a
+            .next_sibling()
+            .and_then(RoseTreeZipper::next_sibling)
+            .unwrap();
+        assert_eq!(c.label(), &"c");
+        assert_eq!(c.clone().next_sibling(), None);
+        assert_eq!(c.clone().prev_sibling().unwrap().label(), &"b");
+
+        assert_eq!(a2.to_root(), zipper);
+    }
+
+    #[test]
+    fn test_modify() {
+        let tree = RoseTreeZipper::new(tree())
+            .first_child()
+            .and_then(RoseTreeZipper::next_sibling)
+            .unwrap()
+            .replace(RoseTree("b", vec![RoseTree("b1", vec![])]))
+            .next_sibling()
+            .and_then(RoseTreeZipper::first_child)
+            .unwrap()
+            .modify(|RoseTree(_, ts)| RoseTree("c2", ts))
+            .to_tree();
+
+        assert_eq!(
+            tree,
+            RoseTree(
+                "root",
+                vec![
+                    RoseTree("a", vec![RoseTree("a1", vec![]), RoseTree("a2", vec![])]),
+                    RoseTree("b", vec![RoseTree("b1", vec![])]),
+                    RoseTree("c", vec![RoseTree("c2", vec![])]),
+                ],
+            )
+        );
+    }
+}
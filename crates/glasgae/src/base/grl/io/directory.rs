@@ -0,0 +1,268 @@
+//! Filesystem queries and manipulation.
+//!
+//! Based on [System.Directory](https://hackage.haskell.org/package/directory/docs/System-Directory.html).
+//!
+//! Each fallible operation returning `IO<T>` has a `try_*` counterpart returning
+//! `IO<Either<IOException, T>>`, in the same manner as [`read_file`] and friends.
+//!
+//! ```
+//! # use glasgae::{base::grl::io::directory::*, prelude::*};
+//! let io = with_temp_directory(|dir| {
+//!     let file = dir.join("hello.txt");
+//!     write_file(file.clone(), "Hello")
+//!         .then_m(does_file_exist(file))
+//!         .chain_m(move |exists| list_directory(dir).fmap(move |entries| (exists, entries)))
+//! });
+//!
+//! assert_eq!(unsafe { io.run() }, (true, vec!["hello.txt".into()]));
+//! ```
+
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::SystemTime,
+};
+
+use crate::{
+    base::control::exception::{bracket, IOException},
+    prelude::*,
+};
+
+/// Returns `true` if `path` exists and is a file.
+pub fn does_file_exist(path: impl Term + AsRef<Path>) -> IO<bool> {
+    IO::new(move || path.as_ref().is_file())
+}
+
+/// Returns `true` if `path` exists and is a directory.
+pub fn does_directory_exist(path: impl Term + AsRef<Path>) -> IO<bool> {
+    IO::new(move || path.as_ref().is_dir())
+}
+
+pub fn try_list_directory(path: impl Term + AsRef<Path>) -> IO<Either<IOException, Vec<PathBuf>>> {
+    IO::new(move || {
+        std::fs::read_dir(&path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| PathBuf::from(entry.file_name())))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map(|mut names| {
+                names.sort();
+                names
+            })
+            .map_err(IOException::on_path("list_directory", path))
+            .into()
+    })
+}
+
+/// The names of the entries in a directory, in sorted order.
+pub fn list_directory(path: impl Term + AsRef<Path>) -> IO<Vec<PathBuf>> {
+    try_list_directory(path).chain_m(unwrap_either)
+}
+
+pub fn try_create_directory_if_missing(
+    create_parents: bool,
+    path: impl Term + AsRef<Path>,
+) -> IO<Either<IOException, ()>> {
+    IO::new(move || {
+        if create_parents {
+            std::fs::create_dir_all(&path)
+        } else if path.as_ref().is_dir() {
+            Ok(())
+        } else {
+            std::fs::create_dir(&path)
+        }
+        .map_err(IOException::on_path("create_directory_if_missing", path))
+        .into()
+    })
+}
+
+/// Create a directory if it does not already exist.
+///
+/// If `create_parents` is `true`, missing parent directories are created too.
+pub fn create_directory_if_missing(create_parents: bool, path: impl Term + AsRef<Path>) -> IO<()> {
+    try_create_directory_if_missing(create_parents, path).chain_m(unwrap_either)
+}
+
+pub fn try_remove_file(path: impl Term + AsRef<Path>) -> IO<Either<IOException, ()>> {
+    IO::new(move || {
+        std::fs::remove_file(&path)
+            .map_err(IOException::on_path("remove_file", path))
+            .into()
+    })
+}
+
+/// Remove a file.
+pub fn remove_file(path: impl Term + AsRef<Path>) -> IO<()> {
+    try_remove_file(path).chain_m(unwrap_either)
+}
+
+pub fn try_rename_path(
+    from: impl Term + AsRef<Path>,
+    to: impl Term + AsRef<Path>,
+) -> IO<Either<IOException, ()>> {
+    IO::new(move || {
+        std::fs::rename(&from, to)
+            .map_err(IOException::on_path("rename_path", from))
+            .into()
+    })
+}
+
+/// Rename a file or directory, replacing the destination if it is a file.
+pub fn rename_path(from: impl Term + AsRef<Path>, to: impl Term + AsRef<Path>) -> IO<()> {
+    try_rename_path(from, to).chain_m(unwrap_either)
+}
+
+pub fn try_copy_file(
+    from: impl Term + AsRef<Path>,
+    to: impl Term + AsRef<Path>,
+) -> IO<Either<IOException, ()>> {
+    IO::new(move || {
+        std::fs::copy(&from, to)
+            .map(drop)
+            .map_err(IOException::on_path("copy_file", from))
+            .into()
+    })
+}
+
+/// Copy the contents and permissions of a file, replacing the destination if it exists.
+pub fn copy_file(from: impl Term + AsRef<Path>, to: impl Term + AsRef<Path>) -> IO<()> {
+    try_copy_file(from, to).chain_m(unwrap_either)
+}
+
+pub fn try_get_modification_time(
+    path: impl Term + AsRef<Path>,
+) -> IO<Either<IOException, SystemTime>> {
+    IO::new(move || {
+        std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(IOException::on_path("get_modification_time", path))
+            .into()
+    })
+}
+
+/// The time at which a file or directory was last modified.
+pub fn get_modification_time(path: impl Term + AsRef<Path>) -> IO<SystemTime> {
+    try_get_modification_time(path).chain_m(unwrap_either)
+}
+
+pub fn try_get_current_directory() -> IO<Either<IOException, PathBuf>> {
+    IO::new(|| {
+        std::env::current_dir()
+            .map_err(|e| IOException::new("get_current_directory", e))
+            .into()
+    })
+}
+
+/// The current working directory of the process.
+pub fn get_current_directory() -> IO<PathBuf> {
+    try_get_current_directory().chain_m(unwrap_either)
+}
+
+pub fn try_set_current_directory(path: impl Term + AsRef<Path>) -> IO<Either<IOException, ()>> {
+    IO::new(move || {
+        std::env::set_current_dir(&path)
+            .map_err(IOException::on_path("set_current_directory", path))
+            .into()
+    })
+}
+
+/// Change the current working directory of the process.
+pub fn set_current_directory(path: impl Term + AsRef<Path>) -> IO<()> {
+    try_set_current_directory(path).chain_m(unwrap_either)
+}
+
+/// Create a fresh directory under the system temporary directory,
+/// pass its path to `f`, and remove it along with its contents afterwards,
+/// even if `f` throws.
+pub fn with_temp_directory<T>(f: impl FunctionT<PathBuf, IO<T>>) -> IO<T>
+where
+    T: Term,
+{
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let create = IO::new(|| {
+        let path = std::env::temp_dir().join(format!(
+            "glasgae-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir(&path)
+            .map(|_| path.clone())
+            .map_err(IOException::on_path("with_temp_directory", path))
+            .into()
+    })
+    .chain_m(unwrap_either);
+
+    let remove = |path: PathBuf| {
+        IO::new(move || {
+            std::fs::remove_dir_all(&path)
+                .map_err(IOException::on_path("with_temp_directory", path))
+                .into()
+        })
+        .chain_m(unwrap_either)
+    };
+
+    bracket(create, remove, f)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn test_directory() {
+        let io = with_temp_directory(|dir| {
+            let nested = dir.join("a").join("b");
+            let file = nested.join("file.txt");
+            let copy = dir.join("copy.txt");
+            let renamed = dir.join("renamed.txt");
+
+            create_directory_if_missing(true, nested.clone())
+                .then_m(create_directory_if_missing(false, nested.clone()))
+                .then_m(write_file(file.clone(), "contents"))
+                .then_m(copy_file(file.clone(), copy.clone()))
+                .then_m(rename_path(copy.clone(), renamed.clone()))
+                .then_m(get_modification_time(renamed.clone()))
+                .then_m(does_file_exist(copy))
+                .chain_m(move |copy_exists| {
+                    read_file(renamed.clone()).chain_m(move |contents| {
+                        remove_file(renamed)
+                            .then_m(list_directory(dir.clone()))
+                            .chain_m(move |entries| {
+                                does_directory_exist(nested).fmap(move |nested_exists| {
+                                    (copy_exists, contents, entries, nested_exists, dir)
+                                })
+                            })
+                    })
+                })
+        });
+
+        let (copy_exists, contents, entries, nested_exists, dir) = unsafe { io.run() };
+        assert!(!copy_exists);
+        assert_eq!(contents, "contents");
+        assert_eq!(entries, vec![PathBuf::from("a")]);
+        assert!(nested_exists);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_errors() {
+        let missing = std::env::temp_dir().join("glasgae-directory-missing");
+
+        let e = match unsafe { try_list_directory(missing.clone()).run() } {
+            Left(e) => e,
+            Right(_) => panic!("expected an exception"),
+        };
+        assert_eq!(e.kind, ErrorKind::NotFound);
+        assert_eq!(e.operation, "list_directory");
+        assert_eq!(e.path, Some(missing.clone()));
+
+        assert!(
+            unsafe { try_create_directory_if_missing(false, missing.join("child")).run() }
+                .is_left()
+        );
+    }
+}
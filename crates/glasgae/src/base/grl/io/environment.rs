@@ -0,0 +1,136 @@
+//! Access to the program's arguments and environment variables.
+//!
+//! Based on [System.Environment](https://hackage.haskell.org/package/base/docs/System-Environment.html).
+//!
+//! ```
+//! # use glasgae::{base::grl::io::environment::*, prelude::*};
+//! let io = with_args(vec!["--verbose".to_string()], get_args());
+//!
+//! assert_eq!(unsafe { io.run() }, vec!["--verbose".to_string()]);
+//! ```
+
+use std::{cell::RefCell, path::Path};
+
+use crate::prelude::*;
+
+thread_local! {
+    static ARGS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Resets the arguments of the current thread when dropped,
+/// including during unwinding.
+struct ArgsGuard(Option<Vec<String>>);
+
+impl ArgsGuard {
+    fn set(args: Vec<String>) -> Self {
+        ArgsGuard(ARGS.with(|cell| cell.replace(Some(args))))
+    }
+}
+
+impl Drop for ArgsGuard {
+    fn drop(&mut self) {
+        ARGS.with(|cell| *cell.borrow_mut() = self.0.take())
+    }
+}
+
+/// The command line arguments of the program, not including the program name.
+pub fn get_args() -> IO<Vec<String>> {
+    IO::new(|| {
+        ARGS.with(|cell| cell.borrow().clone())
+            .unwrap_or_else(|| std::env::args().skip(1).collect())
+    })
+}
+
+/// The name the program was invoked by, without any leading directories.
+pub fn get_prog_name() -> IO<String> {
+    IO::new(|| {
+        std::env::args()
+            .next()
+            .map(|arg0| {
+                Path::new(&arg0)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or(arg0)
+            })
+            .unwrap_or_default()
+    })
+}
+
+/// Run `io` with [`get_args`] returning `args`.
+///
+/// The override only applies to the current thread,
+/// so that concurrently running tests do not observe each other's arguments.
+pub fn with_args<T>(args: Vec<String>, io: IO<T>) -> IO<T>
+where
+    T: Term,
+{
+    IO::new(move || {
+        let _guard = ArgsGuard::set(args);
+        unsafe { io.run() }
+    })
+}
+
+/// The value of an environment variable, if it is set and valid unicode.
+pub fn lookup_env(name: impl Term + AsRef<str>) -> IO<Option<String>> {
+    IO::new(move || std::env::var(name.as_ref()).ok())
+}
+
+/// All environment variables that are valid unicode, as name-value pairs.
+pub fn get_environment() -> IO<Vec<(String, String)>> {
+    IO::new(|| std::env::vars().collect())
+}
+
+/// Set an environment variable for the current process.
+pub fn set_env(name: impl Term + AsRef<str>, value: impl Term + AsRef<str>) -> IO<()> {
+    IO::new(move || std::env::set_var(name.as_ref(), value.as_ref()))
+}
+
+/// Remove an environment variable from the current process.
+pub fn unset_env(name: impl Term + AsRef<str>) -> IO<()> {
+    IO::new(move || std::env::remove_var(name.as_ref()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{base::control::exception::catch, prelude::*};
+
+    use super::{get_args, lookup_env, set_env, unset_env, with_args};
+
+    #[test]
+    fn test_with_args() {
+        let outer = unsafe { get_args().run() };
+
+        let io = with_args(
+            vec!["a".to_string()],
+            get_args().chain_m(|a| {
+                with_args(vec!["b".to_string()], get_args())
+                    .chain_m(move |b| get_args().fmap(move |a_again| (a, b, a_again)))
+            }),
+        );
+        assert_eq!(
+            unsafe { io.run() },
+            (
+                vec!["a".to_string()],
+                vec!["b".to_string()],
+                vec!["a".to_string()]
+            )
+        );
+
+        let io = with_args(vec![], IO::<()>::new(|| panic!("unwinding")));
+        unsafe { catch(io, |_: String| IO::return_m(())).run() };
+        assert_eq!(unsafe { get_args().run() }, outer);
+    }
+
+    #[test]
+    fn test_env() {
+        let name = "GLASGAE_ENVIRONMENT_TEST";
+        let io = set_env(name, "value")
+            .then_m(lookup_env(name))
+            .chain_m(move |set| {
+                unset_env(name)
+                    .then_m(lookup_env(name))
+                    .fmap(|unset| (set, unset))
+            });
+        assert_eq!(unsafe { io.run() }, (Some("value".to_string()), None));
+    }
+}
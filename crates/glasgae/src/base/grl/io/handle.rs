@@ -0,0 +1,857 @@
+//! Handle-based I/O on files and the standard streams.
+//!
+//! Based on [System.IO](https://hackage.haskell.org/package/base/docs/System-IO.html).
+//!
+//! Each operation returning `IO<T>` has a `try_*` counterpart returning
+//! `IO<Either<IOException, T>>`, in the same manner as [`read_file`] and friends.
+//!
+//! ```
+//! # use glasgae::{base::grl::io::handle::*, prelude::*};
+//! let path = std::env::temp_dir().join("glasgae-handle-doctest.txt");
+//!
+//! let io = with_file(path.clone(), IOMode::WriteMode, |h| {
+//!     h_put_str_ln(h.clone(), "Hello".to_string()).then_m(h_put_str_ln(h, "World".to_string()))
+//! })
+//! .then_m(with_file(path.clone(), IOMode::ReadMode, |h| {
+//!     h_get_line(h.clone()).chain_m(|first| h_get_contents(h).fmap(|rest| (first, rest)))
+//! }));
+//!
+//! assert_eq!(
+//!     unsafe { io.run() },
+//!     ("Hello".to_string(), "World\n".to_string())
+//! );
+//! # std::fs::remove_file(path).unwrap();
+//! ```
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{ErrorKind, Read, Seek, SeekFrom, Write},
+    path::Path,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+};
+
+use crate::{
+    base::control::exception::{bracket, IOException},
+    prelude::*,
+};
+
+/// The size of the buffer used by [`BufferMode::BlockBuffering`] when none is specified.
+const DEFAULT_BUFFER_SIZE: usize = 8192;
+
+/// The mode in which a file is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IOMode {
+    /// Open for reading.
+    ReadMode,
+    /// Open for writing, truncating any existing file.
+    WriteMode,
+    /// Open for writing at the end of the file.
+    AppendMode,
+    /// Open for both reading and writing.
+    ReadWriteMode,
+}
+
+impl IOMode {
+    fn readable(self) -> bool {
+        matches!(self, IOMode::ReadMode | IOMode::ReadWriteMode)
+    }
+
+    fn writable(self) -> bool {
+        !matches!(self, IOMode::ReadMode)
+    }
+
+    fn open_options(self) -> OpenOptions {
+        let mut options = OpenOptions::new();
+        match self {
+            IOMode::ReadMode => options.read(true),
+            IOMode::WriteMode => options.write(true).create(true).truncate(true),
+            IOMode::AppendMode => options.append(true).create(true),
+            IOMode::ReadWriteMode => options.read(true).write(true).create(true),
+        };
+        options
+    }
+}
+
+/// How output written to a handle is buffered before reaching the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BufferMode {
+    /// Write output immediately.
+    NoBuffering,
+    /// Write output whenever a newline is written, or the buffer is flushed.
+    LineBuffering,
+    /// Write output whenever the buffer reaches the given size,
+    /// or an implementation-defined size if none is given.
+    BlockBuffering(Option<usize>),
+}
+
+/// Where [`h_seek`] measures its offset from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SeekMode {
+    /// From the start of the file.
+    AbsoluteSeek,
+    /// From the current position.
+    RelativeSeek,
+    /// From the end of the file.
+    SeekFromEnd,
+}
+
+enum Device {
+    File(File),
+    Stdin,
+    Stdout,
+    Stderr,
+}
+
+impl Read for Device {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Device::File(file) => file.read(buf),
+            Device::Stdin => std::io::stdin().read(buf),
+            Device::Stdout | Device::Stderr => Err(ErrorKind::Unsupported.into()),
+        }
+    }
+}
+
+impl Write for Device {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Device::File(file) => file.write(buf),
+            Device::Stdout => std::io::stdout().write(buf),
+            Device::Stderr => std::io::stderr().write(buf),
+            Device::Stdin => Err(ErrorKind::Unsupported.into()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Device::File(file) => file.flush(),
+            Device::Stdout => std::io::stdout().flush(),
+            Device::Stderr => std::io::stderr().flush(),
+            Device::Stdin => Ok(()),
+        }
+    }
+}
+
+struct HandleState {
+    /// The underlying device, or [`None`] once the handle is closed.
+    device: Option<Device>,
+    mode: IOMode,
+    buffering: BufferMode,
+    read_buf: Vec<u8>,
+    read_pos: usize,
+    write_buf: Vec<u8>,
+}
+
+fn illegal_operation(reason: &str) -> std::io::Error {
+    std::io::Error::other(format!("illegal operation ({reason})"))
+}
+
+impl HandleState {
+    fn device(&mut self) -> std::io::Result<&mut Device> {
+        self.device
+            .as_mut()
+            .ok_or_else(|| illegal_operation("handle is closed"))
+    }
+
+    fn check_readable(&mut self) -> std::io::Result<()> {
+        self.device()?;
+        if self.mode.readable() {
+            Ok(())
+        } else {
+            Err(illegal_operation("handle is not open for reading"))
+        }
+    }
+
+    fn check_writable(&mut self) -> std::io::Result<()> {
+        self.device()?;
+        if self.mode.writable() {
+            Ok(())
+        } else {
+            Err(illegal_operation("handle is not open for writing"))
+        }
+    }
+
+    fn flush_write_buf(&mut self) -> std::io::Result<()> {
+        if self.write_buf.is_empty() {
+            // Output to the standard handles is written through, but may still be buffered by std.
+            return match &mut self.device {
+                Some(device @ (Device::Stdout | Device::Stderr)) => device.flush(),
+                _ => Ok(()),
+            };
+        }
+        let buf = std::mem::take(&mut self.write_buf);
+        let device = self.device()?;
+        device.write_all(&buf)?;
+        device.flush()
+    }
+
+    /// Drop any buffered input, moving the device back to the logical read position.
+    fn discard_read_buf(&mut self) -> std::io::Result<()> {
+        let unread = (self.read_buf.len() - self.read_pos) as i64;
+        self.read_buf.clear();
+        self.read_pos = 0;
+        match self.device()? {
+            Device::File(file) if unread > 0 => file.seek(SeekFrom::Current(-unread)).map(drop),
+            _ => Ok(()),
+        }
+    }
+
+    /// The buffered input, refilled from the device if exhausted.
+    ///
+    /// Empty at the end of the file.
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.check_readable()?;
+        if self.read_pos == self.read_buf.len() {
+            self.flush_write_buf()?;
+            let mut buf = vec![0; DEFAULT_BUFFER_SIZE];
+            let len = self.device()?.read(&mut buf)?;
+            buf.truncate(len);
+            self.read_buf = buf;
+            self.read_pos = 0;
+        }
+        Ok(&self.read_buf[self.read_pos..])
+    }
+
+    fn consume(&mut self, len: usize) {
+        self.read_pos += len;
+    }
+
+    fn get_bytes(&mut self, len: usize) -> std::io::Result<Vec<u8>> {
+        let mut out = vec![];
+        while out.len() < len {
+            let buf = self.fill_buf()?;
+            if buf.is_empty() {
+                break;
+            }
+            let take = buf.len().min(len - out.len());
+            out.extend_from_slice(&buf[..take]);
+            self.consume(take);
+        }
+        Ok(out)
+    }
+
+    fn get_contents_bytes(&mut self) -> std::io::Result<Vec<u8>> {
+        let mut out = vec![];
+        loop {
+            let buf = self.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(out);
+            }
+            let len = buf.len();
+            out.extend_from_slice(buf);
+            self.consume(len);
+        }
+    }
+
+    fn get_line(&mut self) -> std::io::Result<String> {
+        let mut line = vec![];
+        loop {
+            let buf = self.fill_buf()?;
+            if buf.is_empty() {
+                if line.is_empty() {
+                    return Err(std::io::Error::new(ErrorKind::UnexpectedEof, "end of file"));
+                }
+                break;
+            }
+            match buf.iter().position(|b| *b == b'\n') {
+                Some(i) => {
+                    line.extend_from_slice(&buf[..i]);
+                    self.consume(i + 1);
+                    break;
+                }
+                None => {
+                    let len = buf.len();
+                    line.extend_from_slice(buf);
+                    self.consume(len);
+                }
+            }
+        }
+        into_string(line)
+    }
+
+    fn get_char(&mut self) -> std::io::Result<char> {
+        let first = self.get_bytes(1)?;
+        let Some(first) = first.first().copied() else {
+            return Err(std::io::Error::new(ErrorKind::UnexpectedEof, "end of file"));
+        };
+        let width = match first.leading_ones() {
+            0 => 1,
+            n => n as usize,
+        };
+        let mut bytes = vec![first];
+        bytes.extend(self.get_bytes(width - 1)?);
+        into_string(bytes).and_then(|s| {
+            s.chars()
+                .next()
+                .ok_or_else(|| std::io::Error::new(ErrorKind::UnexpectedEof, "end of file"))
+        })
+    }
+
+    fn put_bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.check_writable()?;
+        self.discard_read_buf()?;
+
+        // The standard handles are never dropped,
+        // so they write through to std's own buffers, which are flushed at exit.
+        let buffering = self.buffering;
+        let device = self.device()?;
+        if let Device::Stdout | Device::Stderr = device {
+            device.write_all(bytes)?;
+            return match buffering {
+                BufferMode::NoBuffering => device.flush(),
+                BufferMode::LineBuffering if bytes.contains(&b'\n') => device.flush(),
+                _ => Ok(()),
+            };
+        }
+
+        self.write_buf.extend_from_slice(bytes);
+        let flush = match self.buffering {
+            BufferMode::NoBuffering => true,
+            BufferMode::LineBuffering => bytes.contains(&b'\n'),
+            BufferMode::BlockBuffering(size) => {
+                self.write_buf.len() >= size.unwrap_or(DEFAULT_BUFFER_SIZE)
+            }
+        };
+        if flush {
+            self.flush_write_buf()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn seek(&mut self, mode: SeekMode, offset: i64) -> std::io::Result<()> {
+        self.flush_write_buf()?;
+        self.discard_read_buf()?;
+        let pos = match mode {
+            SeekMode::AbsoluteSeek => SeekFrom::Start(
+                u64::try_from(offset).map_err(|_| illegal_operation("negative offset"))?,
+            ),
+            SeekMode::RelativeSeek => SeekFrom::Current(offset),
+            SeekMode::SeekFromEnd => SeekFrom::End(offset),
+        };
+        match self.device()? {
+            Device::File(file) => file.seek(pos).map(drop),
+            _ => Err(illegal_operation("handle is not seekable")),
+        }
+    }
+
+    fn tell(&mut self) -> std::io::Result<u64> {
+        self.flush_write_buf()?;
+        self.discard_read_buf()?;
+        match self.device()? {
+            Device::File(file) => file.stream_position(),
+            _ => Err(illegal_operation("handle is not seekable")),
+        }
+    }
+
+    fn set_buffering(&mut self, buffering: BufferMode) -> std::io::Result<()> {
+        self.device()?;
+        self.flush_write_buf()?;
+        self.buffering = buffering;
+        Ok(())
+    }
+
+    fn close(&mut self) -> std::io::Result<()> {
+        if self.device.is_none() {
+            return Ok(());
+        }
+        let flushed = self.flush_write_buf();
+        self.device = None;
+        flushed
+    }
+}
+
+/// Flush any buffered output once the last reference to a handle is dropped,
+/// as if by an implicit [`h_close`].
+///
+/// Errors are ignored, since there is nowhere to report them.
+impl Drop for HandleState {
+    fn drop(&mut self) {
+        let _ = self.flush_write_buf();
+    }
+}
+
+fn into_string(bytes: Vec<u8>) -> std::io::Result<String> {
+    String::from_utf8(bytes).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// A reference to an open file or standard stream.
+///
+/// Handles are shared between clones, and closed explicitly with [`h_close`].
+#[derive(Clone)]
+pub struct Handle {
+    name: Arc<str>,
+    state: Arc<Mutex<HandleState>>,
+}
+
+impl Handle {
+    fn new(name: impl Into<Arc<str>>, device: Device, mode: IOMode, buffering: BufferMode) -> Self {
+        Handle {
+            name: name.into(),
+            state: Arc::new(Mutex::new(HandleState {
+                device: Some(device),
+                mode,
+                buffering,
+                read_buf: vec![],
+                read_pos: 0,
+                write_buf: vec![],
+            })),
+        }
+    }
+
+    /// The path of the file, or the name of the standard stream, that this handle refers to.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Run `f` on the handle's state, attributing any error to `operation`.
+    fn with_state<T>(
+        &self,
+        operation: &str,
+        f: impl FnOnce(&mut HandleState) -> std::io::Result<T>,
+    ) -> Either<IOException, T> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        f(&mut state)
+            .map_err(IOException::on_path(operation, &*self.name))
+            .into()
+    }
+}
+
+impl PartialEq for Handle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Eq for Handle {}
+
+impl std::fmt::Debug for Handle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{handle: {}}}", self.name)
+    }
+}
+
+impl Show for Handle {
+    fn show(self) -> String {
+        format!("{self:?}")
+    }
+}
+
+/// A handle managing input from the program's standard input.
+pub fn stdin() -> Handle {
+    static STDIN: OnceLock<Handle> = OnceLock::new();
+    STDIN
+        .get_or_init(|| {
+            Handle::new(
+                "<stdin>",
+                Device::Stdin,
+                IOMode::ReadMode,
+                BufferMode::LineBuffering,
+            )
+        })
+        .clone()
+}
+
+/// A handle managing output to the program's standard output.
+pub fn stdout() -> Handle {
+    static STDOUT: OnceLock<Handle> = OnceLock::new();
+    STDOUT
+        .get_or_init(|| {
+            Handle::new(
+                "<stdout>",
+                Device::Stdout,
+                IOMode::WriteMode,
+                BufferMode::LineBuffering,
+            )
+        })
+        .clone()
+}
+
+/// A handle managing output to the program's standard error.
+pub fn stderr() -> Handle {
+    static STDERR: OnceLock<Handle> = OnceLock::new();
+    STDERR
+        .get_or_init(|| {
+            Handle::new(
+                "<stderr>",
+                Device::Stderr,
+                IOMode::WriteMode,
+                BufferMode::NoBuffering,
+            )
+        })
+        .clone()
+}
+
+pub fn try_open_file(
+    path: impl Term + AsRef<Path>,
+    mode: IOMode,
+) -> IO<Either<IOException, Handle>> {
+    IO::new(move || {
+        mode.open_options()
+            .open(&path)
+            .map(|file| {
+                Handle::new(
+                    path.as_ref().to_string_lossy(),
+                    Device::File(file),
+                    mode,
+                    BufferMode::BlockBuffering(None),
+                )
+            })
+            .map_err(IOException::on_path("open_file", path))
+            .into()
+    })
+}
+
+/// Open the file at `path` in the given mode.
+pub fn open_file(path: impl Term + AsRef<Path>, mode: IOMode) -> IO<Handle> {
+    try_open_file(path, mode).chain_m(unwrap_either)
+}
+
+/// Open the file at `path`, pass its handle to `f`, and close it afterwards,
+/// even if `f` throws.
+pub fn with_file<T>(
+    path: impl Term + AsRef<Path>,
+    mode: IOMode,
+    f: impl FunctionT<Handle, IO<T>>,
+) -> IO<T>
+where
+    T: Term,
+{
+    bracket(open_file(path, mode), h_close, f)
+}
+
+pub fn try_h_close(h: Handle) -> IO<Either<IOException, ()>> {
+    IO::new(move || h.with_state("h_close", HandleState::close))
+}
+
+/// Flush and close the handle.
+///
+/// Closing an already-closed handle has no effect.
+pub fn h_close(h: Handle) -> IO<()> {
+    try_h_close(h).chain_m(unwrap_either)
+}
+
+pub fn try_h_flush(h: Handle) -> IO<Either<IOException, ()>> {
+    IO::new(move || h.with_state("h_flush", HandleState::flush_write_buf))
+}
+
+/// Write any buffered output to the device.
+pub fn h_flush(h: Handle) -> IO<()> {
+    try_h_flush(h).chain_m(unwrap_either)
+}
+
+pub fn try_h_set_buffering(h: Handle, mode: BufferMode) -> IO<Either<IOException, ()>> {
+    IO::new(move || h.with_state("h_set_buffering", |state| state.set_buffering(mode)))
+}
+
+/// Change the buffering mode of the handle, flushing any buffered output.
+pub fn h_set_buffering(h: Handle, mode: BufferMode) -> IO<()> {
+    try_h_set_buffering(h, mode).chain_m(unwrap_either)
+}
+
+/// The buffering mode of the handle.
+pub fn h_get_buffering(h: Handle) -> IO<BufferMode> {
+    IO::new(move || {
+        h.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .buffering
+    })
+}
+
+pub fn try_h_seek(h: Handle, mode: SeekMode, offset: i64) -> IO<Either<IOException, ()>> {
+    IO::new(move || h.with_state("h_seek", |state| state.seek(mode, offset)))
+}
+
+/// Set the position of a file handle.
+pub fn h_seek(h: Handle, mode: SeekMode, offset: i64) -> IO<()> {
+    try_h_seek(h, mode, offset).chain_m(unwrap_either)
+}
+
+pub fn try_h_tell(h: Handle) -> IO<Either<IOException, u64>> {
+    IO::new(move || h.with_state("h_tell", HandleState::tell))
+}
+
+/// The position of a file handle, in bytes from the start of the file.
+pub fn h_tell(h: Handle) -> IO<u64> {
+    try_h_tell(h).chain_m(unwrap_either)
+}
+
+pub fn try_h_is_eof(h: Handle) -> IO<Either<IOException, bool>> {
+    IO::new(move || h.with_state("h_is_eof", |state| Ok(state.fill_buf()?.is_empty())))
+}
+
+/// Returns `true` if no input remains on the handle.
+pub fn h_is_eof(h: Handle) -> IO<bool> {
+    try_h_is_eof(h).chain_m(unwrap_either)
+}
+
+pub fn try_h_get_char(h: Handle) -> IO<Either<IOException, char>> {
+    IO::new(move || h.with_state("h_get_char", HandleState::get_char))
+}
+
+/// Read a single character from the handle.
+pub fn h_get_char(h: Handle) -> IO<char> {
+    try_h_get_char(h).chain_m(unwrap_either)
+}
+
+pub fn try_h_get_line(h: Handle) -> IO<Either<IOException, String>> {
+    IO::new(move || h.with_state("h_get_line", HandleState::get_line))
+}
+
+/// Read a line from the handle, without its trailing newline.
+///
+/// Throws an [`IOException`] of kind [`ErrorKind::UnexpectedEof`]
+/// if the handle is at the end of the file.
+pub fn h_get_line(h: Handle) -> IO<String> {
+    try_h_get_line(h).chain_m(unwrap_either)
+}
+
+pub fn try_h_get_contents(h: Handle) -> IO<Either<IOException, String>> {
+    IO::new(move || {
+        h.with_state("h_get_contents", |state| {
+            state.get_contents_bytes().and_then(into_string)
+        })
+    })
+}
+
+/// Read the remaining input from the handle.
+pub fn h_get_contents(h: Handle) -> IO<String> {
+    try_h_get_contents(h).chain_m(unwrap_either)
+}
+
+pub fn try_h_put_str(h: Handle, s: String) -> IO<Either<IOException, ()>> {
+    IO::new(move || h.with_state("h_put_str", |state| state.put_bytes(s.as_bytes())))
+}
+
+/// Write a string to the handle.
+pub fn h_put_str(h: Handle, s: String) -> IO<()> {
+    try_h_put_str(h, s).chain_m(unwrap_either)
+}
+
+pub fn try_h_put_str_ln(h: Handle, s: String) -> IO<Either<IOException, ()>> {
+    try_h_put_str(h, s + "\n")
+}
+
+/// Write a string to the handle, followed by a newline.
+pub fn h_put_str_ln(h: Handle, s: String) -> IO<()> {
+    try_h_put_str_ln(h, s).chain_m(unwrap_either)
+}
+
+/// Write the [`Show`] representation of a value to the handle, followed by a newline.
+pub fn h_print(h: Handle, t: impl Term + Show) -> IO<()> {
+    h_put_str_ln(h, t.show())
+}
+
+pub fn try_h_get_bytes(h: Handle, len: usize) -> IO<Either<IOException, Vec<u8>>> {
+    IO::new(move || h.with_state("h_get_bytes", |state| state.get_bytes(len)))
+}
+
+/// Read up to `len` bytes from the handle.
+///
+/// Fewer bytes are returned only if the end of the file is reached.
+pub fn h_get_bytes(h: Handle, len: usize) -> IO<Vec<u8>> {
+    try_h_get_bytes(h, len).chain_m(unwrap_either)
+}
+
+pub fn try_h_get_contents_bytes(h: Handle) -> IO<Either<IOException, Vec<u8>>> {
+    IO::new(move || h.with_state("h_get_contents_bytes", HandleState::get_contents_bytes))
+}
+
+/// Read the remaining input from the handle as bytes.
+pub fn h_get_contents_bytes(h: Handle) -> IO<Vec<u8>> {
+    try_h_get_contents_bytes(h).chain_m(unwrap_either)
+}
+
+pub fn try_h_put_bytes(h: Handle, bytes: Vec<u8>) -> IO<Either<IOException, ()>> {
+    IO::new(move || h.with_state("h_put_bytes", |state| state.put_bytes(&bytes)))
+}
+
+/// Write bytes to the handle.
+pub fn h_put_bytes(h: Handle, bytes: Vec<u8>) -> IO<()> {
+    try_h_put_bytes(h, bytes).chain_m(unwrap_either)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::base::grl::io::{get_char, get_line};
+
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("glasgae-handle-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn test_read_write() {
+        let path = temp_path("read-write");
+
+        let write = with_file(path.clone(), IOMode::WriteMode, |h| {
+            h_put_str(h.clone(), "one\ntwo\n".to_string())
+                .then_m(h_put_bytes(h.clone(), "θree".as_bytes().to_vec()))
+                .then_m(h_tell(h))
+        });
+        assert_eq!(unsafe { write.run() }, 13);
+
+        let read = with_file(path.clone(), IOMode::ReadMode, |h| {
+            h_get_line(h.clone()).chain_m(move |one| {
+                h_get_char(h.clone()).chain_m(move |t| {
+                    h_seek(h.clone(), SeekMode::SeekFromEnd, -5)
+                        .then_m(h_get_char(h.clone()))
+                        .chain_m(move |theta| {
+                            h_get_contents(h.clone()).chain_m(move |rest| {
+                                h_is_eof(h).fmap(move |eof| (one, t, theta, rest, eof))
+                            })
+                        })
+                })
+            })
+        });
+        assert_eq!(
+            unsafe { read.run() },
+            ("one".to_string(), 't', 'θ', "ree".to_string(), true)
+        );
+
+        let append = with_file(path.clone(), IOMode::AppendMode, |h| {
+            h_put_bytes(h, vec![b'!'])
+        })
+        .then_m(with_file(path.clone(), IOMode::ReadMode, |h| {
+            h_get_bytes(h.clone(), 4)
+                .chain_m(|start| h_get_contents_bytes(h).fmap(|end| (start, end)))
+        }));
+        assert_eq!(
+            unsafe { append.run() },
+            (b"one\n".to_vec(), "two\nθree!".as_bytes().to_vec())
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_read_write_mode() {
+        let path = temp_path("read-write-mode");
+        std::fs::write(&path, "abcdef").unwrap();
+
+        let io = with_file(path.clone(), IOMode::ReadWriteMode, |h| {
+            h_get_bytes(h.clone(), 2)
+                .then_m(h_put_str(h.clone(), "XY".to_string()))
+                .then_m(h_seek(h.clone(), SeekMode::AbsoluteSeek, 0))
+                .then_m(h_get_contents(h))
+        });
+        assert_eq!(unsafe { io.run() }, "abXYef");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_flush_on_drop() {
+        let path = temp_path("flush-on-drop");
+
+        let write = open_file(path.clone(), IOMode::WriteMode)
+            .chain_m(|h| h_put_str(h, "unclosed".to_string()));
+        unsafe { write.run() };
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "unclosed");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// Set in child processes started by [`run_child`].
+    const CHILD: &str = "GLASGAE_HANDLE_TEST_CHILD";
+
+    /// Rerun the named test in a child process with the given input,
+    /// returning its standard output.
+    fn run_child(test: &str, stdin: &str) -> String {
+        use std::process::{Command, Stdio};
+
+        let (_, path) = module_path!().split_once("::").unwrap();
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .args([&format!("{path}::{test}"), "--exact", "--nocapture"])
+            .env(CHILD, "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_std_flush_at_exit() {
+        // A prompt without a trailing newline is still written once the process exits.
+        if std::env::var_os(CHILD).is_some() {
+            unsafe { h_put_str(stdout(), "prompt> ".to_string()).run() };
+            return;
+        }
+
+        assert!(run_child("test_std_flush_at_exit", "").contains("prompt> "));
+    }
+
+    #[test]
+    fn test_stdin_shared() {
+        // Reads through the stdin handle and the std-based helpers share one buffer.
+        if std::env::var_os(CHILD).is_some() {
+            let io = get_line().chain_m(|one| {
+                h_get_line(stdin()).chain_m(move |two| {
+                    let one = one.clone();
+                    get_char().chain_m(move |t| {
+                        let (one, two) = (one.clone(), two.clone());
+                        h_get_contents(stdin())
+                            .chain_m(move |rest| print(format!("[{one}|{two}|{t}|{rest}]")))
+                    })
+                })
+            });
+            unsafe { io.run() };
+            return;
+        }
+
+        let out = run_child("test_stdin_shared", "one\ntwo\nthree\n");
+        assert!(out.contains("[one|two|t|hree\n]"), "{out}");
+    }
+
+    #[test]
+    fn test_errors() {
+        let path = temp_path("errors");
+        std::fs::write(&path, "").unwrap();
+
+        let io = open_file(path.clone(), IOMode::ReadMode).chain_m(|h| {
+            try_h_get_line(h.clone()).chain_m(|eof| {
+                try_h_put_str(h.clone(), "x".to_string()).chain_m(|read_only| {
+                    h_close(h.clone())
+                        .then_m(try_h_is_eof(h))
+                        .fmap(|closed| (eof, read_only, closed))
+                })
+            })
+        });
+
+        let kind = |e: Either<IOException, _>| match e {
+            Left(e) => (e.kind, e.operation),
+            Right(_) => panic!("expected an exception"),
+        };
+
+        let (eof, read_only, closed) = unsafe { io.run() };
+        assert_eq!(
+            kind(eof.fmap(drop)),
+            (ErrorKind::UnexpectedEof, "h_get_line".to_string())
+        );
+        assert_eq!(kind(read_only), (ErrorKind::Other, "h_put_str".to_string()));
+        assert_eq!(
+            kind(closed.fmap(drop)),
+            (ErrorKind::Other, "h_is_eof".to_string())
+        );
+
+        let missing = unsafe { try_open_file(temp_path("missing"), IOMode::ReadMode).run() };
+        assert_eq!(kind(missing.fmap(drop)).0, ErrorKind::NotFound);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,14 +1,20 @@
 //! Monadic I/O operations.
 
+pub mod directory;
+pub mod environment;
+pub mod handle;
+pub mod process;
+
 use std::{
-    io::{ErrorKind, Read, Write},
+    io::Write,
+    panic::{catch_unwind, resume_unwind},
     path::Path,
-    process::Termination,
+    process::{ExitCode, Termination},
 };
 
 use crate::{
     base::{
-        control::exception::{throw, Exception},
+        control::exception::{throw, Exception, IOException, SomeException},
         data::function::{Nullary, NullaryT},
     },
     derive_pointed, derive_with_pointed,
@@ -114,13 +120,23 @@ where
     }
 }
 
+/// Exceptions that escape the action are printed to standard error
+/// using [`Exception::display_exception`], and reported as a failure.
 impl<T> Termination for IO<T>
 where
     T: Term + Termination,
 {
-    fn report(self) -> std::process::ExitCode {
-        let out = unsafe { self.run() };
-        out.report()
+    fn report(self) -> ExitCode {
+        match catch_unwind(move || unsafe { self.run() }) {
+            Ok(out) => out.report(),
+            Err(payload) => match payload.downcast::<SomeException>() {
+                Ok(e) => {
+                    eprintln!("{}", e.display_exception());
+                    ExitCode::FAILURE
+                }
+                Err(payload) => resume_unwind(payload),
+            },
+        }
     }
 }
 
@@ -229,52 +245,47 @@ pub fn print(t: impl Term + Show) -> IO<()> {
     IO::new(move || println!("{}", t.show()))
 }
 
-pub fn try_get_char() -> IO<Either<ErrorKind, char>> {
-    IO::new(|| {
-        let mut buf = [0; 1];
-        match std::io::stdin().read_exact(&mut buf) {
-            Ok(_) => Right(buf[0] as char),
-            Err(e) => Left(e.kind()),
-        }
-    })
+pub fn try_get_char() -> IO<Either<IOException, char>> {
+    handle::try_h_get_char(handle::stdin())
 }
 
+/// Read a single character from standard input.
+///
+/// Input is read through the [`stdin`](handle::stdin) handle,
+/// so it can be freely mixed with reads from that handle.
 pub fn get_char() -> IO<char> {
     try_get_char().chain_m(unwrap_either)
 }
 
-pub fn try_get_line() -> IO<Either<ErrorKind, String>> {
-    IO::new(|| {
-        let mut buf = String::new();
-        match std::io::stdin().read_line(&mut buf) {
-            Ok(_) => Right(buf),
-            Err(e) => Left(e.kind()),
-        }
-    })
+pub fn try_get_line() -> IO<Either<IOException, String>> {
+    handle::try_h_get_line(handle::stdin())
 }
 
+/// Read a line from standard input, without its trailing newline.
+///
+/// Input is read through the [`stdin`](handle::stdin) handle,
+/// so it can be freely mixed with reads from that handle.
 pub fn get_line() -> IO<String> {
     try_get_line().chain_m(unwrap_either)
 }
 
-pub fn try_get_contents() -> IO<Either<ErrorKind, String>> {
-    IO::new(|| {
-        let mut buf = String::new();
-        match std::io::stdin().read_to_string(&mut buf) {
-            Ok(_) => Right(buf),
-            Err(e) => Left(e.kind()),
-        }
-    })
+pub fn try_get_contents() -> IO<Either<IOException, String>> {
+    handle::try_h_get_contents(handle::stdin())
 }
 
+/// Read the remaining input from standard input.
+///
+/// Input is read through the [`stdin`](handle::stdin) handle,
+/// so it can be freely mixed with reads from that handle.
 pub fn get_contents() -> IO<String> {
     try_get_contents().chain_m(unwrap_either)
 }
 
-pub fn try_read_file(path: impl Term + AsRef<Path>) -> IO<Either<ErrorKind, String>> {
-    IO::new(move || match std::fs::read_to_string(path) {
-        Ok(t) => Right(t),
-        Err(e) => Left(e.kind()),
+pub fn try_read_file(path: impl Term + AsRef<Path>) -> IO<Either<IOException, String>> {
+    IO::new(move || {
+        std::fs::read_to_string(&path)
+            .map_err(IOException::on_path("read_file", path))
+            .into()
     })
 }
 
@@ -285,8 +296,12 @@ pub fn read_file(path: impl Term + AsRef<Path>) -> IO<String> {
 pub fn try_write_file(
     path: impl Term + AsRef<Path>,
     string: impl Term + AsRef<[u8]>,
-) -> IO<Either<ErrorKind, ()>> {
-    IO::new(move || std::fs::write(path, string).map_err(|e| e.kind()).into())
+) -> IO<Either<IOException, ()>> {
+    IO::new(move || {
+        std::fs::write(&path, string)
+            .map_err(IOException::on_path("write_file", path))
+            .into()
+    })
 }
 
 pub fn write_file(path: impl Term + AsRef<Path>, string: impl Term + AsRef<[u8]>) -> IO<()> {
@@ -296,14 +311,14 @@ pub fn write_file(path: impl Term + AsRef<Path>, string: impl Term + AsRef<[u8]>
 pub fn try_append_file(
     path: impl Term + AsRef<Path>,
     string: impl Term + AsRef<[u8]>,
-) -> IO<Either<ErrorKind, ()>> {
+) -> IO<Either<IOException, ()>> {
     IO::new(move || {
         std::fs::OpenOptions::new()
             .write(true)
             .append(true)
-            .open(path)
+            .open(&path)
             .and_then(|mut file| file.write_all(string.as_ref()))
-            .map_err(|e| e.kind())
+            .map_err(IOException::on_path("append_file", path))
             .into()
     })
 }
@@ -0,0 +1,359 @@
+//! Running external processes.
+//!
+//! Based on [System.Process](https://hackage.haskell.org/package/process/docs/System-Process.html)
+//! and [System.Exit](https://hackage.haskell.org/package/base/docs/System-Exit.html).
+//!
+//! Failures to start or communicate with a process are reported as [`IOException`]s,
+//! while processes that run but exit unsuccessfully are reported as [`ProcessException::Failed`].
+//!
+//! ```
+//! # use glasgae::{base::grl::io::process::*, prelude::*};
+//! # #[cfg(unix)]
+//! # {
+//! let io = read_process("tr".to_string(), vec!["a-z".to_string(), "A-Z".to_string()], "shout".to_string());
+//!
+//! assert_eq!(unsafe { io.run() }, "SHOUT");
+//! # }
+//! ```
+
+use std::{
+    io::{ErrorKind, Write},
+    panic::resume_unwind,
+    process::{Child, Command, ExitStatus, Output, Stdio},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use crate::{
+    base::control::exception::{Exception, IOException},
+    prelude::*,
+};
+
+use ExitCode::*;
+
+/// The exit status of a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ExitCode {
+    /// The process exited with code zero.
+    ExitSuccess,
+    /// The process exited with a non-zero code.
+    ///
+    /// Processes killed by a signal are given the negated signal number.
+    ExitFailure(i32),
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        match status.code() {
+            Some(0) => ExitSuccess,
+            Some(code) => ExitFailure(code),
+            None => ExitFailure(signal(status)),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn signal(status: ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal().map_or(-1, |signal| -signal)
+}
+
+#[cfg(not(unix))]
+fn signal(_: ExitStatus) -> i32 {
+    -1
+}
+
+impl Show for ExitCode {
+    fn show(self) -> String {
+        format!("{self:?}")
+    }
+}
+
+/// Thrown when an external process cannot be run to successful completion.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProcessException {
+    /// The process could not be started or communicated with.
+    IOFailure(IOException),
+    /// The process ran, but exited with a failure code.
+    Failed {
+        command: String,
+        args: Vec<String>,
+        exit_code: ExitCode,
+    },
+}
+
+impl Show for ProcessException {
+    fn show(self) -> String {
+        match self {
+            ProcessException::IOFailure(e) => e.show(),
+            ProcessException::Failed {
+                command,
+                args,
+                exit_code,
+            } => {
+                let code = match exit_code {
+                    ExitSuccess => 0,
+                    ExitFailure(code) => code,
+                };
+                format!("{command} {args:?} (exit {code}): failed")
+            }
+        }
+    }
+}
+
+impl Exception for ProcessException {}
+
+impl From<IOException> for ProcessException {
+    fn from(e: IOException) -> Self {
+        ProcessException::IOFailure(e)
+    }
+}
+
+fn command(command: &str, args: &[String]) -> Command {
+    let mut cmd = Command::new(command);
+    cmd.args(args);
+    cmd
+}
+
+/// Run `command` to completion, writing `stdin` to it and capturing its standard output.
+fn run_with_input(command: &mut Command, stdin: String) -> std::io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    // Feed stdin from another thread, so that a process producing
+    // a lot of output cannot deadlock against us.
+    let mut pipe = child.stdin.take().expect("stdin is piped");
+    let writer = std::thread::spawn(move || pipe.write_all(stdin.as_bytes()));
+    let output = child.wait_with_output()?;
+
+    match writer.join() {
+        // The process is free to exit without reading all of its input.
+        Ok(Err(e)) if e.kind() != ErrorKind::BrokenPipe => Err(e),
+        Err(payload) => resume_unwind(payload),
+        _ => Ok(output),
+    }
+}
+
+pub fn try_read_process_with_exit_code(
+    cmd: String,
+    args: Vec<String>,
+    stdin: String,
+) -> IO<Either<IOException, (ExitCode, String, String)>> {
+    IO::new(move || {
+        run_with_input(command(&cmd, &args).stderr(Stdio::piped()), stdin)
+            .map(|output| {
+                (
+                    output.status.into(),
+                    String::from_utf8_lossy(&output.stdout).into_owned(),
+                    String::from_utf8_lossy(&output.stderr).into_owned(),
+                )
+            })
+            .map_err(IOException::on_path("read_process_with_exit_code", &cmd))
+            .into()
+    })
+}
+
+/// Run a process to completion, passing it `stdin`,
+/// and return its exit code along with its captured standard output and standard error.
+pub fn read_process_with_exit_code(
+    cmd: String,
+    args: Vec<String>,
+    stdin: String,
+) -> IO<(ExitCode, String, String)> {
+    try_read_process_with_exit_code(cmd, args, stdin).chain_m(unwrap_either)
+}
+
+pub fn try_read_process(
+    cmd: String,
+    args: Vec<String>,
+    stdin: String,
+) -> IO<Either<ProcessException, String>> {
+    IO::new(move || {
+        let output = run_with_input(command(&cmd, &args).stderr(Stdio::inherit()), stdin)
+            .map_err(IOException::on_path("read_process", &cmd));
+
+        match output {
+            Err(e) => Left(e.into()),
+            Ok(output) => match ExitCode::from(output.status) {
+                ExitSuccess => Right(String::from_utf8_lossy(&output.stdout).into_owned()),
+                exit_code => Left(ProcessException::Failed {
+                    command: cmd,
+                    args,
+                    exit_code,
+                }),
+            },
+        }
+    })
+}
+
+/// Run a process to completion, passing it `stdin`, and return its standard output.
+///
+/// Standard error is inherited from the current process.
+/// Throws a [`ProcessException`] if the process exits unsuccessfully.
+pub fn read_process(cmd: String, args: Vec<String>, stdin: String) -> IO<String> {
+    try_read_process(cmd, args, stdin).chain_m(unwrap_either)
+}
+
+pub fn try_call_process(cmd: String, args: Vec<String>) -> IO<Either<ProcessException, ()>> {
+    IO::new(move || {
+        match command(&cmd, &args)
+            .status()
+            .map_err(IOException::on_path("call_process", &cmd))
+        {
+            Err(e) => Left(e.into()),
+            Ok(status) => match ExitCode::from(status) {
+                ExitSuccess => Right(()),
+                exit_code => Left(ProcessException::Failed {
+                    command: cmd,
+                    args,
+                    exit_code,
+                }),
+            },
+        }
+    })
+}
+
+/// Run a process to completion, inheriting the standard streams of the current process.
+///
+/// Throws a [`ProcessException`] if the process exits unsuccessfully.
+pub fn call_process(cmd: String, args: Vec<String>) -> IO<()> {
+    try_call_process(cmd, args).chain_m(unwrap_either)
+}
+
+struct ProcessState {
+    child: Child,
+    exit_code: Option<ExitCode>,
+}
+
+/// A running process, as started by [`spawn_process`].
+#[derive(Clone)]
+pub struct ProcessHandle {
+    command: Arc<str>,
+    state: Arc<Mutex<ProcessState>>,
+}
+
+impl ProcessHandle {
+    /// The operating system identifier of the process.
+    pub fn id(&self) -> u32 {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .child
+            .id()
+    }
+}
+
+impl std::fmt::Debug for ProcessHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessHandle")
+            .field("command", &self.command)
+            .field("id", &self.id())
+            .finish()
+    }
+}
+
+pub fn try_spawn_process(cmd: String, args: Vec<String>) -> IO<Either<IOException, ProcessHandle>> {
+    IO::new(move || {
+        command(&cmd, &args)
+            .spawn()
+            .map(|child| ProcessHandle {
+                command: cmd.as_str().into(),
+                state: Arc::new(Mutex::new(ProcessState {
+                    child,
+                    exit_code: None,
+                })),
+            })
+            .map_err(IOException::on_path("spawn_process", &cmd))
+            .into()
+    })
+}
+
+/// Start a process in the background, inheriting the standard streams of the current process.
+pub fn spawn_process(cmd: String, args: Vec<String>) -> IO<ProcessHandle> {
+    try_spawn_process(cmd, args).chain_m(unwrap_either)
+}
+
+pub fn try_wait_for_process(ph: ProcessHandle) -> IO<Either<IOException, ExitCode>> {
+    IO::new(move || {
+        let mut state = ph.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.exit_code {
+            Some(exit_code) => Right(exit_code),
+            None => match state.child.wait() {
+                Ok(status) => {
+                    let exit_code = ExitCode::from(status);
+                    state.exit_code = Some(exit_code);
+                    Right(exit_code)
+                }
+                Err(e) => Left(IOException::on_path("wait_for_process", &*ph.command)(e)),
+            },
+        }
+    })
+}
+
+/// Wait for a process to exit, and return its exit code.
+///
+/// Waiting on a process that has already exited returns the same exit code again.
+pub fn wait_for_process(ph: ProcessHandle) -> IO<ExitCode> {
+    try_wait_for_process(ph).chain_m(unwrap_either)
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use crate::base::control::exception::catch;
+
+    use super::*;
+
+    fn sh(script: &str) -> (String, Vec<String>) {
+        ("sh".to_string(), vec!["-c".to_string(), script.to_string()])
+    }
+
+    #[test]
+    fn test_read_process() {
+        let (cmd, args) = sh("cat; echo err >&2; exit 3");
+        let io = read_process_with_exit_code(cmd, args, "input".to_string());
+        assert_eq!(
+            unsafe { io.run() },
+            (ExitFailure(3), "input".to_string(), "err\n".to_string())
+        );
+
+        let (cmd, args) = sh("exit 2");
+        assert_eq!(
+            unsafe { try_read_process(cmd.clone(), args.clone(), String::new()).run() },
+            Left(ProcessException::Failed {
+                command: cmd,
+                args,
+                exit_code: ExitFailure(2)
+            })
+        );
+
+        let missing = "glasgae-no-such-command".to_string();
+        match unsafe { try_call_process(missing.clone(), vec![]).run() } {
+            Left(ProcessException::IOFailure(e)) => {
+                assert_eq!(e.kind, ErrorKind::NotFound);
+                assert_eq!(e.path, Some(missing.into()));
+            }
+            other => panic!("expected an IOFailure, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_spawn_process() {
+        let (cmd, args) = sh("exit 0");
+        let io = spawn_process(cmd, args).chain_m(|ph| {
+            wait_for_process(ph.clone())
+                .chain_m(move |first| wait_for_process(ph).fmap(move |second| (first, second)))
+        });
+        assert_eq!(unsafe { io.run() }, (ExitSuccess, ExitSuccess));
+
+        let (cmd, args) = sh("kill -9 $$");
+        let io = call_process(cmd, args).fmap(|_| ExitSuccess);
+        let io = catch(io, |e: ProcessException| {
+            IO::return_m(match e {
+                ProcessException::Failed { exit_code, .. } => exit_code,
+                ProcessException::IOFailure(e) => panic!("{}", e.show()),
+            })
+        });
+        assert_eq!(unsafe { io.run() }, ExitFailure(-9));
+    }
+}
@@ -51,6 +51,9 @@
 //! [`mtl`] contains interfaces generalizing over monad transformers,
 //! smoothing over the process of composing large monad stacks.
 
+// Allow derive macros to refer to this crate as `glasgae` from within it
+extern crate self as glasgae;
+
 pub mod base;
 pub mod logger;
 pub mod mtl;
@@ -66,6 +69,3 @@ pub use log;
 
 #[cfg(feature = "log")]
 pub use env_logger;
-
-// Prototyping
-pub mod zipper_cont;
@@ -28,7 +28,12 @@ use crate::{
     base::control::monad::morph::MonadLower,
     prelude::*,
     transformers::{
-        class::MonadTrans, cont::ContT, reader::ReaderT, state::StateT, writer::WriterT,
+        class::MonadTrans,
+        cont::ContT,
+        free::{FreeF, FreeT, FreeTLayerT},
+        reader::ReaderT,
+        state::StateT,
+        writer::WriterT,
     },
 };
 
@@ -233,6 +238,51 @@ where
     }
 }
 
+// FreeT impl
+impl<MR, F, M, R, A> MonadAsk<MR, R, A> for FreeT<F, M, A>
+where
+    Self: MonadTrans<WithPointedT<M, A>>,
+    F: Term,
+    M: WithPointed<A>,
+    WithPointedT<M, A>: MonadAsk<MR, R, A>,
+    A: Term,
+{
+    fn ask() -> Self {
+        Self::lift(MonadAsk::ask())
+    }
+}
+
+impl<MR, F, M, R, A> MonadLocal<MR, R> for FreeT<F, M, A>
+where
+    F: Term + WithPointed<FreeT<F, M, A>>,
+    M: WithPointed<FreeF<F, A, FreeT<F, M, A>>>,
+    FreeTLayerT<F, M, A>: MonadLocal<MR, R>
+        + Functor<FreeF<F, A, FreeT<F, M, A>>, Mapped = FreeTLayerT<F, M, A>>,
+    WithPointedT<F, FreeT<F, M, A>>:
+        Functor<FreeT<F, M, A>, Mapped = WithPointedT<F, FreeT<F, M, A>>>,
+    R: Term,
+    A: Term,
+{
+    fn local(self, f: impl FunctionT<R, R>) -> Self {
+        let f = f.to_function();
+        self.hoist_free_t(|m: FreeTLayerT<F, M, A>| m.local(f))
+    }
+}
+
+impl<F, M, R, A> MonadReader<R, A> for FreeT<F, M, A>
+where
+    Self: MonadTrans<WithPointedT<M, A>>,
+    F: Term,
+    M: WithPointed<A>,
+    WithPointedT<M, A>: MonadReader<R, A>,
+    R: Term,
+    A: Term,
+{
+    fn reader(f: impl FunctionT<R, A>) -> Self {
+        Self::lift(MonadReader::reader(f))
+    }
+}
+
 // Support functions
 pub trait Asks<R, A>: Sized + MonadReader<R, A>
 where
@@ -5,9 +5,11 @@
 //! Mark P Jones (<http://web.cecs.pdx.edu/~mpj/>) Advanced School of Functional Programming, 1995.
 
 use crate::{
+    base::data::optics::{Over, View},
     prelude::*,
     transformers::{
-        class::MonadTrans, cont::ContT, reader::ReaderT, state::StateT, writer::WriterT,
+        class::MonadTrans, cont::ContT, free::FreeT, reader::ReaderT, state::StateT,
+        writer::WriterT,
     },
 };
 
@@ -200,6 +202,55 @@ where
     }
 }
 
+// FreeT impl
+impl<F, M, A> StateTypes for FreeT<F, M, A>
+where
+    F: Term,
+    M: StateTypes,
+    A: Term,
+{
+    type State = M::State;
+}
+
+impl<F, M, A> MonadGet for FreeT<F, M, A>
+where
+    Self: MonadTrans<WithPointedT<M, A>>,
+    F: Term,
+    M: StateTypes + WithPointed<A>,
+    WithPointedT<M, A>: MonadGet,
+    A: Term,
+{
+    fn get() -> Self {
+        Self::lift(MonadGet::get())
+    }
+}
+
+impl<F, M, A> MonadPut for FreeT<F, M, A>
+where
+    Self: MonadTrans<WithPointedT<M, A>>,
+    F: Term,
+    M: StateTypes + WithPointed<A>,
+    WithPointedT<M, A>: MonadPut<State = M::State>,
+    A: Term,
+{
+    fn put(s: Self::State) -> Self {
+        Self::lift(MonadPut::put(s))
+    }
+}
+
+impl<F, M, A> MonadState for FreeT<F, M, A>
+where
+    Self: MonadTrans<WithPointedT<M, A>>,
+    F: Term,
+    M: StateTypes + WithPointed<A>,
+    WithPointedT<M, A>: MonadState<State = M::State, Pointed = A>,
+    A: Term,
+{
+    fn state(f: impl FunctionT<Self::State, (A, Self::State)>) -> Self {
+        Self::lift(MonadState::state(f))
+    }
+}
+
 // Support functions
 pub trait Modify<S, MA>: Term
 where
@@ -251,3 +302,48 @@ where
         StateT::<S, S>::get().chain_m(|s| ReturnM::return_m(f(s)))
     }
 }
+
+/// Use the target of an optic within the current state.
+///
+/// ```text
+/// use_(l) == gets(|s| l.view(s))
+/// ```
+pub fn use_<M, S, A>(l: impl View<S, A>) -> M
+where
+    M: MonadState<State = S, Pointed = A>,
+    S: Term,
+    A: Term,
+{
+    M::state(move |s: S| (l.view(s.clone()), s))
+}
+
+/// Replace the target of an optic within the current state.
+///
+/// ```text
+/// assign(l, b) == modify(|s| l.set(b, s))
+/// ```
+pub fn assign<M, S, A, B>(l: impl Over<S, S, A, B>, b: B) -> M
+where
+    M: MonadState<State = S, Pointed = ()>,
+    S: Term,
+    A: Term,
+    B: Term,
+{
+    M::state(move |s: S| ((), l.set(b, s)))
+}
+
+/// Modify the target of an optic within the current state.
+///
+/// ```text
+/// modifying(l, f) == modify(|s| l.over(f, s))
+/// ```
+pub fn modifying<M, S, A, B>(l: impl Over<S, S, A, B>, f: impl FunctionT<A, B>) -> M
+where
+    M: MonadState<State = S, Pointed = ()>,
+    S: Term,
+    A: Term,
+    B: Term,
+{
+    let f = f.to_function();
+    M::state(move |s: S| ((), l.over(f, s)))
+}
@@ -5,7 +5,7 @@ pub use std::convert::identity;
 pub use crate::{
     base::{
         control::{
-            applicative::*,
+            applicative::{AppA, Applicative, LiftA2, PureA},
             monad::{io::*, morph::*, *},
         },
         data::{
@@ -224,7 +224,7 @@ derive_with_pointed_via!(ContT<MR: Pointed, (MA)>);
 
 impl<MR, MA, A> Functor<A> for ContT<MR, MA>
 where
-    MA: Pointed<Pointed = A> + WithPointed<A>,
+    MA: Pointed + WithPointed<A>,
     MR: Pointed,
     A: Term,
 {
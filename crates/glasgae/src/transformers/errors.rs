@@ -10,7 +10,7 @@ use super::Lift;
 /// A sequence of computations fails if any of its components do,
 /// but unlike monads made with [`ExceptT`](crate::transformers::except::ExceptT) from [`except`](crate::transformers::except),
 /// these computations continue after an error, collecting all the errors.
-pub type Errors<E> = Lift<Const<E>>;
+pub type Errors<E> = Lift<Const<E, <E as Pointed>::Pointed>>;
 
 impl<E> Errors<E>
 where
@@ -19,12 +19,12 @@ where
     pub fn run(self) -> Either<E, E::Pointed> {
         match self {
             Lift::Pure(x) => Either::Right(x),
-            Lift::Other(Const(e)) => Either::Left(e),
+            Lift::Other(e) => Either::Left(e.get()),
         }
     }
 
     pub fn failure(e: E) -> Self {
-        Lift::Other(Const(e))
+        Lift::Other(Const::new(e))
     }
 }
 
@@ -4,7 +4,13 @@
 //!
 //! If the value of the exception is not required, the variant in Control.Monad.Trans.Maybe may be used instead.
 
-use crate::prelude::*;
+use crate::{
+    base::control::exception::{
+        ExitCase::{self, *},
+        MaskingState, MonadBracket, MonadMask, Restore,
+    },
+    prelude::*,
+};
 
 use super::class::MonadTrans;
 
@@ -420,6 +426,51 @@ where
     }
 }
 
+impl<MA> MonadMask for ExceptT<MA>
+where
+    MA: MonadMask,
+{
+    fn mask_with(state: MaskingState, f: impl FunctionT<Restore, Self>) -> Self {
+        let f = f.to_function();
+        ExceptT::new_t(MA::mask_with(state, |restore| f(restore).run_t()))
+    }
+
+    fn with_masking_state(self, state: MaskingState) -> Self {
+        ExceptT::new_t(self.run_t().with_masking_state(state))
+    }
+}
+
+/// A [`Left`] result counts as a failure, and is passed to `cleanup` as [`ExitCaseAbort`].
+impl<MA, MC, E, A, C> MonadBracket<ExceptT<MC>> for ExceptT<MA>
+where
+    MA: MonadBracket<MC> + Monad<Either<E, A>, Pointed = Either<E, A>, Chained = MA>,
+    MC: Term + ChainM<Either<E, A>, Pointed = Either<E, C>, Chained = MA>,
+    E: Term,
+    A: Term,
+    C: Term,
+{
+    fn on_failure(self, cleanup: impl FunctionT<ExitCase<A>, ExceptT<MC>>) -> Self {
+        let cleanup = cleanup.to_function();
+        ExceptT::new_t(
+            self.run_t()
+                .on_failure({
+                    let cleanup = cleanup.clone();
+                    |exit_case: ExitCase<Either<E, A>>| match exit_case {
+                        ExitCaseSuccess(_) => unreachable!("on_failure cleanup on success"),
+                        ExitCaseException => cleanup(ExitCaseException).run_t(),
+                        ExitCaseAbort => cleanup(ExitCaseAbort).run_t(),
+                    }
+                })
+                .chain_m(|a| match a {
+                    Left(e) => cleanup(ExitCaseAbort)
+                        .run_t()
+                        .chain_m(|_| ReturnM::return_m(Left(e))),
+                    Right(a) => ReturnM::return_m(Right(a)),
+                }),
+        )
+    }
+}
+
 trait LowerEither<E, A>: Pointed<Pointed = Either<E, A>> + WithPointed<A> {
     type Lowered: Pointed<Pointed = A>;
 }
@@ -0,0 +1,534 @@
+//! The free monad transformer.
+//!
+//! [`FreeT<F, M, A>`] interleaves the instructions of a functor `F`
+//! with the effects of an inner monad `M`,
+//! making it suitable for coroutines, generators and iteratees
+//! whose steps need access to an underlying monad.
+//!
+//! As with [`Free`](crate::base::control::monad::free::Free),
+//! `F` is a concrete instantiation of the instruction functor (conventionally `Instr<()>`),
+//! and `M` is a concrete instantiation of the inner monad (conventionally `M<()>`).
+
+use std::marker::PhantomData;
+
+use crate::{base::control::monad::io::MonadIO, prelude::*};
+
+use super::class::MonadTrans;
+
+/// The base functor for a free monad:
+/// either a [`Pure`](FreeF::Pure) result, or a [`Free`](FreeF::Free) layer of `F`.
+#[derive(Clone)]
+pub enum FreeF<F, A, B>
+where
+    F: WithPointed<B>,
+    A: Term,
+    B: Term,
+{
+    Pure(A),
+    Free(WithPointedT<F, B>),
+}
+
+impl<F, A, B> Pointed for FreeF<F, A, B>
+where
+    F: WithPointed<B>,
+    A: Term,
+    B: Term,
+{
+    type Pointed = B;
+}
+
+impl<F, A, B, C> WithPointed<C> for FreeF<F, A, B>
+where
+    F: WithPointed<B> + WithPointed<C>,
+    A: Term,
+    B: Term,
+    C: Term,
+{
+    type WithPointed = FreeF<F, A, C>;
+}
+
+impl<F, A, B, C> Functor<C> for FreeF<F, A, B>
+where
+    F: WithPointed<B> + WithPointed<C>,
+    WithPointedT<F, B>: Functor<C, Mapped = WithPointedT<F, C>>,
+    WithPointedT<F, C>: Functor<B, Mapped = WithPointedT<F, B>>,
+    A: Term,
+    B: Term,
+    C: Term,
+{
+    type Mapped = FreeF<F, A, C>;
+
+    fn fmap(self, f: impl FunctionT<B, C>) -> FreeF<F, A, C> {
+        match self {
+            FreeF::Pure(a) => FreeF::Pure(a),
+            FreeF::Free(fb) => FreeF::Free(fb.fmap(f)),
+        }
+    }
+}
+
+/// The `M`-shaped layer of a [`FreeT`].
+pub type FreeTLayerT<F, M, A> = WithPointedT<M, FreeF<F, A, FreeT<F, M, A>>>;
+
+/// The free monad transformer over the functor `F` and the monad `M`.
+///
+/// Each step of the program runs an `M` action,
+/// which yields either a result or a layer of `F` containing further programs.
+pub struct FreeT<F, M, A>(Erased, PhantomData<fn(F, M) -> A>);
+
+impl<F, M, A> Clone for FreeT<F, M, A> {
+    fn clone(&self) -> Self {
+        FreeT(self.0.clone(), PhantomData)
+    }
+}
+
+impl<F, M, A> FreeT<F, M, A>
+where
+    F: Term + WithPointed<FreeT<F, M, A>>,
+    M: WithPointed<FreeF<F, A, FreeT<F, M, A>>>,
+    A: Term,
+{
+    pub fn new_t(m: FreeTLayerT<F, M, A>) -> Self {
+        FreeT(m.boxed(), PhantomData)
+    }
+
+    pub fn run_t(self) -> FreeTLayerT<F, M, A> {
+        self.0.downcast()
+    }
+
+    /// A version of lift that can be used with just a [`Functor`] for `F`.
+    pub fn lift_f<FA>(fa: FA) -> Self
+    where
+        FA: Functor<FreeT<F, M, A>, Pointed = A, Mapped = WithPointedT<F, FreeT<F, M, A>>>,
+        Self: ReturnM<Pointed = A>,
+        FreeTLayerT<F, M, A>: ReturnM,
+    {
+        FreeT::new_t(ReturnM::return_m(FreeF::Free(fa.fmap(ReturnM::return_m))))
+    }
+
+    /// Tear down a free monad transformer using iteration.
+    ///
+    /// ```text
+    /// iter_t(phi)(m) == m.run_t().chain_m(|v| match v {
+    ///     Pure(a) => return_m(a),
+    ///     Free(fs) => phi(fs.fmap(iter_t(phi))),
+    /// })
+    /// ```
+    pub fn iter_t<MA>(self, phi: impl FunctionT<WithPointedT<F, MA>, MA>) -> MA
+    where
+        F: WithPointed<MA>,
+        WithPointedT<F, FreeT<F, M, A>>: Functor<MA, Mapped = WithPointedT<F, MA>>,
+        FreeTLayerT<F, M, A>: ChainM<A, Chained = MA>,
+        MA: ReturnM<Pointed = A>,
+    {
+        let phi = phi.to_function();
+        self.run_t().chain_m(|v| match v {
+            FreeF::Pure(a) => ReturnM::return_m(a),
+            FreeF::Free(fs) => phi.clone()(fs.fmap(|next: FreeT<F, M, A>| next.iter_t(phi))),
+        })
+    }
+
+    /// Tear down a free monad transformer using iteration over a transformed monad.
+    ///
+    /// The inner monad is [`lift`](MonadTrans::lift)ed into the transformer `TM`
+    /// before being iterated.
+    pub fn iter_t_m<TM>(self, phi: impl FunctionT<WithPointedT<F, TM>, TM>) -> TM
+    where
+        F: WithPointed<TM>,
+        WithPointedT<F, FreeT<F, M, A>>: Functor<TM, Mapped = WithPointedT<F, TM>>,
+        TM: ReturnM<Pointed = A> + WithPointed<FreeF<F, A, FreeT<F, M, A>>>,
+        WithPointedT<TM, FreeF<F, A, FreeT<F, M, A>>>:
+            MonadTrans<FreeTLayerT<F, M, A>> + ChainM<A, Chained = TM>,
+    {
+        let phi = phi.to_function();
+        <WithPointedT<TM, FreeF<F, A, FreeT<F, M, A>>>>::lift(self.run_t()).chain_m(|v| match v {
+            FreeF::Pure(a) => ReturnM::return_m(a),
+            FreeF::Free(fs) => phi.clone()(fs.fmap(|next: FreeT<F, M, A>| next.iter_t_m(phi))),
+        })
+    }
+
+    /// Lift a monad homomorphism from `M` to `N` into a monad homomorphism from `FreeT<F, M, _>` to `FreeT<F, N, _>`.
+    ///
+    /// As Rust lacks rank-2 types, the homomorphism is specialized
+    /// to the layer type it will be applied to.
+    pub fn hoist_free_t<N>(
+        self,
+        nt: impl FunctionT<WithPointedT<M, FreeF<F, A, FreeT<F, N, A>>>, FreeTLayerT<F, N, A>>,
+    ) -> FreeT<F, N, A>
+    where
+        F: WithPointed<FreeT<F, N, A>>,
+        M: WithPointed<FreeF<F, A, FreeT<F, N, A>>>,
+        N: WithPointed<FreeF<F, A, FreeT<F, N, A>>>,
+        FreeTLayerT<F, M, A>: Functor<
+            FreeF<F, A, FreeT<F, N, A>>,
+            Mapped = WithPointedT<M, FreeF<F, A, FreeT<F, N, A>>>,
+        >,
+        WithPointedT<F, FreeT<F, M, A>>:
+            Functor<FreeT<F, N, A>, Mapped = WithPointedT<F, FreeT<F, N, A>>>,
+    {
+        let nt = nt.to_function();
+        FreeT::new_t(nt.clone()(self.run_t().fmap(
+            |v: FreeF<F, A, FreeT<F, M, A>>| match v {
+                FreeF::Pure(a) => FreeF::Pure(a),
+                FreeF::Free(fs) => {
+                    FreeF::Free(fs.fmap(|next: FreeT<F, M, A>| next.hoist_free_t(nt)))
+                }
+            },
+        )))
+    }
+
+    /// Lift a natural transformation from `F` to `G` into a monad homomorphism from `FreeT<F, M, _>` to `FreeT<G, M, _>`.
+    ///
+    /// As with [`FreeT::hoist_free_t`], the transformation is specialized
+    /// to the layer type it will be applied to.
+    pub fn transform_free_t<G>(
+        self,
+        nt: impl FunctionT<WithPointedT<F, FreeT<G, M, A>>, WithPointedT<G, FreeT<G, M, A>>>,
+    ) -> FreeT<G, M, A>
+    where
+        F: WithPointed<FreeT<G, M, A>>,
+        G: Term + WithPointed<FreeT<G, M, A>>,
+        M: WithPointed<FreeF<G, A, FreeT<G, M, A>>>,
+        FreeTLayerT<F, M, A>: Functor<FreeF<G, A, FreeT<G, M, A>>, Mapped = FreeTLayerT<G, M, A>>,
+        WithPointedT<F, FreeT<F, M, A>>:
+            Functor<FreeT<G, M, A>, Mapped = WithPointedT<F, FreeT<G, M, A>>>,
+    {
+        let nt = nt.to_function();
+        FreeT::new_t(self.run_t().fmap(|v: FreeF<F, A, FreeT<F, M, A>>| match v {
+            FreeF::Pure(a) => FreeF::Pure(a),
+            FreeF::Free(fs) => FreeF::Free(nt.clone()(
+                fs.fmap(|next: FreeT<F, M, A>| next.transform_free_t(nt)),
+            )),
+        }))
+    }
+}
+
+impl<F, M, A> Pointed for FreeT<F, M, A>
+where
+    F: Term,
+    M: Term,
+    A: Term,
+{
+    type Pointed = A;
+}
+
+impl<F, M, A, B> WithPointed<B> for FreeT<F, M, A>
+where
+    F: Term,
+    M: Term,
+    A: Term,
+    B: Term,
+{
+    type WithPointed = FreeT<F, M, B>;
+}
+
+impl<F, M, A, B> Functor<B> for FreeT<F, M, A>
+where
+    F: Term + WithPointed<FreeT<F, M, A>> + WithPointed<FreeT<F, M, B>>,
+    M: WithPointed<FreeF<F, A, FreeT<F, M, A>>> + WithPointed<FreeF<F, B, FreeT<F, M, B>>>,
+    FreeTLayerT<F, M, A>: Functor<FreeF<F, B, FreeT<F, M, B>>, Mapped = FreeTLayerT<F, M, B>>,
+    FreeTLayerT<F, M, B>: Functor<FreeF<F, A, FreeT<F, M, A>>, Mapped = FreeTLayerT<F, M, A>>,
+    WithPointedT<F, FreeT<F, M, A>>:
+        Functor<FreeT<F, M, B>, Mapped = WithPointedT<F, FreeT<F, M, B>>>,
+    WithPointedT<F, FreeT<F, M, B>>:
+        Functor<FreeT<F, M, A>, Mapped = WithPointedT<F, FreeT<F, M, A>>>,
+    A: Term,
+    B: Term,
+{
+    type Mapped = FreeT<F, M, B>;
+
+    fn fmap(self, f: impl FunctionT<A, B>) -> FreeT<F, M, B> {
+        let f = f.to_function();
+        FreeT::new_t(self.run_t().fmap(|v: FreeF<F, A, FreeT<F, M, A>>| match v {
+            FreeF::Pure(a) => FreeF::Pure(f(a)),
+            FreeF::Free(fs) => FreeF::Free(fs.fmap(|next: FreeT<F, M, A>| next.fmap(f))),
+        }))
+    }
+}
+
+impl<F, M, A> PureA for FreeT<F, M, A>
+where
+    F: Term + WithPointed<FreeT<F, M, A>>,
+    M: WithPointed<FreeF<F, A, FreeT<F, M, A>>>,
+    FreeTLayerT<F, M, A>: ReturnM,
+    A: Term,
+{
+    fn pure_a(t: A) -> Self {
+        FreeT::new_t(ReturnM::return_m(FreeF::Pure(t)))
+    }
+}
+
+impl<F, M, G, A, B> AppA<A, B> for FreeT<F, M, G>
+where
+    F: Term
+        + WithPointed<FreeT<F, M, G>>
+        + WithPointed<FreeT<F, M, A>>
+        + WithPointed<FreeT<F, M, B>>,
+    M: WithPointed<FreeF<F, G, FreeT<F, M, G>>>
+        + WithPointed<FreeF<F, A, FreeT<F, M, A>>>
+        + WithPointed<FreeF<F, B, FreeT<F, M, B>>>,
+    FreeTLayerT<F, M, G>:
+        ChainM<FreeF<F, B, FreeT<F, M, B>>, Chained = FreeTLayerT<F, M, B>> + ReturnM,
+    FreeTLayerT<F, M, B>:
+        ChainM<FreeF<F, G, FreeT<F, M, G>>, Chained = FreeTLayerT<F, M, G>> + ReturnM,
+    FreeTLayerT<F, M, A>: Functor<FreeF<F, B, FreeT<F, M, B>>, Mapped = FreeTLayerT<F, M, B>>,
+    FreeTLayerT<F, M, B>: Functor<FreeF<F, A, FreeT<F, M, A>>, Mapped = FreeTLayerT<F, M, A>>,
+    WithPointedT<F, FreeT<F, M, G>>:
+        Functor<FreeT<F, M, B>, Mapped = WithPointedT<F, FreeT<F, M, B>>>,
+    WithPointedT<F, FreeT<F, M, B>>:
+        Functor<FreeT<F, M, G>, Mapped = WithPointedT<F, FreeT<F, M, G>>>,
+    WithPointedT<F, FreeT<F, M, A>>:
+        Functor<FreeT<F, M, B>, Mapped = WithPointedT<F, FreeT<F, M, B>>>,
+    WithPointedT<F, FreeT<F, M, B>>:
+        Functor<FreeT<F, M, A>, Mapped = WithPointedT<F, FreeT<F, M, A>>>,
+    G: Term + FunctionT<A, B>,
+    A: Term,
+    B: Term,
+{
+    type WithA = FreeT<F, M, A>;
+    type WithB = FreeT<F, M, B>;
+
+    fn app_a(self, a: FreeT<F, M, A>) -> FreeT<F, M, B> {
+        self.chain_m(|g| a.fmap(g))
+    }
+}
+
+impl<F, M, A> ReturnM for FreeT<F, M, A>
+where
+    F: Term + WithPointed<FreeT<F, M, A>>,
+    M: WithPointed<FreeF<F, A, FreeT<F, M, A>>>,
+    FreeTLayerT<F, M, A>: ReturnM,
+    A: Term,
+{
+}
+
+impl<F, M, A, B> ChainM<B> for FreeT<F, M, A>
+where
+    F: Term + WithPointed<FreeT<F, M, A>> + WithPointed<FreeT<F, M, B>>,
+    M: WithPointed<FreeF<F, A, FreeT<F, M, A>>> + WithPointed<FreeF<F, B, FreeT<F, M, B>>>,
+    FreeTLayerT<F, M, A>:
+        ChainM<FreeF<F, B, FreeT<F, M, B>>, Chained = FreeTLayerT<F, M, B>> + ReturnM,
+    FreeTLayerT<F, M, B>:
+        ChainM<FreeF<F, A, FreeT<F, M, A>>, Chained = FreeTLayerT<F, M, A>> + ReturnM,
+    WithPointedT<F, FreeT<F, M, A>>:
+        Functor<FreeT<F, M, B>, Mapped = WithPointedT<F, FreeT<F, M, B>>>,
+    WithPointedT<F, FreeT<F, M, B>>:
+        Functor<FreeT<F, M, A>, Mapped = WithPointedT<F, FreeT<F, M, A>>>,
+    A: Term,
+    B: Term,
+{
+    type Chained = FreeT<F, M, B>;
+
+    fn chain_m(self, f: impl FunctionT<A, FreeT<F, M, B>>) -> FreeT<F, M, B> {
+        let f = f.to_function();
+        FreeT::new_t(self.run_t().chain_m(|v| match v {
+            FreeF::Pure(a) => f(a).run_t(),
+            FreeF::Free(fs) => {
+                ReturnM::return_m(FreeF::Free(fs.fmap(|next: FreeT<F, M, A>| next.chain_m(f))))
+            }
+        }))
+    }
+}
+
+impl<F, M, MI, A> MonadTrans<MI> for FreeT<F, M, A>
+where
+    F: Term + WithPointed<FreeT<F, M, A>>,
+    M: WithPointed<FreeF<F, A, FreeT<F, M, A>>>,
+    MI: Functor<FreeF<F, A, FreeT<F, M, A>>, Pointed = A, Mapped = FreeTLayerT<F, M, A>>,
+    A: Term,
+{
+    fn lift(m: MI) -> Self {
+        FreeT::new_t(m.fmap(FreeF::Pure))
+    }
+}
+
+impl<F, M, A> MonadIO<A> for FreeT<F, M, A>
+where
+    Self: MonadTrans<WithPointedT<M, A>>,
+    F: Term,
+    M: WithPointed<A>,
+    WithPointedT<M, A>: MonadIO<A>,
+    A: Term,
+{
+    fn lift_io(m: IO<A>) -> Self {
+        Self::lift(MonadIO::lift_io(m))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        base::control::monad::io::MonadIO,
+        derive_pointed, derive_with_pointed,
+        mtl::{
+            reader::{MonadAsk, MonadLocal},
+            state::{MonadGet, MonadPut},
+        },
+        prelude::*,
+        transformers::{class::MonadTrans, reader::Reader, state::State, writer::Writer},
+    };
+
+    use super::{FreeF, FreeT};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Yield<N>(usize, N);
+
+    derive_pointed!(Yield<(N)>);
+    derive_with_pointed!(Yield<(N)>);
+
+    impl<N, M> Functor<M> for Yield<N>
+    where
+        N: Term,
+        M: Term,
+    {
+        type Mapped = Yield<M>;
+
+        fn fmap(self, f: impl FunctionT<N, M>) -> Yield<M> {
+            Yield(self.0, f(self.1))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Emit<N>(String, N);
+
+    derive_pointed!(Emit<(N)>);
+    derive_with_pointed!(Emit<(N)>);
+
+    impl<N, M> Functor<M> for Emit<N>
+    where
+        N: Term,
+        M: Term,
+    {
+        type Mapped = Emit<M>;
+
+        fn fmap(self, f: impl FunctionT<N, M>) -> Emit<M> {
+            Emit(self.0, f(self.1))
+        }
+    }
+
+    type Producer<A> = FreeT<Yield<()>, State<usize, ()>, A>;
+
+    fn yield_(o: usize) -> Producer<()> {
+        FreeT::lift_f(Yield(o, ()))
+    }
+
+    /// Count upward from the current state, yielding each value.
+    fn counter(n: usize) -> Producer<()> {
+        if n == 0 {
+            return ReturnM::return_m(());
+        }
+
+        Producer::<usize>::get()
+            .chain_m(|s| Producer::put(s + 1).then_m(yield_(s)))
+            .then_m(counter(n - 1))
+    }
+
+    /// Pull up to `n` values from a producer, interleaving its state effects.
+    fn take(n: usize, p: Producer<()>) -> State<usize, Vec<usize>> {
+        if n == 0 {
+            return ReturnM::return_m(vec![]);
+        }
+
+        p.run_t().chain_m(move |v| match v {
+            FreeF::Pure(()) => ReturnM::return_m(vec![]),
+            FreeF::Free(Yield(o, next)) => take(n - 1, next).fmap(move |mut os: Vec<usize>| {
+                os.insert(0, o);
+                os
+            }),
+        })
+    }
+
+    #[test]
+    fn test_coroutine() {
+        let (out, s) = take(3, counter(5)).run(10);
+        assert_eq!(out, vec![10, 11, 12]);
+        assert_eq!(s, 13);
+
+        let (out, s) = take(10, counter(2)).run(0);
+        assert_eq!(out, vec![0, 1]);
+        assert_eq!(s, 2);
+    }
+
+    #[test]
+    fn test_iter_t() {
+        let out = counter(4)
+            .iter_t(|Yield(o, next): Yield<State<usize, ()>>| {
+                next.fmap(move |_| o).then_m(ReturnM::return_m(()))
+            })
+            .run(3);
+        assert_eq!(out, ((), 7));
+
+        let sum = counter(4)
+            .fmap(|_| 0)
+            .iter_t(|Yield(o, next): Yield<State<usize, usize>>| next.fmap(move |acc| acc + o))
+            .run(1);
+        assert_eq!(sum, (1 + 2 + 3 + 4, 5));
+    }
+
+    #[test]
+    fn test_transform_free_t() {
+        let emitter = counter(3).transform_free_t(
+            |Yield(o, next): Yield<FreeT<Emit<()>, State<usize, ()>, ()>>| {
+                Emit(format!("#{o}"), next)
+            },
+        );
+
+        let out = emitter
+            .fmap(|_| String::new())
+            .iter_t(|Emit(s, next): Emit<State<usize, String>>| next.fmap(move |t| s + &t))
+            .run(0);
+        assert_eq!(out, ("#0#1#2".to_string(), 3));
+    }
+
+    #[test]
+    fn test_iter_t_m() {
+        let out: Writer<Vec<usize>, ()> =
+            FreeT::<Yield<()>, Identity<()>, ()>::lift_f(Yield(1, ()))
+                .then_m(FreeT::lift_f(Yield(2, ())))
+                .iter_t_m(|Yield(o, next): Yield<Writer<Vec<usize>, ()>>| {
+                    Writer::tell(vec![o]).then_m(next)
+                });
+        assert_eq!(out.exec(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_hoist_free_t() {
+        let p = FreeT::<Yield<()>, Identity<()>, usize>::lift(Identity(3))
+            .chain_m(|n| FreeT::lift_f(Yield(n, ())).then_m(ReturnM::return_m(n * 2)));
+
+        let out: State<usize, usize> = p
+            .hoist_free_t::<State<usize, ()>>(|Identity(v)| State::new(move |s| (v, s)))
+            .iter_t(|Yield(o, next): Yield<State<usize, usize>>| {
+                State::<usize, ()>::modify(move |s| s + o).then_m(next)
+            });
+        assert_eq!(out.run(1), (6, 4));
+    }
+
+    #[test]
+    fn test_lift_io() {
+        let out = FreeT::<Yield<()>, IO<()>, usize>::lift_io(IO::new(|| 4))
+            .chain_m(|n| FreeT::lift_f(Yield(n, ())).then_m(ReturnM::return_m(n + 1)))
+            .iter_t(|Yield(o, next): Yield<IO<usize>>| next.fmap(move |n| n + o));
+        assert_eq!(unsafe { out.run() }, 9);
+    }
+
+    #[test]
+    fn test_reader() {
+        type P<A> = FreeT<Yield<()>, Reader<usize, ()>, A>;
+
+        let p = P::<usize>::ask()
+            .chain_m(|r| FreeT::lift_f(Yield(r, ())))
+            .then_m(P::<usize>::ask().local(|r| r * 10))
+            .chain_m(|r| FreeT::lift_f(Yield(r, ())));
+
+        let out = p
+            .fmap(|_| vec![])
+            .iter_t(|Yield(o, next): Yield<Reader<usize, Vec<usize>>>| {
+                next.map(move |mut os: Vec<usize>| {
+                    os.insert(0, o);
+                    os
+                })
+            })
+            .run(3);
+        assert_eq!(out, vec![3, 30]);
+    }
+}
@@ -3,6 +3,7 @@
 pub mod class;
 pub mod cont;
 pub mod except;
+pub mod free;
 pub mod identity;
 pub mod maybe;
 pub mod reader;
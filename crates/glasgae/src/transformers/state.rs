@@ -9,9 +9,12 @@
 
 use crate::{
     base::{
-        control::monad::{
-            io::MonadIO,
-            morph::{HoistTupleT, MonadLower},
+        control::{
+            exception::{ExitCase, MaskingState, MonadBracket, MonadMask, Restore},
+            monad::{
+                io::MonadIO,
+                morph::{HoistTupleT, MonadLower},
+            },
         },
         data::{functor::identity::Identity, tuple::pair::Pair},
     },
@@ -392,6 +395,41 @@ where
     }
 }
 
+impl<S, MA> MonadMask for StateT<S, MA>
+where
+    S: Term,
+    MA: MonadMask,
+{
+    fn mask_with(state: MaskingState, f: impl FunctionT<Restore, Self>) -> Self {
+        let f = f.to_function();
+        StateT::new_t(move |s| MA::mask_with(state, |restore| f(restore).run_t(s)))
+    }
+
+    fn with_masking_state(self, state: MaskingState) -> Self {
+        StateT::new_t(move |s| self.run_t(s).with_masking_state(state))
+    }
+}
+
+/// On failure, `cleanup` runs from the state the computation started in.
+impl<S, MA, A, MC, C> MonadBracket<StateT<S, MC>> for StateT<S, MA>
+where
+    S: Term,
+    MA: MonadBracket<MC> + Pointed<Pointed = (A, S)>,
+    MC: Term + Pointed<Pointed = (C, S)>,
+    A: Term,
+    C: Term,
+{
+    fn on_failure(self, cleanup: impl FunctionT<ExitCase<A>, StateT<S, MC>>) -> Self {
+        let cleanup = cleanup.to_function();
+        StateT::new_t(|s: S| {
+            self.run_t(s.clone())
+                .on_failure(|exit_case: ExitCase<(A, S)>| {
+                    cleanup(exit_case.fmap(|(a, _)| a)).run_t(s)
+                })
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]